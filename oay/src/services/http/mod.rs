@@ -36,9 +36,7 @@ use futures::AsyncWriteExt;
 use futures::StreamExt;
 use log::error;
 use log::warn;
-use opendal::io_util::into_stream;
 use opendal::ops::BytesRange;
-use opendal::BytesReader;
 use opendal::Operator;
 use percent_encoding::percent_decode;
 
@@ -79,7 +77,7 @@ impl Service {
 
         let meta = o.metadata().await?;
 
-        let (size, r) = if let Some(range) = req.headers().get(header::RANGE) {
+        let (size, s) = if let Some(range) = req.headers().get(header::RANGE) {
             let br = BytesRange::from_header_range(range.to_str().map_err(|e| {
                 Error::new(
                     ErrorKind::InvalidInput,
@@ -89,18 +87,12 @@ impl Service {
 
             let range = br.to_range(meta.content_length());
 
-            (
-                range.size_hint().0 as u64,
-                Box::new(o.range_reader(range).await?) as BytesReader,
-            )
+            (range.size_hint().0 as u64, o.range_stream(range).await?)
         } else {
-            (
-                meta.content_length(),
-                Box::new(o.reader().await?) as BytesReader,
-            )
+            (meta.content_length(), o.stream().await?)
         };
 
-        Ok(HttpResponse::Ok().body(SizedStream::new(size, into_stream(r, 8 * 1024))))
+        Ok(HttpResponse::Ok().body(SizedStream::new(size, s)))
     }
 
     async fn put(&self, req: HttpRequest, mut body: web::Payload) -> Result<HttpResponse> {
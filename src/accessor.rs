@@ -17,10 +17,17 @@ use std::io::Result;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use bytes::Bytes;
+use bytes::BytesMut;
 use flagset::flags;
 use flagset::FlagSet;
+use futures::stream;
+use futures::StreamExt;
+use futures::TryStreamExt;
 
 use crate::error::new_unsupported_object_error;
+use crate::io_util::into_reader;
+use crate::io_util::into_stream;
 use crate::ops::OpAbortMultipart;
 use crate::ops::OpCompleteMultipart;
 use crate::ops::OpCreate;
@@ -29,6 +36,7 @@ use crate::ops::OpDelete;
 use crate::ops::OpList;
 use crate::ops::OpPresign;
 use crate::ops::OpRead;
+use crate::ops::OpReadRanges;
 use crate::ops::OpStat;
 use crate::ops::OpWrite;
 use crate::ops::OpWriteMultipart;
@@ -36,6 +44,7 @@ use crate::ops::Operation;
 use crate::ops::PresignedRequest;
 use crate::BlockingBytesReader;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::ObjectIterator;
 use crate::ObjectMetadata;
 use crate::ObjectPart;
@@ -57,6 +66,9 @@ use crate::Scheme;
 /// | [`create`][crate::Accessor::create] | - |
 /// | [`read`][crate::Accessor::read] | - |
 /// | [`write`][crate::Accessor::write] | - |
+/// | [`read_stream`][crate::Accessor::read_stream] | - |
+/// | [`write_stream`][crate::Accessor::write_stream] | - |
+/// | [`read_ranges`][crate::Accessor::read_ranges] | - |
 /// | [`delete`][crate::Accessor::delete] | - |
 /// | [`list`][crate::Accessor::list] | - |
 /// | [`presign`][crate::Accessor::presign] | `Presign` |
@@ -144,6 +156,79 @@ pub trait Accessor: Send + Sync + Debug + 'static {
         }
     }
 
+    /// Invoke the `read` operation on the specified path, returns a
+    /// [`BytesStreamer`][crate::BytesStreamer] if operate successful.
+    ///
+    /// # Behavior
+    ///
+    /// - Input path MUST be file path, DON'T NEED to check object mode.
+    /// - The default implementation converts the reader returned by `read`.
+    ///   Services that receive data in chunks SHOULD implement this to pass
+    ///   chunks through without copy.
+    async fn read_stream(&self, path: &str, args: OpRead) -> Result<BytesStreamer> {
+        let r = self.read(path, args).await?;
+        Ok(Box::new(into_stream(r, 64 * 1024)))
+    }
+
+    /// Invoke the `write` operation on the specified path with a
+    /// [`BytesStreamer`][crate::BytesStreamer], returns a written size if
+    /// operate successful.
+    ///
+    /// # Behavior
+    ///
+    /// - Input path MUST be file path, DON'T NEED to check object mode.
+    /// - The default implementation converts the stream into a reader and
+    ///   calls `write`. Services that send data in chunks SHOULD implement
+    ///   this to pass chunks through without copy.
+    async fn write_stream(&self, path: &str, args: OpWrite, s: BytesStreamer) -> Result<u64> {
+        self.write(path, args, Box::new(into_reader(s))).await
+    }
+
+    /// Invoke the `read` operation on the specified path with multiple
+    /// ranges, returns the content of every range in the same order.
+    ///
+    /// # Behavior
+    ///
+    /// - Input path MUST be file path, DON'T NEED to check object mode.
+    /// - Content of range beyond the end of object will be truncated.
+    /// - The default implementation merges close ranges and sends
+    ///   `read_stream` concurrently. Services that store data in blocks
+    ///   SHOULD implement this to fetch every block only once.
+    async fn read_ranges(&self, path: &str, args: OpReadRanges) -> Result<Vec<Bytes>> {
+        let merged = args.merged_ranges();
+        let fetched: Vec<Bytes> = stream::iter(merged.iter().cloned())
+            .map(|r| async move {
+                let mut s = self.read_stream(path, OpRead::new(r)).await?;
+
+                let mut buf = BytesMut::new();
+                while let Some(bs) = s.try_next().await? {
+                    buf.extend_from_slice(&bs);
+                }
+                Ok::<_, std::io::Error>(buf.freeze())
+            })
+            .buffered(args.concurrency())
+            .try_collect()
+            .await?;
+
+        Ok(args
+            .ranges()
+            .iter()
+            .map(|r| {
+                if r.start >= r.end {
+                    return Bytes::new();
+                }
+
+                // Merged ranges are sorted, find the one that covers `r`.
+                let idx = merged.partition_point(|v| v.start <= r.start) - 1;
+                let bs = &fetched[idx];
+                let offset = (r.start - merged[idx].start) as usize;
+                let start = offset.min(bs.len());
+                let end = (offset + (r.end - r.start) as usize).min(bs.len());
+                bs.slice(start..end)
+            })
+            .collect())
+    }
+
     /// Invoke the `stat` operation on the specified path.
     ///
     /// # Behavior
@@ -372,6 +457,15 @@ impl<T: Accessor> Accessor for Arc<T> {
     async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<u64> {
         self.as_ref().write(path, args, r).await
     }
+    async fn read_stream(&self, path: &str, args: OpRead) -> Result<BytesStreamer> {
+        self.as_ref().read_stream(path, args).await
+    }
+    async fn write_stream(&self, path: &str, args: OpWrite, s: BytesStreamer) -> Result<u64> {
+        self.as_ref().write_stream(path, args, s).await
+    }
+    async fn read_ranges(&self, path: &str, args: OpReadRanges) -> Result<Vec<Bytes>> {
+        self.as_ref().read_ranges(path, args).await
+    }
     async fn stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
         self.as_ref().stat(path, args).await
     }
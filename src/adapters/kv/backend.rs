@@ -12,10 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::cmp::min;
+use std::collections::HashMap;
 use std::io::Error;
 use std::io::ErrorKind;
 use std::io::Result;
+use std::ops::Range;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::Context;
@@ -24,14 +25,15 @@ use std::vec::IntoIter;
 
 use anyhow::anyhow;
 use async_trait::async_trait;
-use bytes::Buf;
+use bytes::Bytes;
 use bytes::BytesMut;
 use futures::future::BoxFuture;
 use futures::ready;
-use futures::AsyncRead;
+use futures::stream;
 use futures::AsyncReadExt;
 use futures::Future;
 use futures::Stream;
+use futures::StreamExt;
 use futures::TryStreamExt;
 use pin_project::pin_project;
 use time::OffsetDateTime;
@@ -42,11 +44,13 @@ use super::Key;
 use super::KeyStreamer;
 use super::BLOCK_SIZE;
 use super::INODE_ROOT;
+use crate::io_util::into_reader;
 use crate::object::EmptyObjectStreamer;
 use crate::ops::OpCreate;
 use crate::ops::OpDelete;
 use crate::ops::OpList;
 use crate::ops::OpRead;
+use crate::ops::OpReadRanges;
 use crate::ops::OpStat;
 use crate::ops::OpWrite;
 use crate::path::build_rooted_abs_path;
@@ -57,6 +61,7 @@ use crate::Accessor;
 use crate::AccessorCapability;
 use crate::AccessorMetadata;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::ObjectEntry;
 use crate::ObjectMetadata;
 use crate::ObjectMode;
@@ -130,10 +135,26 @@ where
     }
 
     async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        let s = self.read_stream(path, args).await?;
+        Ok(Box::new(into_reader(s)))
+    }
+
+    async fn read_stream(&self, path: &str, args: OpRead) -> Result<BytesStreamer> {
         let p = build_rooted_abs_path(&self.root, path);
         let inode = self.lookup(&p).await?;
         let meta = self.get_inode(inode).await?;
 
+        // Range beyond the end of object will be truncated.
+        let total = meta.content_length();
+        let (offset, size) = match (args.offset(), args.size()) {
+            (Some(offset), size) => {
+                let offset = offset.min(total);
+                (offset, size.unwrap_or(total).min(total - offset))
+            }
+            (None, Some(size)) => (total - size.min(total), size.min(total)),
+            (None, None) => (0, total),
+        };
+
         // kv can't list means it will only have one block.
         if !self.can_list() {
             let key = Key::block(inode, 0);
@@ -144,23 +165,70 @@ where
                     anyhow!("entry inode: {inode} is not found"),
                 )),
                 Some(mut buf) => {
-                    if let Some(v) = args.offset() {
-                        buf = buf.split_off(v as usize);
-                    }
-                    if let Some(v) = args.size() {
-                        let _ = buf.split_off(v as usize);
-                    }
-                    Ok(Box::new(futures::io::Cursor::new(buf)))
+                    buf = buf.split_off(offset as usize);
+                    let _ = buf.split_off(size as usize);
+                    Ok(Box::new(stream::iter(vec![Ok(Bytes::from(buf))])))
                 }
             };
         }
 
-        let blocks = calculate_blocks(
-            args.offset().unwrap_or_default(),
-            args.size().unwrap_or_else(|| meta.content_length()),
-        );
-        let r = BlockReader::new(self.clone(), inode, blocks);
-        Ok(Box::new(r))
+        if size == 0 {
+            return Ok(Box::new(stream::empty()));
+        }
+        let blocks = calculate_blocks(offset, size);
+        let s = BlockStream::new(self.clone(), inode, blocks);
+        Ok(Box::new(s))
+    }
+
+    async fn read_ranges(&self, path: &str, args: OpReadRanges) -> Result<Vec<Bytes>> {
+        let p = build_rooted_abs_path(&self.root, path);
+        let inode = self.lookup(&p).await?;
+        let meta = self.get_inode(inode).await?;
+        let total = meta.content_length();
+
+        // Map every range onto blocks, ranges beyond the end of object
+        // will be truncated.
+        let plans: Vec<Vec<(u64, usize, usize)>> = args
+            .ranges()
+            .iter()
+            .map(|r| {
+                let r: Range<u64> = r.start.min(total)..r.end.min(total);
+                if r.start >= r.end {
+                    vec![]
+                } else if !self.can_list() {
+                    // kv can't list means it will only have one block.
+                    vec![(0, r.start as usize, (r.end - r.start) as usize)]
+                } else {
+                    calculate_blocks(r.start, r.end - r.start)
+                }
+            })
+            .collect();
+
+        // Every block will be fetched only once even if it's covered by
+        // many ranges.
+        let mut ids: Vec<u64> = plans.iter().flatten().map(|(b, _, _)| *b).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        let blocks: HashMap<u64, Bytes> = stream::iter(ids)
+            .map(|b| async move { self.get_block(inode, b).await.map(|bs| (b, bs)) })
+            .buffer_unordered(args.concurrency())
+            .try_collect()
+            .await?;
+
+        Ok(plans
+            .into_iter()
+            .map(|plan| match plan.as_slice() {
+                [] => Bytes::new(),
+                [(b, offset, size)] => blocks[b].slice(*offset..*offset + *size),
+                _ => {
+                    let mut buf = BytesMut::with_capacity(plan.iter().map(|v| v.2).sum());
+                    for &(b, offset, size) in plan.iter() {
+                        buf.extend_from_slice(&blocks[&b][offset..offset + size]);
+                    }
+                    buf.freeze()
+                }
+            })
+            .collect())
     }
 
     async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<u64> {
@@ -338,13 +406,7 @@ impl<S: Adapter> Backend<S> {
     }
 
     /// Read a block by its inode, version, block id along with offset and size.
-    async fn read_block(
-        &self,
-        ino: u64,
-        block: u64,
-        offset: usize,
-        size: usize,
-    ) -> Result<Vec<u8>> {
+    async fn read_block(&self, ino: u64, block: u64, offset: usize, size: usize) -> Result<Bytes> {
         debug_assert!(
             offset + size <= BLOCK_SIZE,
             "given offset {} size {} must be lower then block size",
@@ -352,6 +414,13 @@ impl<S: Adapter> Backend<S> {
             size
         );
 
+        // Slice the block without copy.
+        let bs = self.get_block(ino, block).await?;
+        Ok(bs.slice(offset..offset + size))
+    }
+
+    /// Get the whole block by its inode and block id.
+    async fn get_block(&self, ino: u64, block: u64) -> Result<Bytes> {
         let key = Key::block(ino, block);
         let bs = self.kv.get(&key.encode()).await?;
         match bs {
@@ -359,13 +428,7 @@ impl<S: Adapter> Backend<S> {
                 ErrorKind::NotFound,
                 anyhow!("block ino: {},  block: {} is not found", ino, block),
             )),
-            Some(bs) => {
-                if offset == 0 && size == bs.len() {
-                    Ok(bs)
-                } else {
-                    Ok(bs.into_iter().skip(offset).take(size).collect())
-                }
-            }
+            Some(bs) => Ok(Bytes::from(bs)),
         }
     }
 
@@ -510,48 +573,37 @@ fn calculate_blocks(offset: u64, size: u64) -> Vec<(u64, usize, usize)> {
 }
 
 #[pin_project]
-struct BlockReader<S: Adapter> {
+struct BlockStream<S: Adapter> {
     backend: Backend<S>,
     ino: u64,
     blocks: IntoIter<(u64, usize, usize)>,
-    buf: BytesMut,
-    fut: Option<BoxFuture<'static, Result<Vec<u8>>>>,
+    fut: Option<BoxFuture<'static, Result<Bytes>>>,
 }
 
-impl<S: Adapter> BlockReader<S> {
+impl<S: Adapter> BlockStream<S> {
     pub fn new(backend: Backend<S>, ino: u64, blocks: Vec<(u64, usize, usize)>) -> Self {
         Self {
             backend,
             ino,
             blocks: blocks.into_iter(),
-            buf: BytesMut::with_capacity(BLOCK_SIZE),
             fut: None,
         }
     }
 }
 
-impl<S> AsyncRead for BlockReader<S>
+impl<S> Stream for BlockStream<S>
 where
     S: Adapter,
 {
-    fn poll_read(
-        self: Pin<&mut Self>,
-        cx: &mut Context<'_>,
-        buf: &mut [u8],
-    ) -> Poll<Result<usize>> {
-        let mut this = self.project();
+    type Item = Result<Bytes>;
 
-        loop {
-            if this.buf.has_remaining() {
-                let size = min(buf.len(), this.buf.len());
-                buf[..size].copy_from_slice(&this.buf[..size]);
-                this.buf.advance(size);
-                return Poll::Ready(Ok(size));
-            }
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
 
-            match &mut this.fut {
+        loop {
+            match &mut *this.fut {
                 None => match this.blocks.next() {
-                    None => return Poll::Ready(Ok(0)),
+                    None => return Poll::Ready(None),
                     Some((block, offset, size)) => {
                         let backend = this.backend.clone();
                         let ino = *this.ino;
@@ -561,14 +613,11 @@ where
                     }
                 },
                 Some(fut) => {
-                    let bs = ready!(Pin::new(fut).poll(cx)?);
+                    let res = ready!(Pin::new(fut).poll(cx));
                     *this.fut = None;
 
-                    // Clear the buf to reuse the same space.
-                    this.buf.clear();
-                    // Extend from given slice.
-                    this.buf.extend_from_slice(&bs);
-                    continue;
+                    // Blocks will be passed through without copy.
+                    return Poll::Ready(Some(res));
                 }
             }
         }
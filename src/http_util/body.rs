@@ -19,10 +19,13 @@ use std::io::Write;
 
 use bytes::Buf;
 use bytes::Bytes;
+use futures::TryStreamExt;
 
+use crate::io_util::into_reader;
 use crate::io_util::into_stream;
 use crate::BlockingBytesReader;
 use crate::BytesReader;
+use crate::BytesStreamer;
 
 /// Body used in blocking HTTP requests.
 pub enum Body {
@@ -76,6 +79,8 @@ pub enum AsyncBody {
     Bytes(Bytes),
     /// Body with a Reader.
     Reader(BytesReader),
+    /// Body with a Stream, chunks will be sent without copy.
+    Stream(BytesStreamer),
     /// Body with a multipart field.
     ///
     /// If input with this field, we will goto the internal multipart
@@ -95,6 +100,7 @@ impl From<AsyncBody> for reqwest::Body {
             AsyncBody::Empty => reqwest::Body::from(""),
             AsyncBody::Bytes(bs) => reqwest::Body::from(bs),
             AsyncBody::Reader(r) => reqwest::Body::wrap_stream(into_stream(r, 16 * 1024)),
+            AsyncBody::Stream(s) => reqwest::Body::wrap_stream(s),
             AsyncBody::Multipart(_, _) => {
                 unreachable!("reqwest multipart should not be constructed by body")
             }
@@ -107,34 +113,55 @@ impl From<AsyncBody> for reqwest::Body {
 /// # Notes
 ///
 /// Client SHOULD NEVER construct this body.
-pub struct IncomingAsyncBody(BytesReader);
+pub struct IncomingAsyncBody(BytesStreamer);
 
 impl IncomingAsyncBody {
     /// Construct a new incoming async body
     pub fn new(r: BytesReader) -> Self {
-        Self(r)
+        Self(Box::new(into_stream(r, 16 * 1024)))
     }
 
-    /// Consume the entire body.
-    pub async fn consume(self) -> Result<()> {
-        use futures::io;
+    /// Construct a new incoming async body from stream.
+    pub fn from_stream(s: BytesStreamer) -> Self {
+        Self(s)
+    }
 
-        io::copy(self.0, &mut io::sink()).await?;
+    /// Consume the entire body.
+    pub async fn consume(mut self) -> Result<()> {
+        while self.0.try_next().await?.is_some() {}
 
         Ok(())
     }
 
     /// Consume the response to bytes.
-    pub async fn bytes(self) -> Result<Bytes> {
-        use futures::io;
-
-        let mut w = io::Cursor::new(Vec::with_capacity(1024));
-        io::copy(self.0, &mut w).await?;
-        Ok(Bytes::from(w.into_inner()))
+    pub async fn bytes(mut self) -> Result<Bytes> {
+        // Avoid copy if there is only one chunk.
+        let first = match self.0.try_next().await? {
+            None => return Ok(Bytes::new()),
+            Some(bs) => bs,
+        };
+        let second = match self.0.try_next().await? {
+            None => return Ok(first),
+            Some(bs) => bs,
+        };
+
+        let mut buf = Vec::with_capacity(first.len() + second.len());
+        buf.extend_from_slice(&first);
+        buf.extend_from_slice(&second);
+        while let Some(bs) = self.0.try_next().await? {
+            buf.extend_from_slice(&bs);
+        }
+        Ok(Bytes::from(buf))
     }
 
     /// Consume the response to build a reader.
     pub fn reader(self) -> BytesReader {
+        Box::new(into_reader(self.0))
+    }
+
+    /// Consume the response to build a stream, chunks will be passed
+    /// through without copy.
+    pub fn stream(self) -> BytesStreamer {
         self.0
     }
 }
@@ -19,25 +19,34 @@ use std::io::Error;
 use std::io::ErrorKind;
 use std::io::Result;
 use std::str::FromStr;
+use std::time::Duration;
 
+use anyhow::anyhow;
+use futures::stream;
+use futures::StreamExt;
 use futures::TryStreamExt;
 use http::Request;
 use http::Response;
 use log::debug;
 use reqwest::redirect::Policy;
+use reqwest::Certificate;
 use reqwest::ClientBuilder;
 use reqwest::Url;
 
 use super::AsyncBody;
 use super::Body;
 use crate::http_util::body::IncomingAsyncBody;
-use crate::io_util::into_reader;
 
 /// HttpClient that used across opendal.
+///
+/// Clones of a client share the same connection pool. Pass the same client
+/// to builders of services via `http_client` to share the connection pool
+/// across them.
 #[derive(Clone)]
 pub struct HttpClient {
     async_client: reqwest::Client,
     sync_client: ureq::Agent,
+    read_timeout: Option<Duration>,
 }
 
 impl Default for HttpClient {
@@ -56,48 +65,36 @@ impl Debug for HttpClient {
 impl HttpClient {
     /// Create a new http client.
     pub fn new() -> Self {
-        let async_client = {
-            let mut builder = ClientBuilder::new();
-
-            // Make sure we don't enable auto gzip decompress.
-            builder = builder.no_gzip();
-            // Make sure we don't enable auto brotli decompress.
-            builder = builder.no_brotli();
-            // Make sure we don't enable auto deflate decompress.
-            builder = builder.no_deflate();
-            // Redirect will be handled by ourselves.
-            builder = builder.redirect(Policy::none());
-
-            #[cfg(feature = "trust-dns")]
-            // using trust-dns async resolver
-            let builder = builder.trust_dns(true);
-            #[cfg(not(feature = "trust-dns"))]
-            // using `getaddrinfo`
-            let builder = builder.no_trust_dns();
-
-            builder.build().expect("reqwest client must build succeed")
-        };
-
-        let sync_client = {
-            let mut builder = ureq::AgentBuilder::new();
-
-            for key in ["http_proxy", "https_proxy", "HTTP_PROXY", "HTTPS_PROXY"] {
-                if let Ok(proxy) = env::var(key) {
-                    // Ignore proxy setting if proxy is invalid.
-                    if let Ok(proxy) = ureq::Proxy::new(proxy) {
-                        debug!("sync client: set proxy to {proxy:?}");
-                        builder = builder.proxy(proxy);
-                    }
-                }
-            }
-
-            builder.build()
-        };
+        HttpClientBuilder::default()
+            .build()
+            .expect("http client must build succeed")
+    }
 
-        HttpClient {
-            async_client,
-            sync_client,
-        }
+    /// Create a builder to configure http client.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use anyhow::Result;
+    /// use opendal::http_util::HttpClient;
+    /// use opendal::services::s3;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let client = HttpClient::builder()
+    ///     .connect_timeout(Duration::from_secs(3))
+    ///     .read_timeout(Duration::from_secs(30))
+    ///     .pool_max_idle_per_host(64)
+    ///     .build()?;
+    ///
+    /// // The client can be shared by many services.
+    /// let mut builder = s3::Builder::default();
+    /// builder.http_client(client.clone());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn builder() -> HttpClientBuilder {
+        HttpClientBuilder::default()
     }
 
     /// Send a request in blocking way.
@@ -165,7 +162,14 @@ impl HttpClient {
             req_builder.body(body)
         };
 
-        let resp = req_builder.send().await.map_err(|err| {
+        let fut = req_builder.send();
+        let resp = match self.read_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, fut)
+                .await
+                .map_err(|_| new_read_timeout_error(timeout))?,
+            None => fut.await,
+        }
+        .map_err(|err| {
             let kind = error_kind_from_reqwest_error(&err);
 
             Error::new(kind, err)
@@ -183,7 +187,20 @@ impl HttpClient {
 
             Error::new(kind, err)
         });
-        let body = IncomingAsyncBody::new(Box::new(into_reader(stream)));
+        let body = match self.read_timeout {
+            // Every chunk must arrive in time, or the body will be failed.
+            Some(timeout) => {
+                let stream = stream::unfold(stream, move |mut s| async move {
+                    match tokio::time::timeout(timeout, s.next()).await {
+                        Ok(Some(v)) => Some((v, s)),
+                        Ok(None) => None,
+                        Err(_) => Some((Err(new_read_timeout_error(timeout)), s)),
+                    }
+                });
+                IncomingAsyncBody::from_stream(Box::new(Box::pin(stream)))
+            }
+            None => IncomingAsyncBody::from_stream(Box::new(stream)),
+        };
 
         let resp = hr.body(body).expect("response must build succeed");
 
@@ -191,6 +208,201 @@ impl HttpClient {
     }
 }
 
+/// Builder for [`HttpClient`].
+///
+/// Options will be applied to both async and blocking clients unless noted.
+#[derive(Default, Clone)]
+pub struct HttpClientBuilder {
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout: Option<Duration>,
+    http2_only: bool,
+    root_certificates: Vec<Vec<u8>>,
+    proxy: Option<String>,
+}
+
+impl Debug for HttpClientBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpClientBuilder")
+            .field("connect_timeout", &self.connect_timeout)
+            .field("read_timeout", &self.read_timeout)
+            .field("pool_max_idle_per_host", &self.pool_max_idle_per_host)
+            .field("pool_idle_timeout", &self.pool_idle_timeout)
+            .field("http2_only", &self.http2_only)
+            .field("root_certificates", &self.root_certificates.len())
+            .field("proxy", &self.proxy)
+            .finish()
+    }
+}
+
+impl HttpClientBuilder {
+    /// Set timeout for establishing connections.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set timeout for reading from connections.
+    ///
+    /// For async client, this timeout applies to waiting for response
+    /// headers and to every chunk of response body.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the max idle connections kept in pool for every host.
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Set timeout for idle connections kept in pool.
+    ///
+    /// Only async client supports this option.
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Only use HTTP/2 with prior knowledge.
+    ///
+    /// Only async client supports this option.
+    pub fn http2_only(mut self) -> Self {
+        self.http2_only = true;
+        self
+    }
+
+    /// Add a PEM encoded root certificate to trust.
+    ///
+    /// Only async client supports this option.
+    pub fn root_certificate(mut self, pem: &[u8]) -> Self {
+        self.root_certificates.push(pem.to_vec());
+        self
+    }
+
+    /// Set proxy for all requests, like `http://127.0.0.1:8080`.
+    ///
+    /// If not set, the blocking client will read proxy from
+    /// `http_proxy` and `https_proxy` envs.
+    pub fn proxy(mut self, proxy: &str) -> Self {
+        self.proxy = Some(proxy.to_string());
+        self
+    }
+
+    /// Build the http client.
+    pub fn build(self) -> Result<HttpClient> {
+        let async_client = {
+            let mut builder = ClientBuilder::new();
+
+            // Make sure we don't enable auto gzip decompress.
+            builder = builder.no_gzip();
+            // Make sure we don't enable auto brotli decompress.
+            builder = builder.no_brotli();
+            // Make sure we don't enable auto deflate decompress.
+            builder = builder.no_deflate();
+            // Redirect will be handled by ourselves.
+            builder = builder.redirect(Policy::none());
+
+            #[cfg(feature = "trust-dns")]
+            // using trust-dns async resolver
+            let mut builder = builder.trust_dns(true);
+            #[cfg(not(feature = "trust-dns"))]
+            // using `getaddrinfo`
+            let mut builder = builder.no_trust_dns();
+
+            if let Some(timeout) = self.connect_timeout {
+                builder = builder.connect_timeout(timeout);
+            }
+            if let Some(max) = self.pool_max_idle_per_host {
+                builder = builder.pool_max_idle_per_host(max);
+            }
+            if let Some(timeout) = self.pool_idle_timeout {
+                builder = builder.pool_idle_timeout(timeout);
+            }
+            if self.http2_only {
+                builder = builder.http2_prior_knowledge();
+            }
+            for pem in &self.root_certificates {
+                let cert = Certificate::from_pem(pem).map_err(|err| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        anyhow!("root certificate is invalid: {err:?}"),
+                    )
+                })?;
+                builder = builder.add_root_certificate(cert);
+            }
+            if let Some(proxy) = &self.proxy {
+                let proxy = reqwest::Proxy::all(proxy).map_err(|err| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        anyhow!("proxy {proxy} is invalid: {err:?}"),
+                    )
+                })?;
+                builder = builder.proxy(proxy);
+            }
+
+            builder
+                .build()
+                .map_err(|err| Error::new(ErrorKind::Other, err))?
+        };
+
+        let sync_client = {
+            let mut builder = ureq::AgentBuilder::new();
+
+            if let Some(timeout) = self.connect_timeout {
+                builder = builder.timeout_connect(timeout);
+            }
+            if let Some(timeout) = self.read_timeout {
+                builder = builder.timeout_read(timeout);
+            }
+            if let Some(max) = self.pool_max_idle_per_host {
+                builder = builder.max_idle_connections_per_host(max);
+            }
+
+            match &self.proxy {
+                Some(proxy) => {
+                    let proxy = ureq::Proxy::new(proxy).map_err(|err| {
+                        Error::new(
+                            ErrorKind::InvalidInput,
+                            anyhow!("proxy {proxy} is invalid: {err:?}"),
+                        )
+                    })?;
+                    builder = builder.proxy(proxy);
+                }
+                None => {
+                    for key in ["http_proxy", "https_proxy", "HTTP_PROXY", "HTTPS_PROXY"] {
+                        if let Ok(proxy) = env::var(key) {
+                            // Ignore proxy setting if proxy is invalid.
+                            if let Ok(proxy) = ureq::Proxy::new(proxy) {
+                                debug!("sync client: set proxy to {proxy:?}");
+                                builder = builder.proxy(proxy);
+                            }
+                        }
+                    }
+                }
+            }
+
+            builder.build()
+        };
+
+        Ok(HttpClient {
+            async_client,
+            sync_client,
+            read_timeout: self.read_timeout,
+        })
+    }
+}
+
+fn new_read_timeout_error(timeout: Duration) -> Error {
+    // Timeout is temporary, allow retry.
+    Error::new(
+        ErrorKind::Interrupted,
+        anyhow!("read timeout after {}s", timeout.as_secs_f64()),
+    )
+}
+
 fn error_kind_from_reqwest_error(err: &reqwest::Error) -> ErrorKind {
     // Builder related error should not be retried.
     if err.is_builder() {
@@ -211,3 +423,84 @@ fn error_kind_from_reqwest_error(err: &reqwest::Error) -> ErrorKind {
 
     ErrorKind::Interrupted
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+    use std::io::BufReader;
+    use std::io::Write;
+    use std::net::TcpListener;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use std::thread;
+
+    use super::*;
+    use crate::Operator;
+
+    #[test]
+    fn test_http_client_builder() {
+        let client = HttpClient::builder()
+            .connect_timeout(Duration::from_secs(1))
+            .read_timeout(Duration::from_secs(1))
+            .pool_max_idle_per_host(1)
+            .proxy("http://127.0.0.1:8080")
+            .build();
+        assert!(client.is_ok());
+
+        let client = HttpClient::builder().proxy("invalid proxy://").build();
+        assert!(client.is_err());
+    }
+
+    /// Serve `200 OK` for all requests and count accepted connections.
+    fn serve(listener: TcpListener, conns: Arc<AtomicUsize>) {
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(v) => v,
+                    Err(_) => return,
+                };
+                conns.fetch_add(1, Ordering::SeqCst);
+
+                thread::spawn(move || -> std::io::Result<()> {
+                    let mut w = stream.try_clone()?;
+                    let mut r = BufReader::new(stream);
+                    loop {
+                        // Read request headers until the empty line.
+                        let mut line = String::new();
+                        loop {
+                            line.clear();
+                            if r.read_line(&mut line)? == 0 {
+                                return Ok(());
+                            }
+                            if line == "\r\n" {
+                                break;
+                            }
+                        }
+                        w.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")?;
+                    }
+                });
+            }
+        });
+    }
+
+    #[tokio::test]
+    async fn test_http_client_shared() -> anyhow::Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let endpoint = format!("http://{}", listener.local_addr()?);
+        let conns = Arc::new(AtomicUsize::new(0));
+        serve(listener, conns.clone());
+
+        let client = HttpClient::new();
+        for path in ["a", "b"] {
+            let mut builder = crate::services::http::Builder::default();
+            builder.endpoint(&endpoint).http_client(client.clone());
+            let op = Operator::new(builder.build()?);
+            op.object(path).metadata().await?;
+        }
+        // Requests of both services are sent via the same pooled connection.
+        assert_eq!(conns.load(Ordering::SeqCst), 1);
+
+        Ok(())
+    }
+}
@@ -21,6 +21,7 @@
 
 mod client;
 pub use client::HttpClient;
+pub use client::HttpClientBuilder;
 
 mod body;
 pub use body::AsyncBody;
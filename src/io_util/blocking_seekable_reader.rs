@@ -0,0 +1,428 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::BufRead;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::ops::RangeBounds;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+
+use super::seekable_reader::BlockCache;
+use super::SeekableReaderStats;
+use crate::ops::BytesRange;
+use crate::ops::OpRead;
+use crate::ops::OpStat;
+use crate::Accessor;
+use crate::BlockingBytesReader;
+use crate::Object;
+
+/// Size of the internal buffer used by `BufRead` while read-ahead is disabled.
+const DEFAULT_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Add blocking seek support for object via internal lazy operation.
+///
+/// # Example
+///
+/// ```no_run
+/// # use opendal::Operator;
+/// # use opendal::Scheme;
+/// # use anyhow::Result;
+/// use std::io::Read;
+/// use std::io::Seek;
+/// use std::io::SeekFrom;
+///
+/// use opendal::io_util::blocking_seekable_read;
+/// # fn main() -> Result<()> {
+/// let op = Operator::from_env(Scheme::Fs)?;
+/// let o = op.object("test");
+/// let mut r = blocking_seekable_read(&o, 10..);
+/// r.seek(SeekFrom::Current(10))?;
+/// let mut bs = vec![0; 10];
+/// r.read(&mut bs)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn blocking_seekable_read(o: &Object, range: impl RangeBounds<u64>) -> BlockingSeekableReader {
+    let br = BytesRange::from(range);
+
+    BlockingSeekableReader {
+        acc: o.accessor(),
+        path: o.path().to_string(),
+        offset: br.offset(),
+        size: br.size(),
+
+        pos: 0,
+        stream: None,
+
+        read_ahead: 0,
+        coalesce_threshold: 0,
+        blocks: BlockCache::new(1),
+        buffer: Vec::new(),
+        buffer_start: 0,
+        stats: SeekableReaderStats::default(),
+    }
+}
+
+/// BlockingSeekableReader implement `Read`, `Seek` and `BufRead`.
+///
+/// It shares the same buffering policy with
+/// [`SeekableReader`][super::SeekableReader].
+pub struct BlockingSeekableReader {
+    acc: Arc<dyn Accessor>,
+    path: String,
+    offset: Option<u64>,
+    size: Option<u64>,
+
+    pos: u64,
+    /// Current stream and the position of its next byte.
+    stream: Option<(BlockingBytesReader, u64)>,
+
+    read_ahead: usize,
+    coalesce_threshold: u64,
+    blocks: BlockCache,
+    /// Buffer for `BufRead` while read-ahead is disabled.
+    buffer: Vec<u8>,
+    buffer_start: u64,
+    stats: SeekableReaderStats,
+}
+
+impl BlockingSeekableReader {
+    /// Set the read-ahead window in bytes, default to `0` which means disabled.
+    ///
+    /// See [`SeekableReader::with_read_ahead`][super::SeekableReader::with_read_ahead].
+    pub fn with_read_ahead(mut self, size: usize) -> Self {
+        self.read_ahead = size;
+        self
+    }
+
+    /// Set how many recently fetched blocks will be kept, default to `1`.
+    ///
+    /// See [`SeekableReader::with_block_cache`][super::SeekableReader::with_block_cache].
+    pub fn with_block_cache(mut self, capacity: usize) -> Self {
+        self.blocks = BlockCache::new(capacity.max(1));
+        self
+    }
+
+    /// Set the max distance in bytes of forward seeks that will reuse the
+    /// current stream, default to `0`.
+    ///
+    /// See [`SeekableReader::with_coalesce_threshold`][super::SeekableReader::with_coalesce_threshold].
+    pub fn with_coalesce_threshold(mut self, threshold: u64) -> Self {
+        self.coalesce_threshold = threshold;
+        self
+    }
+
+    /// Get the request counters of this reader.
+    pub fn stats(&self) -> SeekableReaderStats {
+        self.stats
+    }
+
+    fn is_eof(&self) -> bool {
+        matches!(self.size, Some(size) if self.pos >= size)
+    }
+
+    /// Make sure current stream is at `target`, reusing current stream if
+    /// it's behind `target` within the coalesce threshold.
+    ///
+    /// Returns the position of stream, which could be behind `target`.
+    fn prepare_stream(&mut self, target: u64) -> Result<u64> {
+        if let Some((_, cur)) = &self.stream {
+            if *cur <= target && target - *cur <= self.coalesce_threshold {
+                return Ok(*cur);
+            }
+        }
+
+        let op = OpRead::default()
+            .with_offset(Some(self.offset.unwrap_or_default() + target))
+            .with_size(self.size.map(|v| v.saturating_sub(target)));
+        self.stats.read_requests += 1;
+        let r = self.acc.blocking_read(&self.path, op)?;
+        self.stream = Some((r, target));
+        Ok(target)
+    }
+
+    /// Read from current stream, the stream will be dropped on error.
+    fn read_stream(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let (r, cur) = self.stream.as_mut().expect("stream must be prepared");
+        match r.read(buf) {
+            Ok(n) => {
+                *cur += n as u64;
+                self.stats.bytes_fetched += n as u64;
+                Ok(n)
+            }
+            Err(e) => {
+                self.stream = None;
+                Err(e)
+            }
+        }
+    }
+
+    /// Make sure the block of current position has been cached.
+    ///
+    /// Blocks skipped by coalesced seeks will be cached too.
+    fn load_block(&mut self) -> Result<()> {
+        let bs = self.read_ahead as u64;
+        let idx = self.pos / bs;
+
+        while self.blocks.get(idx).is_none() {
+            let cur = self.prepare_stream(idx * bs)?;
+
+            let mut block = vec![0; self.read_ahead];
+            let mut filled = 0;
+            while filled < block.len() {
+                let n = self.read_stream(&mut block[filled..])?;
+                if n == 0 {
+                    break;
+                }
+                filled += n;
+            }
+            block.truncate(filled);
+
+            if filled < self.read_ahead {
+                // Stream reaches the end, cached block will tell the
+                // reader to return EOF.
+                self.stream = None;
+            }
+            self.blocks.insert(cur / bs, block);
+        }
+
+        Ok(())
+    }
+
+    /// Read directly from stream while read-ahead is disabled.
+    fn read_direct(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let mut cur = self.prepare_stream(self.pos)?;
+
+        let mut skip = [0; 8 * 1024];
+        while cur < self.pos {
+            let amt = skip.len().min((self.pos - cur) as usize);
+            let n = self.read_stream(&mut skip[..amt])?;
+            if n == 0 {
+                return Ok(0);
+            }
+            cur += n as u64;
+        }
+
+        let n = self.read_stream(buf)?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+
+    /// Read from `BufRead` buffer, returns `None` if buffer doesn't cover
+    /// current position.
+    fn read_buffered(&mut self, buf: &mut [u8]) -> Option<usize> {
+        let end = self.buffer_start + self.buffer.len() as u64;
+        if self.pos < self.buffer_start || self.pos >= end {
+            return None;
+        }
+
+        let off = (self.pos - self.buffer_start) as usize;
+        let n = buf.len().min(self.buffer.len() - off);
+        buf[..n].copy_from_slice(&self.buffer[off..off + n]);
+        self.pos += n as u64;
+        Some(n)
+    }
+}
+
+impl Read for BlockingSeekableReader {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.is_eof() {
+            return Ok(0);
+        }
+
+        if self.read_ahead > 0 {
+            if let Some(n) = self.blocks.read_at(self.pos, self.read_ahead, buf) {
+                self.pos += n as u64;
+                self.stats.cache_hits += 1;
+                return Ok(n);
+            }
+
+            self.load_block()?;
+            let n = self
+                .blocks
+                .read_at(self.pos, self.read_ahead, buf)
+                .expect("block must be cached");
+            self.pos += n as u64;
+            return Ok(n);
+        }
+
+        if let Some(n) = self.read_buffered(buf) {
+            return Ok(n);
+        }
+        self.read_direct(buf)
+    }
+}
+
+impl BufRead for BlockingSeekableReader {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        if self.is_eof() {
+            return Ok(&[]);
+        }
+
+        if self.read_ahead > 0 {
+            self.load_block()?;
+
+            let off = (self.pos % self.read_ahead as u64) as usize;
+            let block = self
+                .blocks
+                .get(self.pos / self.read_ahead as u64)
+                .expect("block must be cached");
+            return Ok(&block[off.min(block.len())..]);
+        }
+
+        let end = self.buffer_start + self.buffer.len() as u64;
+        if self.pos < self.buffer_start || self.pos >= end {
+            let pos = self.pos;
+            let mut buffer = std::mem::take(&mut self.buffer);
+            buffer.resize(DEFAULT_BUFFER_SIZE, 0);
+            let n = self.read_direct(&mut buffer);
+
+            // Data is kept in buffer, reset the position until consumed.
+            self.pos = pos;
+            buffer.truncate(*n.as_ref().unwrap_or(&0));
+            self.buffer = buffer;
+            self.buffer_start = pos;
+            n?;
+        }
+
+        let off = (self.pos - self.buffer_start) as usize;
+        Ok(&self.buffer[off..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos += amt as u64;
+    }
+}
+
+impl Seek for BlockingSeekableReader {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let cur = match pos {
+            SeekFrom::Start(off) => off as i64,
+            SeekFrom::Current(off) => self.pos as i64 + off,
+            SeekFrom::End(off) => {
+                // Stat the object to get it's content-length.
+                if self.size.is_none() {
+                    self.stats.stat_requests += 1;
+                    let meta = self.acc.blocking_stat(&self.path, OpStat::new())?;
+                    // Range could start after the end of object.
+                    self.size = Some(
+                        meta.content_length()
+                            .saturating_sub(self.offset.unwrap_or_default()),
+                    );
+                }
+
+                let total_size = self.size.expect("must have valid total_size");
+
+                total_size as i64 + off
+            }
+        };
+
+        if cur < 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                anyhow!("invalid seek to a negative position"),
+            ));
+        }
+
+        // Seeking is pure in memory operation, the stream will be reused
+        // or dropped while reading.
+        self.pos = cur as u64;
+        Ok(self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+    use std::io::Read;
+    use std::io::Seek;
+    use std::io::SeekFrom;
+
+    use anyhow::Result;
+
+    use super::*;
+    use crate::Operator;
+    use crate::Scheme;
+
+    #[test]
+    fn test_blocking_reader() -> Result<()> {
+        let op = Operator::from_env(Scheme::Fs)?;
+        let path = format!("/tmp/{}", uuid::Uuid::new_v4());
+        let content: Vec<u8> = (0..=255).cycle().take(10 * 1024).collect();
+        op.object(&path).blocking_write(content.clone())?;
+
+        for read_ahead in [0, 1024] {
+            let o = op.object(&path);
+            let mut r = blocking_seekable_read(&o, ..)
+                .with_read_ahead(read_ahead)
+                .with_block_cache(2)
+                .with_coalesce_threshold(4096);
+
+            let mut bs = vec![0; 10];
+            r.read_exact(&mut bs)?;
+            assert_eq!(bs, &content[0..10]);
+
+            // Short forward seek should reuse current stream.
+            r.seek(SeekFrom::Start(3000))?;
+            r.read_exact(&mut bs)?;
+            assert_eq!(bs, &content[3000..3010]);
+            assert_eq!(r.stats().read_requests(), 1);
+
+            // Seek backward.
+            r.seek(SeekFrom::Start(5))?;
+            r.read_exact(&mut bs)?;
+            assert_eq!(bs, &content[5..15]);
+
+            // BufRead should work.
+            let buf = r.fill_buf()?;
+            assert_eq!(buf[0], content[15]);
+            r.consume(5);
+            assert_eq!(r.stream_position()?, 20);
+            r.read_exact(&mut bs)?;
+            assert_eq!(bs, &content[20..30]);
+
+            // Read to the end.
+            let n = r.seek(SeekFrom::End(-100))?;
+            assert_eq!(n, content.len() as u64 - 100);
+            let mut bs = Vec::new();
+            r.read_to_end(&mut bs)?;
+            assert_eq!(bs, &content[content.len() - 100..]);
+            assert!(r.fill_buf()?.is_empty());
+        }
+
+        op.object(&path).blocking_delete()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_blocking_reader_seek_past_end() -> Result<()> {
+        let op = Operator::from_env(Scheme::Fs)?;
+        let path = format!("/tmp/{}", uuid::Uuid::new_v4());
+        op.object(&path).blocking_write("Hello, world!")?;
+
+        let o = op.object(&path);
+        let mut r = blocking_seekable_read(&o, 20..);
+        // Range starts after the end of object, there is nothing to read.
+        assert_eq!(r.seek(SeekFrom::End(0))?, 0);
+
+        op.object(&path).blocking_delete()?;
+        Ok(())
+    }
+}
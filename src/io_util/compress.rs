@@ -29,10 +29,19 @@ use async_compression::codec::LzmaDecoder;
 use async_compression::codec::XzDecoder;
 use async_compression::codec::ZlibDecoder;
 use async_compression::codec::ZstdDecoder;
+use async_compression::futures::bufread::BrotliEncoder;
+use async_compression::futures::bufread::BzEncoder;
+use async_compression::futures::bufread::DeflateEncoder;
+use async_compression::futures::bufread::GzipEncoder;
+use async_compression::futures::bufread::LzmaEncoder;
+use async_compression::futures::bufread::XzEncoder;
+use async_compression::futures::bufread::ZlibEncoder;
+use async_compression::futures::bufread::ZstdEncoder;
 use async_compression::util::PartialBuffer;
 use bytes::Buf;
 use bytes::BytesMut;
 use futures::io::AsyncBufRead;
+use futures::io::AsyncRead;
 use futures::io::BufReader;
 use futures::ready;
 use log::trace;
@@ -110,6 +119,29 @@ impl CompressAlgorithm {
 
         CompressAlgorithm::from_extension(&ext)
     }
+
+    /// Get the HTTP `Content-Encoding` of this compress algorithm.
+    ///
+    /// Only algorithms registered as HTTP content coding will return a value,
+    /// others will return `None` instead.
+    ///
+    /// # Notes
+    ///
+    /// HTTP's `deflate` coding is actually the zlib format, so we map
+    /// [`CompressAlgorithm::Zlib`] to `deflate` and raw
+    /// [`CompressAlgorithm::Deflate`] to `None`.
+    pub fn content_encoding(&self) -> Option<&str> {
+        match self {
+            CompressAlgorithm::Brotli => Some("br"),
+            CompressAlgorithm::Gzip => Some("gzip"),
+            CompressAlgorithm::Zlib => Some("deflate"),
+            CompressAlgorithm::Zstd => Some("zstd"),
+            CompressAlgorithm::Bz2
+            | CompressAlgorithm::Deflate
+            | CompressAlgorithm::Lzma
+            | CompressAlgorithm::Xz => None,
+        }
+    }
 }
 
 impl From<CompressAlgorithm> for DecompressCodec {
@@ -439,7 +471,7 @@ impl<R: BytesRead> DecompressReader<R> {
     }
 }
 
-impl<R: BytesRead> futures::io::AsyncRead for DecompressReader<R> {
+impl<R: BytesRead> AsyncRead for DecompressReader<R> {
     fn poll_read(
         self: Pin<&mut Self>,
         cx: &mut Context<'_>,
@@ -482,6 +514,357 @@ impl<R: BytesRead> futures::io::AsyncRead for DecompressReader<R> {
     }
 }
 
+/// CompressCodec contains all encoders that opendal supports.
+///
+/// CompressCodec reads uncompressed data from the inner reader and
+/// returns compressed data.
+///
+/// # Example
+///
+/// Please use `CompressCodec::new()` to create a new encoder
+///
+/// ```
+/// use futures::io::BufReader;
+/// use futures::io::Cursor;
+/// use opendal::io_util::CompressAlgorithm;
+/// use opendal::io_util::CompressCodec;
+///
+/// let en = CompressCodec::new(BufReader::new(Cursor::new(vec![])), CompressAlgorithm::Zstd);
+/// ```
+#[derive(Debug)]
+#[pin_project(project = CompressCodecProj)]
+pub enum CompressCodec<R: AsyncBufRead> {
+    /// Encoder for [`CompressAlgorithm::Brotli`]
+    ///
+    /// BrotliEncoder is too large as BrotliDecoder does.
+    /// Wrap into box to reduce the total size of the enum
+    Brotli(Pin<Box<BrotliEncoder<R>>>),
+    /// Encoder for [`CompressAlgorithm::Bz2`]
+    Bz2(#[pin] BzEncoder<R>),
+    /// Encoder for [`CompressAlgorithm::Deflate`]
+    Deflate(#[pin] DeflateEncoder<R>),
+    /// Encoder for [`CompressAlgorithm::Gzip`]
+    Gzip(#[pin] GzipEncoder<R>),
+    /// Encoder for [`CompressAlgorithm::Lzma`]
+    Lzma(#[pin] LzmaEncoder<R>),
+    /// Encoder for [`CompressAlgorithm::Xz`]
+    Xz(#[pin] XzEncoder<R>),
+    /// Encoder for [`CompressAlgorithm::Zlib`]
+    Zlib(#[pin] ZlibEncoder<R>),
+    /// Encoder for [`CompressAlgorithm::Zstd`]
+    Zstd(#[pin] ZstdEncoder<R>),
+}
+
+impl<R: AsyncBufRead> CompressCodec<R> {
+    /// Create a new encoder of given algorithm with the default level.
+    pub fn new(reader: R, algo: CompressAlgorithm) -> Self {
+        match algo {
+            CompressAlgorithm::Brotli => {
+                CompressCodec::Brotli(Box::pin(BrotliEncoder::new(reader)))
+            }
+            CompressAlgorithm::Bz2 => CompressCodec::Bz2(BzEncoder::new(reader)),
+            CompressAlgorithm::Deflate => CompressCodec::Deflate(DeflateEncoder::new(reader)),
+            CompressAlgorithm::Gzip => CompressCodec::Gzip(GzipEncoder::new(reader)),
+            CompressAlgorithm::Lzma => CompressCodec::Lzma(LzmaEncoder::new(reader)),
+            CompressAlgorithm::Xz => CompressCodec::Xz(XzEncoder::new(reader)),
+            CompressAlgorithm::Zlib => CompressCodec::Zlib(ZlibEncoder::new(reader)),
+            CompressAlgorithm::Zstd => CompressCodec::Zstd(ZstdEncoder::new(reader)),
+        }
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> CompressCodec<R> {
+    /// Get a mutable reference to the inner reader.
+    fn get_mut(&mut self) -> &mut R {
+        match self {
+            CompressCodec::Brotli(v) => v.as_mut().get_pin_mut().get_mut(),
+            CompressCodec::Bz2(v) => v.get_mut(),
+            CompressCodec::Deflate(v) => v.get_mut(),
+            CompressCodec::Gzip(v) => v.get_mut(),
+            CompressCodec::Lzma(v) => v.get_mut(),
+            CompressCodec::Xz(v) => v.get_mut(),
+            CompressCodec::Zlib(v) => v.get_mut(),
+            CompressCodec::Zstd(v) => v.get_mut(),
+        }
+    }
+}
+
+impl<R: AsyncBufRead> AsyncRead for CompressCodec<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        match self.project() {
+            CompressCodecProj::Brotli(v) => v.as_mut().poll_read(cx, buf),
+            CompressCodecProj::Bz2(v) => v.poll_read(cx, buf),
+            CompressCodecProj::Deflate(v) => v.poll_read(cx, buf),
+            CompressCodecProj::Gzip(v) => v.poll_read(cx, buf),
+            CompressCodecProj::Lzma(v) => v.poll_read(cx, buf),
+            CompressCodecProj::Xz(v) => v.poll_read(cx, buf),
+            CompressCodecProj::Zlib(v) => v.poll_read(cx, buf),
+            CompressCodecProj::Zstd(v) => v.poll_read(cx, buf),
+        }
+    }
+}
+
+/// CompressInput is the input buffer of [`CompressEncoder`].
+///
+/// It returns `Pending` while no data is buffered and EOF has not been
+/// reached, which tells [`CompressEncoder`] to fill more data.
+#[derive(Debug, Default)]
+struct CompressInput {
+    buf: BytesMut,
+    eof: bool,
+}
+
+impl AsyncRead for CompressInput {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        let input = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let len = input.len().min(buf.len());
+        buf[..len].copy_from_slice(&input[..len]);
+        self.consume(len);
+        Poll::Ready(Ok(len))
+    }
+}
+
+impl AsyncBufRead for CompressInput {
+    fn poll_fill_buf(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<&[u8]>> {
+        let this = self.get_mut();
+        if this.buf.is_empty() && !this.eof {
+            return Poll::Pending;
+        }
+        Poll::Ready(Ok(&this.buf))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().buf.advance(amt)
+    }
+}
+
+/// CompressState is that encode state during compress.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CompressState {
+    /// Reading means there is no data to be consume, we need to fetch more.
+    ///
+    /// We need to call `CompressEncoder::fill()`.
+    Reading,
+    /// Encoding means data is ready.
+    ///
+    /// We need to call `CompressEncoder::encode()`
+    Encoding,
+    /// Finishing means all data has been consumed.
+    ///
+    /// We need to call `CompressEncoder::finish()` to flush them into output.
+    Finishing,
+    /// Done means the whole process of compress is done.
+    ///
+    /// We should not call any function of `CompressEncoder` anymore.
+    Done,
+}
+
+/// CompressEncoder provides blocking compress support for opendal: `encode` happen
+/// inside a blocking thread (user need to handle the compress logic)
+///
+/// Note: please handle state carefully!
+///
+/// # Examples
+///
+/// ```no_run
+/// use opendal::io_util::CompressAlgorithm;
+/// use opendal::io_util::CompressEncoder;
+/// use opendal::io_util::CompressState;
+/// # use std::io::Result;
+///
+/// # fn main() -> Result<()> {
+/// let content = vec![0; 16 * 1024 * 1024];
+/// let mut ce = CompressEncoder::new(CompressAlgorithm::Gzip);
+/// let mut result = vec![];
+/// let mut buf = vec![0; 1024 * 1024];
+/// let mut amt = 0;
+/// loop {
+///     match ce.state() {
+///         CompressState::Reading => {
+///             // Filling an empty slice means input has reached EOF.
+///             let read = ce.fill(&content[amt..]);
+///             amt += read;
+///         }
+///         CompressState::Encoding => {
+///             let written = ce.encode(&mut buf)?;
+///             result.extend_from_slice(&buf[..written]);
+///         }
+///         CompressState::Finishing => {
+///             let written = ce.finish(&mut buf)?;
+///             result.extend_from_slice(&buf[..written]);
+///         }
+///         CompressState::Done => {
+///             break;
+///         }
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct CompressEncoder {
+    encoder: CompressCodec<CompressInput>,
+    state: CompressState,
+}
+
+impl CompressEncoder {
+    /// Create a new CompressEncoder with given CompressAlgorithm
+    pub fn new(algo: CompressAlgorithm) -> Self {
+        Self {
+            encoder: CompressCodec::new(CompressInput::default(), algo),
+            state: CompressState::Reading,
+        }
+    }
+
+    /// Get compress state
+    pub fn state(&self) -> CompressState {
+        self.state
+    }
+
+    /// Fill more data from underlying reader.
+    ///
+    /// Filling an empty slice means the underlying reader has reached EOF,
+    /// encoder will move to [`CompressState::Finishing`] after all buffered
+    /// data has been encoded.
+    ///
+    /// # Notes
+    ///
+    /// For now, we will read all content into internal buffer. But in the future,
+    /// we may change the implementation to only read part of input data.
+    ///
+    /// So it's required to check returning read size and advance the reader's amt.
+    pub fn fill(&mut self, bs: &[u8]) -> usize {
+        debug_assert_eq!(self.state, CompressState::Reading);
+
+        let len = bs.len();
+        let input = self.encoder.get_mut();
+        if len == 0 {
+            input.eof = true;
+        }
+        input.buf.extend_from_slice(bs);
+        self.state = CompressState::Encoding;
+
+        trace!(
+            "fill: read {len} bytes from src, next state {:?}",
+            self.state
+        );
+        len
+    }
+
+    /// Encode data into output.
+    /// Returns the data that has been written.
+    pub fn encode(&mut self, output: &mut [u8]) -> Result<usize> {
+        debug_assert_eq!(self.state, CompressState::Encoding);
+
+        // If input reaches EOF, start finishing.
+        if self.encoder.get_mut().eof {
+            trace!("input reaches EOF, start finishing");
+            self.state = CompressState::Finishing;
+            return Ok(0);
+        }
+
+        // `CompressInput` never registers the waker, so polling is done
+        // synchronously: `Pending` means all buffered input has been consumed.
+        let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+        let written_len = match AsyncRead::poll_read(Pin::new(&mut self.encoder), &mut cx, output)?
+        {
+            Poll::Ready(n) => {
+                self.state = CompressState::Encoding;
+                n
+            }
+            Poll::Pending => {
+                self.state = CompressState::Reading;
+                0
+            }
+        };
+
+        trace!(
+            "encode: write {written_len} bytes into dst, next state {:?}",
+            self.state
+        );
+        Ok(written_len)
+    }
+
+    /// Finish a compress process, writing remaining data and trailer into output.
+    /// Return the data that has been written.
+    pub fn finish(&mut self, output: &mut [u8]) -> Result<usize> {
+        debug_assert_eq!(self.state, CompressState::Finishing);
+
+        let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+        let len = match AsyncRead::poll_read(Pin::new(&mut self.encoder), &mut cx, output)? {
+            Poll::Ready(n) => n,
+            Poll::Pending => unreachable!("input has reached EOF, encoder must not be pending"),
+        };
+        if len == 0 {
+            self.state = CompressState::Done;
+        } else {
+            self.state = CompressState::Finishing;
+        }
+
+        trace!(
+            "finish: flush {len} bytes into dst, next state {:?}",
+            self.state
+        );
+        Ok(len)
+    }
+}
+
+/// CompressReader provides async compress support for opendal: `encode` happen inside `poll_read` (will block the runtime)
+///
+/// Reading from CompressReader returns the compressed content of the inner reader.
+///
+/// # Examples
+///
+/// ```no_run
+/// use futures::io::Cursor;
+/// use opendal::io_util::CompressReader;
+/// use opendal::io_util::CompressAlgorithm;
+/// # use std::io::Result;
+/// # use futures::AsyncReadExt;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<()> {
+/// let content = vec![0; 16 * 1024 * 1024];
+/// let mut cr = CompressReader::new(Cursor::new(content), CompressAlgorithm::Gzip);
+/// let mut result = vec![];
+/// cr.read_to_end(&mut result).await?;
+/// # Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+#[pin_project]
+pub struct CompressReader<R: BytesRead> {
+    #[pin]
+    encoder: CompressCodec<BufReader<R>>,
+}
+
+impl<R: BytesRead> CompressReader<R> {
+    /// Create a new CompressReader.
+    pub fn new(reader: R, algo: CompressAlgorithm) -> Self {
+        Self {
+            encoder: CompressCodec::new(BufReader::new(reader), algo),
+        }
+    }
+}
+
+impl<R: BytesRead> AsyncRead for CompressReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        self.project().encoder.poll_read(cx, buf)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::cmp::min;
@@ -489,8 +872,6 @@ mod tests {
     use std::fs;
     use std::io::Result;
 
-    use async_compression::futures::bufread::GzipEncoder;
-    use async_compression::futures::bufread::ZlibEncoder;
     use futures::io::Cursor;
     use futures::AsyncReadExt;
     use rand::prelude::*;
@@ -764,4 +1145,91 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_compress_bytes_gzip_read_multiple() -> Result<()> {
+        let _ = env_logger::try_init();
+
+        let mut rng = ThreadRng::default();
+        let size = rng.gen_range(1..16 * 1024 * 1024);
+        let mut content = vec![0; size];
+        rng.fill_bytes(&mut content);
+
+        let mut ce = CompressEncoder::new(CompressAlgorithm::Gzip);
+
+        let mut compressed_content = vec![];
+        let mut buf = vec![0; 1024 * 1024];
+        let mut read = 0;
+        loop {
+            match ce.state() {
+                CompressState::Reading => {
+                    // Simulate read in 4 MiB, fill an empty slice at EOF.
+                    let size = min(read + 4 * 1024 * 1024, content.len());
+                    let n = ce.fill(&content[read..size]);
+                    read += n;
+                }
+                CompressState::Encoding => {
+                    let n = ce.encode(&mut buf)?;
+                    compressed_content.extend_from_slice(&buf[..n])
+                }
+                CompressState::Finishing => {
+                    let n = ce.finish(&mut buf)?;
+                    compressed_content.extend_from_slice(&buf[..n])
+                }
+                CompressState::Done => {
+                    break;
+                }
+            }
+        }
+
+        let mut cr =
+            DecompressReader::new(Cursor::new(compressed_content), CompressAlgorithm::Gzip);
+        let mut result = vec![];
+        cr.read_to_end(&mut result).await?;
+
+        assert_eq!(result.len(), content.len());
+        assert_eq!(
+            format!("{:x}", Sha256::digest(&result)),
+            format!("{:x}", Sha256::digest(&content))
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_compress_reader_all_algorithms() -> Result<()> {
+        let _ = env_logger::try_init();
+
+        let mut rng = ThreadRng::default();
+        let mut content = vec![0; 4 * 1024 * 1024];
+        rng.fill_bytes(&mut content);
+
+        for algo in [
+            CompressAlgorithm::Brotli,
+            CompressAlgorithm::Bz2,
+            CompressAlgorithm::Deflate,
+            CompressAlgorithm::Gzip,
+            CompressAlgorithm::Lzma,
+            CompressAlgorithm::Xz,
+            CompressAlgorithm::Zlib,
+            CompressAlgorithm::Zstd,
+        ] {
+            let mut cr = CompressReader::new(Cursor::new(content.clone()), algo);
+            let mut compressed_content = vec![];
+            cr.read_to_end(&mut compressed_content).await?;
+
+            let mut dr = DecompressReader::new(Cursor::new(compressed_content), algo);
+            let mut result = vec![];
+            dr.read_to_end(&mut result).await?;
+
+            assert_eq!(result.len(), content.len(), "{algo:?}");
+            assert_eq!(
+                format!("{:x}", Sha256::digest(&result)),
+                format!("{:x}", Sha256::digest(&content)),
+                "{algo:?}"
+            );
+        }
+
+        Ok(())
+    }
 }
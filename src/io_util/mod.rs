@@ -44,12 +44,29 @@ pub use write_observer::WriteObserver;
 mod seekable_reader;
 pub use seekable_reader::seekable_read;
 pub use seekable_reader::SeekableReader;
+pub use seekable_reader::SeekableReaderStats;
+
+mod streaming_writer;
+pub use streaming_writer::streaming_write;
+pub use streaming_writer::StreamingWriter;
+
+mod blocking_seekable_reader;
+pub use blocking_seekable_reader::blocking_seekable_read;
+pub use blocking_seekable_reader::BlockingSeekableReader;
 
 #[cfg(feature = "compress")]
 mod compress;
 #[cfg(feature = "compress")]
 pub use compress::CompressAlgorithm;
 #[cfg(feature = "compress")]
+pub use compress::CompressCodec;
+#[cfg(feature = "compress")]
+pub use compress::CompressEncoder;
+#[cfg(feature = "compress")]
+pub use compress::CompressReader;
+#[cfg(feature = "compress")]
+pub use compress::CompressState;
+#[cfg(feature = "compress")]
 pub use compress::DecompressCodec;
 #[cfg(feature = "compress")]
 pub use compress::DecompressDecoder;
@@ -12,7 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::VecDeque;
 use std::future::Future;
+use std::io::Error;
+use std::io::ErrorKind;
 use std::io::Result;
 use std::io::SeekFrom;
 use std::ops::RangeBounds;
@@ -21,6 +24,7 @@ use std::sync::Arc;
 use std::task::Context;
 use std::task::Poll;
 
+use anyhow::anyhow;
 use futures::future::BoxFuture;
 use futures::ready;
 use futures::AsyncRead;
@@ -67,11 +71,24 @@ pub fn seekable_read(o: &Object, range: impl RangeBounds<u64>) -> SeekableReader
         size: br.size(),
 
         pos: 0,
-        state: State::Idle,
+        stream: Stream::Idle,
+        stat: None,
+
+        read_ahead: 0,
+        coalesce_threshold: 0,
+        blocks: BlockCache::new(1),
+        filling: Vec::new(),
+        stats: SeekableReaderStats::default(),
     }
 }
 
 /// SeekableReader implement `AsyncRead` and `AsyncSeek`.
+///
+/// By default, every read after seeking backward will start a new read
+/// operation on backend. Use [`SeekableReader::with_read_ahead`],
+/// [`SeekableReader::with_block_cache`] and
+/// [`SeekableReader::with_coalesce_threshold`] to reduce the requests
+/// for workloads that do many small seeks like parquet or zip.
 pub struct SeekableReader {
     acc: Arc<dyn Accessor>,
     path: String,
@@ -79,23 +96,108 @@ pub struct SeekableReader {
     size: Option<u64>,
 
     pos: u64,
-    state: State,
+    stream: Stream,
+    stat: Option<BoxFuture<'static, Result<ObjectMetadata>>>,
+
+    read_ahead: usize,
+    coalesce_threshold: u64,
+    blocks: BlockCache,
+    /// Block that is filling from current stream.
+    filling: Vec<u8>,
+    stats: SeekableReaderStats,
 }
 
-enum State {
+enum Stream {
     Idle,
-    Sending(BoxFuture<'static, Result<BytesReader>>),
-    Seeking(BoxFuture<'static, Result<ObjectMetadata>>),
-    Reading(BytesReader),
+    /// Sending read request which starts at given position.
+    Sending(BoxFuture<'static, Result<BytesReader>>, u64),
+    /// Reading from stream whose next byte is at given position.
+    Reading(BytesReader, u64),
+}
+
+/// Counters of requests sent by [`SeekableReader`] and
+/// [`BlockingSeekableReader`][super::BlockingSeekableReader].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SeekableReaderStats {
+    pub(super) read_requests: u64,
+    pub(super) stat_requests: u64,
+    pub(super) cache_hits: u64,
+    pub(super) bytes_fetched: u64,
+}
+
+impl SeekableReaderStats {
+    /// Count of `read` requests sent to backend.
+    pub fn read_requests(&self) -> u64 {
+        self.read_requests
+    }
+
+    /// Count of `stat` requests sent to backend.
+    pub fn stat_requests(&self) -> u64 {
+        self.stat_requests
+    }
+
+    /// Count of reads that served by cached blocks.
+    pub fn cache_hits(&self) -> u64 {
+        self.cache_hits
+    }
+
+    /// Bytes fetched from backend, including the skipped and read-ahead bytes.
+    pub fn bytes_fetched(&self) -> u64 {
+        self.bytes_fetched
+    }
 }
 
 impl SeekableReader {
-    fn current_offset(&self) -> u64 {
-        self.offset.unwrap_or_default() + self.pos
+    /// Set the read-ahead window in bytes, default to `0` which means disabled.
+    ///
+    /// With read-ahead enabled, data will be fetched in blocks of this size
+    /// and fetched blocks will be kept in cache, small reads will be served
+    /// from the cache without sending new requests.
+    pub fn with_read_ahead(mut self, size: usize) -> Self {
+        self.read_ahead = size;
+        self
+    }
+
+    /// Set how many recently fetched blocks will be kept, default to `1`.
+    ///
+    /// Only works while read-ahead is enabled. The least recently used block
+    /// will be evicted while cache is full.
+    pub fn with_block_cache(mut self, capacity: usize) -> Self {
+        self.blocks = BlockCache::new(capacity.max(1));
+        self
+    }
+
+    /// Set the max distance in bytes of forward seeks that will reuse the
+    /// current stream instead of sending a new request, default to `0`.
+    ///
+    /// Bytes between current stream position and the seek target will be
+    /// read and discarded (or cached while read-ahead is enabled).
+    pub fn with_coalesce_threshold(mut self, threshold: u64) -> Self {
+        self.coalesce_threshold = threshold;
+        self
+    }
+
+    /// Get the request counters of this reader.
+    pub fn stats(&self) -> SeekableReaderStats {
+        self.stats
+    }
+
+    fn current_size(&self, pos: u64) -> Option<u64> {
+        self.size.map(|v| v.saturating_sub(pos))
+    }
+
+    /// Read from cached blocks, returns `None` if cache missed.
+    fn read_cached(&mut self, buf: &mut [u8]) -> Option<usize> {
+        let n = self.blocks.read_at(self.pos, self.read_ahead, buf)?;
+        self.pos += n as u64;
+        self.stats.cache_hits += 1;
+        Some(n)
     }
 
-    fn current_size(&self) -> Option<u64> {
-        self.size.map(|v| v - self.pos)
+    /// Drop current stream and the block that is filling.
+    fn reset_stream(&mut self) {
+        self.stream = Stream::Idle;
+        self.filling.clear();
     }
 }
 
@@ -105,34 +207,115 @@ impl AsyncRead for SeekableReader {
         cx: &mut Context<'_>,
         buf: &mut [u8],
     ) -> Poll<Result<usize>> {
-        match &mut self.state {
-            State::Idle => {
-                let acc = self.acc.clone();
-                let path = self.path.clone();
-                let op = OpRead::default()
-                    .with_offset(Some(self.current_offset()))
-                    .with_size(self.current_size());
-
-                let future = async move { acc.read(&path, op).await };
-
-                self.state = State::Sending(Box::pin(future));
-                self.poll_read(cx, buf)
+        let this = &mut *self;
+
+        loop {
+            if let Some(size) = this.size {
+                if this.pos >= size {
+                    return Poll::Ready(Ok(0));
+                }
             }
-            State::Sending(future) => match ready!(Pin::new(future).poll(cx)) {
-                Ok(r) => {
-                    self.state = State::Reading(Box::new(r));
-                    self.poll_read(cx, buf)
+
+            // Position where the stream should be at.
+            let target = if this.read_ahead > 0 {
+                if let Some(n) = this.read_cached(buf) {
+                    return Poll::Ready(Ok(n));
+                }
+                this.pos - this.pos % this.read_ahead as u64
+            } else {
+                this.pos
+            };
+
+            match &mut this.stream {
+                Stream::Idle => {
+                    let acc = this.acc.clone();
+                    let path = this.path.clone();
+                    let op = OpRead::default()
+                        .with_offset(Some(this.offset.unwrap_or_default() + target))
+                        .with_size(this.current_size(target));
+
+                    let future = async move { acc.read(&path, op).await };
+
+                    this.stats.read_requests += 1;
+                    this.stream = Stream::Sending(Box::pin(future), target);
                 }
-                Err(e) => Poll::Ready(Err(e)),
-            },
-            State::Reading(r) => match ready!(Pin::new(r).poll_read(cx, buf)) {
-                Ok(n) => {
-                    self.pos += n as u64;
-                    Poll::Ready(Ok(n))
+                Stream::Sending(future, start) => {
+                    let start = *start;
+                    match ready!(Pin::new(future).poll(cx)) {
+                        Ok(r) => this.stream = Stream::Reading(r, start),
+                        Err(e) => {
+                            this.reset_stream();
+                            return Poll::Ready(Err(e));
+                        }
+                    }
                 }
-                Err(e) => Poll::Ready(Err(e)),
-            },
-            _ => unreachable!("read while seeking is invalid"),
+                Stream::Reading(r, stream_pos) => {
+                    // Position of the stream excluding the filling block.
+                    let cur = *stream_pos - this.filling.len() as u64;
+                    if cur > target || target - cur > this.coalesce_threshold {
+                        this.reset_stream();
+                        continue;
+                    }
+
+                    if this.read_ahead > 0 {
+                        // Fill the block that starts at `cur`, skipped blocks
+                        // will be cached too.
+                        let filled = this.filling.len();
+                        this.filling.resize(this.read_ahead, 0);
+                        let n = match Pin::new(r).poll_read(cx, &mut this.filling[filled..]) {
+                            Poll::Ready(Ok(n)) => n,
+                            Poll::Ready(Err(e)) => {
+                                this.reset_stream();
+                                return Poll::Ready(Err(e));
+                            }
+                            Poll::Pending => {
+                                this.filling.truncate(filled);
+                                return Poll::Pending;
+                            }
+                        };
+                        this.filling.truncate(filled + n);
+                        *stream_pos += n as u64;
+                        this.stats.bytes_fetched += n as u64;
+
+                        if n == 0 || this.filling.len() == this.read_ahead {
+                            let block = std::mem::take(&mut this.filling);
+                            this.blocks.insert(cur / this.read_ahead as u64, block);
+                        }
+                        if n == 0 {
+                            // Stream reaches the end, cached block will
+                            // tell the reader to return EOF.
+                            this.stream = Stream::Idle;
+                        }
+                    } else if cur < target {
+                        let mut skip = [0; 8 * 1024];
+                        let amt = skip.len().min((target - cur) as usize);
+                        let n = match ready!(Pin::new(r).poll_read(cx, &mut skip[..amt])) {
+                            Ok(n) => n,
+                            Err(e) => {
+                                this.reset_stream();
+                                return Poll::Ready(Err(e));
+                            }
+                        };
+                        if n == 0 {
+                            return Poll::Ready(Ok(0));
+                        }
+                        *stream_pos += n as u64;
+                        this.stats.bytes_fetched += n as u64;
+                    } else {
+                        let n = match ready!(Pin::new(r).poll_read(cx, buf)) {
+                            Ok(n) => n,
+                            Err(e) => {
+                                this.reset_stream();
+                                return Poll::Ready(Err(e));
+                            }
+                        };
+                        *stream_pos += n as u64;
+                        this.pos += n as u64;
+                        this.stats.bytes_fetched += n as u64;
+                        return Poll::Ready(Ok(n));
+                    }
+                }
+            }
         }
     }
 }
@@ -143,9 +326,15 @@ impl AsyncSeek for SeekableReader {
         cx: &mut Context<'_>,
         pos: SeekFrom,
     ) -> Poll<Result<u64>> {
-        if let State::Seeking(future) = &mut self.state {
-            let meta = ready!(Pin::new(future).poll(cx))?;
-            self.size = Some(meta.content_length() - self.offset.unwrap_or_default())
+        if let Some(future) = &mut self.stat {
+            let meta = ready!(Pin::new(future).poll(cx));
+            self.stat = None;
+            // Range could start after the end of object.
+            self.size = Some(
+                meta?
+                    .content_length()
+                    .saturating_sub(self.offset.unwrap_or_default()),
+            )
         }
 
         let cur = self.pos as i64;
@@ -160,7 +349,8 @@ impl AsyncSeek for SeekableReader {
 
                     let future = async move { acc.stat(&path, OpStat::new()).await };
 
-                    self.state = State::Seeking(Box::pin(future));
+                    self.stats.stat_requests += 1;
+                    self.stat = Some(Box::pin(future));
                     return self.poll_seek(cx, pos);
                 }
 
@@ -170,13 +360,67 @@ impl AsyncSeek for SeekableReader {
             }
         };
 
-        self.pos = cur as u64;
+        if cur < 0 {
+            return Poll::Ready(Err(Error::new(
+                ErrorKind::InvalidInput,
+                anyhow!("invalid seek to a negative position"),
+            )));
+        }
 
-        self.state = State::Idle;
+        // Seeking is pure in memory operation, the stream will be reused
+        // or dropped while reading.
+        self.pos = cur as u64;
         Poll::Ready(Ok(self.pos))
     }
 }
 
+/// BlockCache is a tiny LRU cache of fetched blocks.
+///
+/// The capacity is expected to be small, so we use a `VecDeque` instead
+/// of maintaining a map.
+pub(super) struct BlockCache {
+    capacity: usize,
+    /// Blocks ordered from the most recently used to the least.
+    blocks: VecDeque<(u64, Vec<u8>)>,
+}
+
+impl BlockCache {
+    pub(super) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            blocks: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub(super) fn get(&mut self, idx: u64) -> Option<&[u8]> {
+        let i = self.blocks.iter().position(|(v, _)| *v == idx)?;
+        if i != 0 {
+            let block = self.blocks.remove(i).expect("block must exist");
+            self.blocks.push_front(block);
+        }
+        self.blocks.front().map(|(_, v)| v.as_slice())
+    }
+
+    pub(super) fn insert(&mut self, idx: u64, block: Vec<u8>) {
+        self.blocks.retain(|(v, _)| *v != idx);
+        if self.blocks.len() >= self.capacity {
+            self.blocks.pop_back();
+        }
+        self.blocks.push_front((idx, block));
+    }
+
+    /// Read the cached data at `pos` into buf with given block size,
+    /// returns `None` if the block is not cached.
+    pub(super) fn read_at(&mut self, pos: u64, block_size: usize, buf: &mut [u8]) -> Option<usize> {
+        let block = self.get(pos / block_size as u64)?;
+
+        let off = (pos % block_size as u64) as usize;
+        let n = buf.len().min(block.len().saturating_sub(off));
+        buf[..n].copy_from_slice(&block[off..off + n]);
+        Some(n)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::SeekFrom;
@@ -187,6 +431,7 @@ mod tests {
     use futures::AsyncSeekExt;
 
     use super::*;
+    use crate::services::memory;
     use crate::Operator;
     use crate::Scheme;
 
@@ -230,4 +475,96 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_reader_seek_past_end() -> Result<()> {
+        let f = Operator::from_env(Scheme::Fs)?;
+
+        let path = format!("/tmp/{}", uuid::Uuid::new_v4());
+        f.object(&path).write("Hello, world!").await?;
+
+        let o = f.object(&path);
+        let mut r = seekable_read(&o, 20..);
+        // Range starts after the end of object, there is nothing to read.
+        let n = r.seek(SeekFrom::End(0)).await?;
+        assert_eq!(n, 0);
+
+        f.object(&path).delete().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reader_read_ahead() -> Result<()> {
+        let op = Operator::new(memory::Builder::default().build()?);
+        let content: Vec<u8> = (0..=255).cycle().take(10 * 1024).collect();
+        op.object("test").write(content.clone()).await?;
+
+        let o = op.object("test");
+        let mut r = seekable_read(&o, ..)
+            .with_read_ahead(1024)
+            .with_block_cache(2)
+            .with_coalesce_threshold(4096);
+
+        // Small reads in the same block should be served by cache.
+        let mut bs = vec![0; 10];
+        r.read_exact(&mut bs).await?;
+        assert_eq!(bs, &content[0..10]);
+        r.seek(SeekFrom::Start(100)).await?;
+        r.read_exact(&mut bs).await?;
+        assert_eq!(bs, &content[100..110]);
+        assert_eq!(r.stats().read_requests(), 1);
+
+        // Short forward seek should reuse current stream.
+        r.seek(SeekFrom::Start(3000)).await?;
+        r.read_exact(&mut bs).await?;
+        assert_eq!(bs, &content[3000..3010]);
+        assert_eq!(r.stats().read_requests(), 1);
+
+        // Seek back to a cached block.
+        r.seek(SeekFrom::Start(2048)).await?;
+        r.read_exact(&mut bs).await?;
+        assert_eq!(bs, &content[2048..2058]);
+        assert_eq!(r.stats().read_requests(), 1);
+
+        // Seek back to an evicted block will start a new request.
+        r.seek(SeekFrom::Start(5)).await?;
+        r.read_exact(&mut bs).await?;
+        assert_eq!(bs, &content[5..15]);
+        assert_eq!(r.stats().read_requests(), 2);
+
+        // Read to the end.
+        r.seek(SeekFrom::End(-100)).await?;
+        let mut bs = Vec::new();
+        r.read_to_end(&mut bs).await?;
+        assert_eq!(bs, &content[content.len() - 100..]);
+        assert_eq!(r.stats().stat_requests(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reader_coalesce_without_read_ahead() -> Result<()> {
+        let op = Operator::new(memory::Builder::default().build()?);
+        let content: Vec<u8> = (0..=255).cycle().take(4096).collect();
+        op.object("test").write(content.clone()).await?;
+
+        let o = op.object("test");
+        let mut r = seekable_read(&o, 1024..).with_coalesce_threshold(1024);
+
+        let mut bs = vec![0; 10];
+        r.read_exact(&mut bs).await?;
+        assert_eq!(bs, &content[1024..1034]);
+        r.seek(SeekFrom::Current(500)).await?;
+        r.read_exact(&mut bs).await?;
+        assert_eq!(bs, &content[1534..1544]);
+        assert_eq!(r.stats().read_requests(), 1);
+
+        // Seek backward will drop current stream.
+        r.seek(SeekFrom::Start(0)).await?;
+        r.read_exact(&mut bs).await?;
+        assert_eq!(bs, &content[1024..1034]);
+        assert_eq!(r.stats().read_requests(), 2);
+
+        Ok(())
+    }
 }
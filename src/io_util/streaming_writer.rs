@@ -0,0 +1,185 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
+
+use anyhow::anyhow;
+use bytes::Bytes;
+use futures::channel::mpsc;
+use futures::future::BoxFuture;
+use futures::ready;
+use futures::AsyncWrite;
+use futures::Sink;
+
+use super::into_reader;
+use crate::ops::OpWrite;
+use crate::Object;
+
+/// Create a writer that streams data into object with known size.
+///
+/// The underlying `write` operation is driven by the writer itself, so no
+/// background task is spawned. Data will be committed after `close` returns.
+///
+/// # Example
+///
+/// ```no_run
+/// # use opendal::Operator;
+/// # use opendal::Scheme;
+/// # use anyhow::Result;
+/// use futures::AsyncWriteExt;
+///
+/// use opendal::io_util::streaming_write;
+/// # #[tokio::main]
+/// # async fn main() -> Result<()> {
+/// let op = Operator::from_env(Scheme::Memory)?;
+/// let o = op.object("test");
+/// let mut w = streaming_write(&o, 13);
+/// w.write_all(b"Hello, ").await?;
+/// w.write_all(b"World!").await?;
+/// w.close().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn streaming_write(o: &Object, size: u64) -> StreamingWriter {
+    // Use a zero-sized channel so that at most one chunk is buffered.
+    let (tx, rx) = mpsc::channel(0);
+
+    let acc = o.accessor();
+    let path = o.path().to_string();
+    let future = async move {
+        acc.write(&path, OpWrite::new(size), Box::new(into_reader(rx)))
+            .await
+    };
+
+    StreamingWriter {
+        tx: Some(tx),
+        future: Some(Box::pin(future)),
+    }
+}
+
+/// StreamingWriter implement `AsyncWrite`.
+pub struct StreamingWriter {
+    tx: Option<mpsc::Sender<Result<Bytes>>>,
+    future: Option<BoxFuture<'static, Result<u64>>>,
+}
+
+impl StreamingWriter {
+    /// Drive the underlying write operation, returns `Ready` once finished.
+    fn poll_future(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        match &mut self.future {
+            Some(future) => {
+                let res = ready!(future.as_mut().poll(cx));
+                self.future = None;
+                self.tx = None;
+                Poll::Ready(res.map(|_| ()))
+            }
+            None => Poll::Ready(Ok(())),
+        }
+    }
+}
+
+impl AsyncWrite for StreamingWriter {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<Result<usize>> {
+        // Underlying write will only finish while all data has been consumed
+        // or failed.
+        if let Poll::Ready(res) = self.poll_future(cx) {
+            res?;
+            return Poll::Ready(Err(Error::new(
+                ErrorKind::WriteZero,
+                anyhow!("streaming writer has been finished, no more data can be written"),
+            )));
+        }
+
+        let tx = self
+            .tx
+            .as_mut()
+            .expect("sender must be valid while writing");
+        if ready!(Pin::new(&mut *tx).poll_ready(cx)).is_err() {
+            return Poll::Ready(Err(Error::new(
+                ErrorKind::BrokenPipe,
+                anyhow!("streaming writer has been closed"),
+            )));
+        }
+
+        let size = buf.len();
+        if Pin::new(tx)
+            .start_send(Ok(Bytes::copy_from_slice(buf)))
+            .is_err()
+        {
+            return Poll::Ready(Err(Error::new(
+                ErrorKind::BrokenPipe,
+                anyhow!("streaming writer has been closed"),
+            )));
+        }
+        Poll::Ready(Ok(size))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        // Drive the underlying write so that buffered chunk could be sent.
+        match self.poll_future(cx) {
+            Poll::Ready(res) => Poll::Ready(res),
+            Poll::Pending => Poll::Ready(Ok(())),
+        }
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        // Close the channel so that underlying reader will reach EOF.
+        if let Some(tx) = self.tx.as_mut() {
+            tx.close_channel();
+        }
+
+        self.poll_future(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use futures::AsyncWriteExt;
+
+    use super::*;
+    use crate::services::memory;
+    use crate::Operator;
+
+    #[tokio::test]
+    async fn test_streaming_writer() -> Result<()> {
+        let op = Operator::new(memory::Builder::default().build()?);
+        let content: Vec<u8> = (0..=255).cycle().take(64 * 1024).collect();
+
+        let o = op.object("test");
+        let mut w = streaming_write(&o, content.len() as u64);
+        for chunk in content.chunks(1000) {
+            w.write_all(chunk).await?;
+        }
+        w.close().await?;
+
+        assert_eq!(o.read().await?, content);
+
+        // Writer with less data should fail.
+        let mut w = streaming_write(&o, 10);
+        w.write_all(b"Hello").await?;
+        assert!(w.close().await.is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,698 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use log::info;
+use log::warn;
+use parking_lot::Mutex;
+use serde::Serialize;
+use time::OffsetDateTime;
+use tokio::runtime::Handle;
+
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpDelete;
+use crate::ops::OpWrite;
+use crate::ops::Operation;
+use crate::Accessor;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::BytesStreamer;
+use crate::Layer;
+use crate::ObjectMode;
+use crate::Operator;
+use crate::Scheme;
+
+/// Outcome of an audited operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditOutcome {
+    /// The operation succeeded.
+    Success,
+    /// The operation failed.
+    Failure {
+        /// Kind of the returned error.
+        kind: ErrorKind,
+        /// Message of the returned error.
+        message: String,
+    },
+}
+
+/// AuditRecord describes one mutating operation.
+#[derive(Debug, Clone)]
+pub struct AuditRecord {
+    timestamp: OffsetDateTime,
+    scheme: Scheme,
+    op: Operation,
+    path: String,
+    size: Option<u64>,
+    outcome: AuditOutcome,
+    principal: Option<String>,
+}
+
+impl AuditRecord {
+    /// Time when the operation finished.
+    pub fn timestamp(&self) -> OffsetDateTime {
+        self.timestamp
+    }
+
+    /// Scheme of the audited service.
+    pub fn scheme(&self) -> Scheme {
+        self.scheme
+    }
+
+    /// Audited operation.
+    pub fn operation(&self) -> Operation {
+        self.op
+    }
+
+    /// Path of the audited operation.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Size of the content, only available for file `create` and `write`.
+    ///
+    /// For failed writes, this is the size caller intended to write.
+    pub fn size(&self) -> Option<u64> {
+        self.size
+    }
+
+    /// Outcome of the audited operation.
+    pub fn outcome(&self) -> &AuditOutcome {
+        &self.outcome
+    }
+
+    /// Principal set by [`AuditLayer::with_principal`].
+    pub fn principal(&self) -> Option<&str> {
+        self.principal.as_deref()
+    }
+
+    /// Encode this record as a single line JSON object without the trailing
+    /// newline.
+    ///
+    /// ```json
+    /// {"timestamp":1666000000000,"scheme":"s3","operation":"write","path":"abc","size":3,"outcome":"success","error_kind":null,"error":null,"principal":"alice"}
+    /// ```
+    ///
+    /// `timestamp` is in milliseconds since unix epoch.
+    pub fn to_json(&self) -> String {
+        let (outcome, error_kind, error) = match &self.outcome {
+            AuditOutcome::Success => ("success", None, None),
+            AuditOutcome::Failure { kind, message } => {
+                ("failure", Some(format!("{kind:?}")), Some(message.as_str()))
+            }
+        };
+
+        let record = JsonRecord {
+            timestamp: (self.timestamp.unix_timestamp_nanos() / 1_000_000) as i64,
+            scheme: self.scheme.into(),
+            operation: self.op.into(),
+            path: &self.path,
+            size: self.size,
+            outcome,
+            error_kind,
+            error,
+            principal: self.principal(),
+        };
+        serde_json::to_string(&record).expect("audit record must be serializable")
+    }
+}
+
+#[derive(Serialize)]
+struct JsonRecord<'a> {
+    timestamp: i64,
+    scheme: &'static str,
+    operation: &'static str,
+    path: &'a str,
+    size: Option<u64>,
+    outcome: &'static str,
+    error_kind: Option<String>,
+    error: Option<&'a str>,
+    principal: Option<&'a str>,
+}
+
+/// AuditSink receives records emitted by [`AuditLayer`].
+///
+/// `append` is called on the path of every mutating operation, including
+/// blocking ones, so it should not block. Sinks that need io should buffer
+/// records and flush them in background, like [`OperatorAuditSink`].
+pub trait AuditSink: Send + Sync + 'static {
+    /// Append a record to this sink.
+    fn append(&self, record: AuditRecord);
+}
+
+/// LogAuditSink emits every record as a JSON line via `log` at `info`
+/// level with target `opendal::audit`.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct LogAuditSink;
+
+impl AuditSink for LogAuditSink {
+    fn append(&self, record: AuditRecord) {
+        info!(target: "opendal::audit", "{}", record.to_json())
+    }
+}
+
+/// OperatorAuditSink appends records as JSON Lines to another [`Operator`].
+///
+/// Most services don't support appending to an existing object, so every
+/// flushed batch is written as a new object named
+/// `{prefix}{unix_nanos}-{seq}.jsonl`. Objects sorted by name follow the
+/// order they are flushed by this sink.
+///
+/// Records are flushed in background when:
+///
+/// - `batch_size` records are buffered.
+/// - `flush_interval` has passed since the oldest buffered record was
+///   appended, even if no more records are appended.
+///
+/// Background flushes need a tokio runtime, records appended outside of
+/// tokio are kept until the next flush. Batches that failed to write will
+/// be logged and retried after `flush_interval`. Buffered records are lost
+/// if the process exits before they are flushed, call
+/// [`OperatorAuditSink::flush`] before shutdown to write out the remaining
+/// records.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use opendal::layers::AuditLayer;
+/// use opendal::layers::OperatorAuditSink;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// let log_op = Operator::from_env(Scheme::Memory).expect("must init");
+/// let sink = OperatorAuditSink::new(log_op, "audit/")
+///     .with_batch_size(100)
+///     .with_flush_interval(Duration::from_secs(10));
+///
+/// let _ = Operator::from_env(Scheme::Memory)
+///     .expect("must init")
+///     .layer(AuditLayer::new(sink.clone()).with_principal("alice"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct OperatorAuditSink {
+    op: Operator,
+    prefix: String,
+    batch_size: usize,
+    flush_interval: Duration,
+    state: Arc<Mutex<SinkState>>,
+    seq: Arc<AtomicU64>,
+}
+
+#[derive(Debug, Default)]
+struct SinkState {
+    buf: Vec<u8>,
+    records: usize,
+    /// Time when the oldest buffered record was appended.
+    since: Option<Instant>,
+}
+
+impl SinkState {
+    fn take(&mut self) -> Option<(Vec<u8>, usize)> {
+        if self.records == 0 {
+            return None;
+        }
+
+        self.since = None;
+        Some((
+            std::mem::take(&mut self.buf),
+            std::mem::take(&mut self.records),
+        ))
+    }
+
+    /// Put a failed batch back in front of buffered records.
+    ///
+    /// Returns the time of oldest record if the buffer was empty.
+    fn requeue(&mut self, mut buf: Vec<u8>, records: usize) -> Option<Instant> {
+        buf.append(&mut self.buf);
+        self.buf = buf;
+        self.records += records;
+        match self.since {
+            Some(_) => None,
+            None => {
+                let now = Instant::now();
+                self.since = Some(now);
+                Some(now)
+            }
+        }
+    }
+}
+
+impl OperatorAuditSink {
+    /// Create a new sink that writes batches under `prefix` of `op`.
+    pub fn new(op: Operator, prefix: &str) -> Self {
+        Self {
+            op,
+            prefix: prefix.to_string(),
+            batch_size: 1000,
+            flush_interval: Duration::from_secs(60),
+            state: Arc::new(Mutex::new(SinkState::default())),
+            seq: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Set records in one batch, default to 1000.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Set max interval between flushes, default to 60s.
+    pub fn with_flush_interval(mut self, flush_interval: Duration) -> Self {
+        self.flush_interval = flush_interval;
+        self
+    }
+
+    /// Write all buffered records out.
+    pub async fn flush(&self) -> Result<()> {
+        let batch = self.state.lock().take();
+        match batch {
+            Some((buf, records)) => self.write_batch(buf, records).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn write_batch(&self, buf: Vec<u8>, records: usize) -> Result<()> {
+        let nanos = OffsetDateTime::now_utc().unix_timestamp_nanos();
+        let seq = self.seq.fetch_add(1, Ordering::Relaxed);
+        let path = format!("{}{nanos:020}-{seq:010}.jsonl", self.prefix);
+
+        if let Err(err) = self.op.object(&path).write(buf.clone()).await {
+            warn!(
+                target: "opendal::layers::audit",
+                "path={path} records={records} -> flush failed: {err:?}"
+            );
+            let since = self.state.lock().requeue(buf, records);
+            if let Some(since) = since {
+                self.schedule_flush(&Handle::current(), since);
+            }
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Flush records buffered since `since` after `flush_interval`.
+    ///
+    /// Nothing will be flushed if they have been flushed by others.
+    fn schedule_flush(&self, handle: &Handle, since: Instant) {
+        let sink = self.clone();
+        handle.spawn(async move {
+            tokio::time::sleep(sink.flush_interval).await;
+
+            let batch = {
+                let mut state = sink.state.lock();
+                if state.since != Some(since) {
+                    return;
+                }
+                state.take()
+            };
+            if let Some((buf, records)) = batch {
+                let _ = sink.write_batch(buf, records).await;
+            }
+        });
+    }
+}
+
+impl AuditSink for OperatorAuditSink {
+    fn append(&self, record: AuditRecord) {
+        let handle = Handle::try_current().ok();
+        let batch = {
+            let mut state = self.state.lock();
+            state.buf.extend_from_slice(record.to_json().as_bytes());
+            state.buf.push(b'\n');
+            state.records += 1;
+            let since = match state.since {
+                Some(since) => since,
+                None => {
+                    let now = Instant::now();
+                    state.since = Some(now);
+                    // Make sure records will be flushed without more appends.
+                    if let Some(handle) = &handle {
+                        self.schedule_flush(handle, now);
+                    }
+                    now
+                }
+            };
+
+            if state.records < self.batch_size && since.elapsed() < self.flush_interval {
+                return;
+            }
+            match handle {
+                Some(handle) => state.take().map(|batch| (handle, batch)),
+                None => None,
+            }
+        };
+
+        if let Some((handle, (buf, records))) = batch {
+            let sink = self.clone();
+            handle.spawn(async move {
+                let _ = sink.write_batch(buf, records).await;
+            });
+        }
+    }
+}
+
+/// AuditLayer will emit an [`AuditRecord`] to the [`AuditSink`] for every
+/// mutating operation: `create`, `write`, `delete` and `complete_multipart`,
+/// including their blocking and stream versions.
+///
+/// Records are emitted after the operation returns, whether it succeeded or
+/// not. Read operations are not audited, use
+/// [`LoggingLayer`][super::LoggingLayer] for them instead.
+///
+/// # Examples
+///
+/// ```
+/// use opendal::layers::AuditLayer;
+/// use opendal::layers::LogAuditSink;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// let _ = Operator::from_env(Scheme::Memory)
+///     .expect("must init")
+///     .layer(AuditLayer::new(LogAuditSink).with_principal("alice"));
+/// ```
+#[derive(Clone)]
+pub struct AuditLayer {
+    sink: Arc<dyn AuditSink>,
+    principal: Option<String>,
+}
+
+impl Debug for AuditLayer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditLayer")
+            .field("principal", &self.principal)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AuditLayer {
+    /// Create a new AuditLayer that emits records to `sink`.
+    pub fn new(sink: impl AuditSink) -> Self {
+        Self {
+            sink: Arc::new(sink),
+            principal: None,
+        }
+    }
+
+    /// Set the principal carried by every record.
+    pub fn with_principal(mut self, principal: &str) -> Self {
+        self.principal = Some(principal.to_string());
+        self
+    }
+}
+
+impl Layer for AuditLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(AuditAccessor {
+            scheme: inner.metadata().scheme(),
+            inner,
+            sink: self.sink.clone(),
+            principal: self.principal.clone(),
+        })
+    }
+}
+
+#[derive(Clone)]
+struct AuditAccessor {
+    scheme: Scheme,
+    inner: Arc<dyn Accessor>,
+    sink: Arc<dyn AuditSink>,
+    principal: Option<String>,
+}
+
+impl Debug for AuditAccessor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditAccessor")
+            .field("scheme", &self.scheme)
+            .field("inner", &self.inner)
+            .field("principal", &self.principal)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AuditAccessor {
+    fn audit<T>(&self, op: Operation, path: &str, size: Option<u64>, res: &Result<T>) {
+        let outcome = match res {
+            Ok(_) => AuditOutcome::Success,
+            Err(err) => AuditOutcome::Failure {
+                kind: err.kind(),
+                message: err.to_string(),
+            },
+        };
+
+        self.sink.append(AuditRecord {
+            timestamp: OffsetDateTime::now_utc(),
+            scheme: self.scheme,
+            op,
+            path: path.to_string(),
+            size,
+            outcome,
+            principal: self.principal.clone(),
+        })
+    }
+
+    fn create_size(args: &OpCreate) -> Option<u64> {
+        match args.mode() {
+            ObjectMode::FILE => Some(0),
+            _ => None,
+        }
+    }
+
+    fn write_size(args: &OpWrite, res: &Result<u64>) -> Option<u64> {
+        match res {
+            Ok(n) => Some(*n),
+            Err(_) => Some(args.size()),
+        }
+    }
+}
+
+#[async_trait]
+impl Accessor for AuditAccessor {
+    fn inner(&self) -> Option<Arc<dyn Accessor>> {
+        Some(self.inner.clone())
+    }
+
+    async fn create(&self, path: &str, args: OpCreate) -> Result<()> {
+        let size = Self::create_size(&args);
+        let res = self.inner.create(path, args).await;
+        self.audit(Operation::Create, path, size, &res);
+        res
+    }
+
+    async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<u64> {
+        let res = self.inner.write(path, args.clone(), r).await;
+        self.audit(Operation::Write, path, Self::write_size(&args, &res), &res);
+        res
+    }
+
+    async fn write_stream(&self, path: &str, args: OpWrite, s: BytesStreamer) -> Result<u64> {
+        let res = self.inner.write_stream(path, args.clone(), s).await;
+        self.audit(Operation::Write, path, Self::write_size(&args, &res), &res);
+        res
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<()> {
+        let res = self.inner.delete(path, args).await;
+        self.audit(Operation::Delete, path, None, &res);
+        res
+    }
+
+    async fn complete_multipart(&self, path: &str, args: OpCompleteMultipart) -> Result<()> {
+        let res = self.inner.complete_multipart(path, args).await;
+        self.audit(Operation::CompleteMultipart, path, None, &res);
+        res
+    }
+
+    fn blocking_create(&self, path: &str, args: OpCreate) -> Result<()> {
+        let size = Self::create_size(&args);
+        let res = self.inner.blocking_create(path, args);
+        self.audit(Operation::BlockingCreate, path, size, &res);
+        res
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite, r: BlockingBytesReader) -> Result<u64> {
+        let res = self.inner.blocking_write(path, args.clone(), r);
+        self.audit(
+            Operation::BlockingWrite,
+            path,
+            Self::write_size(&args, &res),
+            &res,
+        );
+        res
+    }
+
+    fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<()> {
+        let res = self.inner.blocking_delete(path, args);
+        self.audit(Operation::BlockingDelete, path, None, &res);
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::services::memory;
+
+    #[derive(Clone, Default)]
+    struct MemorySink {
+        records: Arc<Mutex<Vec<AuditRecord>>>,
+    }
+
+    impl AuditSink for MemorySink {
+        fn append(&self, record: AuditRecord) {
+            self.records.lock().push(record)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_audit() -> anyhow::Result<()> {
+        let sink = MemorySink::default();
+        let op = Operator::new(memory::Builder::default().build()?)
+            .layer(AuditLayer::new(sink.clone()).with_principal("alice"));
+
+        op.object("a").write("abc").await?;
+        op.object("dir/").create().await?;
+        // Reads are not audited.
+        op.object("a").read().await?;
+        op.object("a").delete().await?;
+        assert!(op.object("a").read().await.is_err());
+
+        let records = sink.records.lock().clone();
+        let ops: Vec<_> = records
+            .iter()
+            .map(|r| (r.operation(), r.path(), r.size()))
+            .collect();
+        assert_eq!(
+            ops,
+            vec![
+                (Operation::Write, "a", Some(3)),
+                (Operation::Create, "dir/", None),
+                (Operation::Delete, "a", None),
+            ]
+        );
+        assert!(records
+            .iter()
+            .all(|r| r.principal() == Some("alice") && r.scheme() == Scheme::Memory));
+        assert_eq!(records[0].outcome(), &AuditOutcome::Success);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_audit_record_json() {
+        let record = AuditRecord {
+            timestamp: OffsetDateTime::from_unix_timestamp(1666000000).expect("must be valid"),
+            scheme: Scheme::S3,
+            op: Operation::Delete,
+            path: "a\"b".to_string(),
+            size: None,
+            outcome: AuditOutcome::Failure {
+                kind: ErrorKind::PermissionDenied,
+                message: "denied".to_string(),
+            },
+            principal: None,
+        };
+
+        assert_eq!(
+            record.to_json(),
+            r#"{"timestamp":1666000000000,"scheme":"s3","operation":"delete","path":"a\"b","size":null,"outcome":"failure","error_kind":"PermissionDenied","error":"denied","principal":null}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_operator_audit_sink() -> anyhow::Result<()> {
+        let log_op = Operator::new(memory::Builder::default().build()?);
+        let sink = OperatorAuditSink::new(log_op.clone(), "audit/")
+            .with_flush_interval(Duration::from_secs(3600));
+        let op =
+            Operator::new(memory::Builder::default().build()?).layer(AuditLayer::new(sink.clone()));
+
+        op.object("a").write("abc").await?;
+        op.object("b").write("abcd").await?;
+        // Nothing flushed before batch is full.
+        assert!(log_op
+            .object("audit/")
+            .list()
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?
+            .is_empty());
+
+        sink.flush().await?;
+        let entries: Vec<_> = log_op.object("audit/").list().await?.try_collect().await?;
+        assert_eq!(entries.len(), 1);
+
+        let content = String::from_utf8(entries[0].clone().into_object().read().await?)?;
+        let lines: Vec<serde_json::Value> = content
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?;
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1]["path"], "b");
+        assert_eq!(lines[1]["size"], 4);
+
+        // Flush with nothing buffered should write nothing.
+        sink.flush().await?;
+        assert_eq!(
+            log_op
+                .object("audit/")
+                .list()
+                .await?
+                .try_collect::<Vec<_>>()
+                .await?
+                .len(),
+            1
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_operator_audit_sink_flush_interval() -> anyhow::Result<()> {
+        let log_op = Operator::new(memory::Builder::default().build()?);
+        let sink = OperatorAuditSink::new(log_op.clone(), "audit/")
+            .with_flush_interval(Duration::from_secs(10));
+        let op = Operator::new(memory::Builder::default().build()?).layer(AuditLayer::new(sink));
+
+        op.object("a").write("abc").await?;
+
+        // Records will be flushed after interval without more appends.
+        let mut entries = Vec::new();
+        for _ in 0..100 {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            entries = log_op.object("audit/").list().await?.try_collect().await?;
+            if !entries.is_empty() {
+                break;
+            }
+        }
+        assert_eq!(entries.len(), 1);
+
+        Ok(())
+    }
+}
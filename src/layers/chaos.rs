@@ -0,0 +1,641 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Context;
+use std::task::Poll;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use futures::ready;
+use futures::AsyncRead;
+use rand::rngs::StdRng;
+use rand::Rng;
+use rand::SeedableRng;
+
+use super::util::set_accessor_for_object_iterator;
+use super::util::set_accessor_for_object_steamer;
+use crate::error::ObjectError;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpRead;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::Operation;
+use crate::Accessor;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::Layer;
+use crate::ObjectIterator;
+use crate::ObjectMetadata;
+use crate::ObjectPart;
+use crate::ObjectStreamer;
+
+/// ChaosLayer will inject faults into operations for resilience testing.
+///
+/// Faults are described by [`ChaosRule`] and matched by path prefix, the
+/// longest matched prefix wins. All randomness comes from the given seed,
+/// so the same sequence of operations will meet the same faults.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::ErrorKind;
+///
+/// use anyhow::Result;
+/// use opendal::layers::ChaosLayer;
+/// use opendal::layers::ChaosRule;
+/// use opendal::ops::Operation;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// let _ = Operator::from_env(Scheme::Memory)
+///     .expect("must init")
+///     .layer(
+///         ChaosLayer::new(42)
+///             .with_rule(
+///                 "",
+///                 ChaosRule::new().with_error(Operation::Read, 0.1, ErrorKind::Interrupted),
+///             )
+///             .with_rule("flaky/", ChaosRule::new().with_read_truncate(0.5)),
+///     );
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChaosLayer {
+    seed: u64,
+    rules: Vec<(String, ChaosRule)>,
+}
+
+impl ChaosLayer {
+    /// Create a new ChaosLayer with seed.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rules: Vec::new(),
+        }
+    }
+
+    /// Apply rule to paths start with prefix, use `""` to match all paths.
+    pub fn with_rule(mut self, prefix: &str, rule: ChaosRule) -> Self {
+        self.rules.push((prefix.to_string(), rule));
+        self
+    }
+}
+
+impl Layer for ChaosLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        let mut rules = self.rules.clone();
+        // Make sure the longest prefix will be matched first.
+        rules.sort_by_key(|(p, _)| Reverse(p.len()));
+
+        Arc::new(ChaosAccessor {
+            inner,
+            rules: Arc::new(rules),
+            rng: Arc::new(Mutex::new(StdRng::seed_from_u64(self.seed))),
+        })
+    }
+}
+
+/// ChaosRule describes faults to inject.
+///
+/// All probabilities are in `[0.0, 1.0]`.
+#[derive(Debug, Clone, Default)]
+pub struct ChaosRule {
+    errors: HashMap<Operation, (f64, ErrorKind)>,
+    latency: Option<(f64, Duration)>,
+    read_truncate: f64,
+    read_corrupt: f64,
+    write_fail: f64,
+}
+
+impl ChaosRule {
+    /// Create a new rule without any fault.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fail operation with given error kind.
+    pub fn with_error(mut self, op: Operation, probability: f64, kind: ErrorKind) -> Self {
+        self.errors.insert(op, (probability, kind));
+        self
+    }
+
+    /// Add latency to all operations.
+    pub fn with_latency(mut self, probability: f64, latency: Duration) -> Self {
+        self.latency = Some((probability, latency));
+        self
+    }
+
+    /// End read streams early at a random position without error.
+    pub fn with_read_truncate(mut self, probability: f64) -> Self {
+        self.read_truncate = probability;
+        self
+    }
+
+    /// Flip one byte at a random position of read streams.
+    pub fn with_read_corrupt(mut self, probability: f64) -> Self {
+        self.read_corrupt = probability;
+        self
+    }
+
+    /// Fail write with `Interrupted` after consuming part of input.
+    pub fn with_write_fail(mut self, probability: f64) -> Self {
+        self.write_fail = probability;
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ChaosAccessor {
+    inner: Arc<dyn Accessor>,
+    rules: Arc<Vec<(String, ChaosRule)>>,
+    rng: Arc<Mutex<StdRng>>,
+}
+
+impl ChaosAccessor {
+    fn rule(&self, path: &str) -> Option<&ChaosRule> {
+        self.rules
+            .iter()
+            .find(|(prefix, _)| path.starts_with(prefix))
+            .map(|(_, rule)| rule)
+    }
+
+    /// Returns `true` with probability.
+    fn hit(&self, probability: f64) -> bool {
+        probability > 0.0
+            && self
+                .rng
+                .lock()
+                .expect("lock must succeed")
+                .gen_bool(probability.min(1.0))
+    }
+
+    /// Pick a position in `[0, size)`, returns `0` for empty content.
+    fn position(&self, size: u64) -> u64 {
+        if size == 0 {
+            return 0;
+        }
+        self.rng
+            .lock()
+            .expect("lock must succeed")
+            .gen_range(0..size)
+    }
+
+    /// Decide the latency and error to inject before operation.
+    fn plan(&self, op: Operation, path: &str) -> (Option<Duration>, Result<()>) {
+        let rule = match self.rule(path) {
+            Some(rule) => rule,
+            None => return (None, Ok(())),
+        };
+
+        let latency = match rule.latency {
+            Some((probability, latency)) if self.hit(probability) => Some(latency),
+            _ => None,
+        };
+        let res = match rule.errors.get(&op) {
+            Some((probability, kind)) if self.hit(*probability) => Err(Error::new(
+                *kind,
+                ObjectError::new(op, path, anyhow!("error injected by chaos layer")),
+            )),
+            _ => Ok(()),
+        };
+        (latency, res)
+    }
+
+    async fn inject(&self, op: Operation, path: &str) -> Result<()> {
+        let (latency, res) = self.plan(op, path);
+        if let Some(latency) = latency {
+            tokio::time::sleep(latency).await;
+        }
+        res
+    }
+
+    fn blocking_inject(&self, op: Operation, path: &str) -> Result<()> {
+        let (latency, res) = self.plan(op, path);
+        if let Some(latency) = latency {
+            thread::sleep(latency);
+        }
+        res
+    }
+
+    /// Decide whether to truncate or corrupt read streams.
+    fn plan_read(&self, path: &str) -> (bool, bool) {
+        match self.rule(path) {
+            Some(rule) => (self.hit(rule.read_truncate), self.hit(rule.read_corrupt)),
+            None => (false, false),
+        }
+    }
+
+    /// Pick positions for planned faults, `size` is the length of stream.
+    fn read_state(&self, op: Operation, path: &str, plan: (bool, bool), size: u64) -> ChaosState {
+        let mut state = ChaosState::new(op, path);
+        if plan.0 {
+            state.truncate_at = Some(self.position(size));
+        }
+        if plan.1 {
+            state.corrupt_at = Some(self.position(size));
+        }
+        state
+    }
+
+    /// Plan faults for write input.
+    fn plan_write(&self, op: Operation, path: &str, size: u64) -> ChaosState {
+        let mut state = ChaosState::new(op, path);
+        if let Some(rule) = self.rule(path) {
+            if self.hit(rule.write_fail) {
+                state.fail_at = Some(self.position(size));
+            }
+        }
+        state
+    }
+
+    /// Returns the size of stream that will be read by args.
+    fn read_size(args: &OpRead, content_length: u64) -> u64 {
+        match (args.offset(), args.size()) {
+            (_, Some(size)) => size,
+            (Some(offset), None) => content_length.saturating_sub(offset),
+            (None, None) => content_length,
+        }
+    }
+}
+
+#[async_trait]
+impl Accessor for ChaosAccessor {
+    fn inner(&self) -> Option<Arc<dyn Accessor>> {
+        Some(self.inner.clone())
+    }
+
+    fn metadata(&self) -> AccessorMetadata {
+        self.inner.metadata()
+    }
+
+    async fn create(&self, path: &str, args: OpCreate) -> Result<()> {
+        self.inject(Operation::Create, path).await?;
+
+        self.inner.create(path, args).await
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        self.inject(Operation::Read, path).await?;
+
+        let plan = self.plan_read(path);
+        // Fetch content length only when we need to pick a position.
+        let size = if (plan.0 || plan.1) && args.size().is_none() {
+            let content_length = self
+                .inner
+                .stat(path, OpStat::new())
+                .await
+                .map(|meta| meta.content_length())
+                .unwrap_or_default();
+            Self::read_size(&args, content_length)
+        } else {
+            args.size().unwrap_or_default()
+        };
+        let state = self.read_state(Operation::Read, path, plan, size);
+
+        self.inner
+            .read(path, args)
+            .await
+            .map(|r| Box::new(ChaosReader { inner: r, state }) as BytesReader)
+    }
+
+    async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<u64> {
+        self.inject(Operation::Write, path).await?;
+
+        let state = self.plan_write(Operation::Write, path, args.size());
+        self.inner
+            .write(path, args, Box::new(ChaosReader { inner: r, state }))
+            .await
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
+        self.inject(Operation::Stat, path).await?;
+
+        self.inner.stat(path, args).await
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<()> {
+        self.inject(Operation::Delete, path).await?;
+
+        self.inner.delete(path, args).await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<ObjectStreamer> {
+        self.inject(Operation::List, path).await?;
+
+        self.inner
+            .list(path, args)
+            .await
+            .map(|s| set_accessor_for_object_steamer(s, self.clone()))
+    }
+
+    async fn create_multipart(&self, path: &str, args: OpCreateMultipart) -> Result<String> {
+        self.inject(Operation::CreateMultipart, path).await?;
+
+        self.inner.create_multipart(path, args).await
+    }
+
+    async fn write_multipart(
+        &self,
+        path: &str,
+        args: OpWriteMultipart,
+        r: BytesReader,
+    ) -> Result<ObjectPart> {
+        self.inject(Operation::WriteMultipart, path).await?;
+
+        let state = self.plan_write(Operation::WriteMultipart, path, args.size());
+        self.inner
+            .write_multipart(path, args, Box::new(ChaosReader { inner: r, state }))
+            .await
+    }
+
+    async fn complete_multipart(&self, path: &str, args: OpCompleteMultipart) -> Result<()> {
+        self.inject(Operation::CompleteMultipart, path).await?;
+
+        self.inner.complete_multipart(path, args).await
+    }
+
+    async fn abort_multipart(&self, path: &str, args: OpAbortMultipart) -> Result<()> {
+        self.inject(Operation::AbortMultipart, path).await?;
+
+        self.inner.abort_multipart(path, args).await
+    }
+
+    fn blocking_create(&self, path: &str, args: OpCreate) -> Result<()> {
+        self.blocking_inject(Operation::BlockingCreate, path)?;
+
+        self.inner.blocking_create(path, args)
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<BlockingBytesReader> {
+        self.blocking_inject(Operation::BlockingRead, path)?;
+
+        let plan = self.plan_read(path);
+        let size = if (plan.0 || plan.1) && args.size().is_none() {
+            let content_length = self
+                .inner
+                .blocking_stat(path, OpStat::new())
+                .map(|meta| meta.content_length())
+                .unwrap_or_default();
+            Self::read_size(&args, content_length)
+        } else {
+            args.size().unwrap_or_default()
+        };
+        let state = self.read_state(Operation::BlockingRead, path, plan, size);
+
+        self.inner
+            .blocking_read(path, args)
+            .map(|r| Box::new(ChaosReader { inner: r, state }) as BlockingBytesReader)
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite, r: BlockingBytesReader) -> Result<u64> {
+        self.blocking_inject(Operation::BlockingWrite, path)?;
+
+        let state = self.plan_write(Operation::BlockingWrite, path, args.size());
+        self.inner
+            .blocking_write(path, args, Box::new(ChaosReader { inner: r, state }))
+    }
+
+    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
+        self.blocking_inject(Operation::BlockingStat, path)?;
+
+        self.inner.blocking_stat(path, args)
+    }
+
+    fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<()> {
+        self.blocking_inject(Operation::BlockingDelete, path)?;
+
+        self.inner.blocking_delete(path, args)
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<ObjectIterator> {
+        self.blocking_inject(Operation::BlockingList, path)?;
+
+        self.inner
+            .blocking_list(path, args)
+            .map(|it| set_accessor_for_object_iterator(it, self.clone()))
+    }
+}
+
+/// ChaosState tracks the position of a stream and the faults planned on it.
+struct ChaosState {
+    op: Operation,
+    path: String,
+    pos: u64,
+    /// Stream will reach EOF at this position.
+    truncate_at: Option<u64>,
+    /// Byte at this position will be flipped.
+    corrupt_at: Option<u64>,
+    /// Stream will return error at this position.
+    fail_at: Option<u64>,
+}
+
+impl ChaosState {
+    fn new(op: Operation, path: &str) -> Self {
+        Self {
+            op,
+            path: path.to_string(),
+            pos: 0,
+            truncate_at: None,
+            corrupt_at: None,
+            fail_at: None,
+        }
+    }
+
+    /// Returns the max bytes could be read next, `Ok(0)` means EOF.
+    fn limit(&self, len: usize) -> Result<usize> {
+        if let Some(fail_at) = self.fail_at {
+            if self.pos >= fail_at {
+                return Err(Error::new(
+                    ErrorKind::Interrupted,
+                    ObjectError::new(
+                        self.op,
+                        &self.path,
+                        anyhow!("stream failed by chaos layer at {}", self.pos),
+                    ),
+                ));
+            }
+        }
+
+        let end = match (self.truncate_at, self.fail_at) {
+            (Some(a), Some(b)) => a.min(b),
+            (Some(v), None) | (None, Some(v)) => v,
+            (None, None) => return Ok(len),
+        };
+        Ok(len.min(end.saturating_sub(self.pos) as usize))
+    }
+
+    /// Apply faults on bytes that have been read into `buf`.
+    fn advance(&mut self, buf: &mut [u8]) {
+        if let Some(corrupt_at) = self.corrupt_at {
+            let end = self.pos + buf.len() as u64;
+            if (self.pos..end).contains(&corrupt_at) {
+                buf[(corrupt_at - self.pos) as usize] ^= 0xff;
+            }
+        }
+        self.pos += buf.len() as u64;
+    }
+}
+
+struct ChaosReader<R> {
+    inner: R,
+    state: ChaosState,
+}
+
+impl AsyncRead for ChaosReader<BytesReader> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        let limit = self.state.limit(buf.len())?;
+        if limit == 0 && !buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        let n = ready!(Pin::new(&mut (*self.inner)).poll_read(cx, &mut buf[..limit]))?;
+        self.state.advance(&mut buf[..n]);
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl Read for ChaosReader<BlockingBytesReader> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let limit = self.state.limit(buf.len())?;
+        if limit == 0 && !buf.is_empty() {
+            return Ok(0);
+        }
+
+        let n = self.inner.read(&mut buf[..limit])?;
+        self.state.advance(&mut buf[..n]);
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::memory;
+    use crate::Operator;
+
+    fn new_operator(layer: ChaosLayer) -> anyhow::Result<Operator> {
+        Ok(Operator::new(memory::Builder::default().build()?).layer(layer))
+    }
+
+    #[tokio::test]
+    async fn test_chaos_error() -> anyhow::Result<()> {
+        let op = new_operator(ChaosLayer::new(0).with_rule(
+            "fail/",
+            ChaosRule::new().with_error(Operation::Stat, 1.0, ErrorKind::Interrupted),
+        ))?;
+
+        op.object("fail/test").write("Hello").await?;
+        op.object("ok/test").write("Hello").await?;
+
+        let err = op.object("fail/test").metadata().await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Interrupted);
+        assert!(op.object("ok/test").metadata().await.is_ok());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_chaos_deterministic() -> anyhow::Result<()> {
+        let layer = ChaosLayer::new(42).with_rule(
+            "",
+            ChaosRule::new().with_error(Operation::Stat, 0.5, ErrorKind::Interrupted),
+        );
+
+        let mut results = Vec::new();
+        for _ in 0..2 {
+            let op = new_operator(layer.clone())?;
+            op.object("test").write("Hello").await?;
+
+            let mut result = Vec::new();
+            for _ in 0..32 {
+                result.push(op.object("test").metadata().await.is_ok());
+            }
+            results.push(result);
+        }
+
+        assert_eq!(results[0], results[1]);
+        assert!(results[0].contains(&true));
+        assert!(results[0].contains(&false));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_chaos_read() -> anyhow::Result<()> {
+        let content: Vec<u8> = (0..=255).cycle().take(4096).collect();
+
+        let op = new_operator(
+            ChaosLayer::new(0)
+                .with_rule("truncate/", ChaosRule::new().with_read_truncate(1.0))
+                .with_rule("corrupt/", ChaosRule::new().with_read_corrupt(1.0)),
+        )?;
+        op.object("truncate/test").write(content.clone()).await?;
+        op.object("corrupt/test").write(content.clone()).await?;
+
+        let bs = op.object("truncate/test").read().await?;
+        assert!(bs.len() < content.len());
+        assert_eq!(bs, content[..bs.len()]);
+
+        let bs = op.object("corrupt/test").read().await?;
+        assert_eq!(bs.len(), content.len());
+        let diff = bs.iter().zip(content.iter()).filter(|(a, b)| a != b);
+        assert_eq!(diff.count(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_chaos_write() -> anyhow::Result<()> {
+        let op = new_operator(
+            ChaosLayer::new(0).with_rule("fail/", ChaosRule::new().with_write_fail(1.0)),
+        )?;
+
+        let content = vec![1; 4096];
+        let err = op
+            .object("fail/test")
+            .write(content.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Interrupted);
+        // Services may create the object before consuming input, but the
+        // complete content must never be committed.
+        if let Ok(bs) = op.object("fail/test").read().await {
+            assert_ne!(bs, content);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,777 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use futures::io;
+use futures::io::Cursor;
+use futures::ready;
+use futures::AsyncReadExt;
+use futures::Stream;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::error::new_other_object_error;
+use crate::error::new_unsupported_object_error;
+use crate::error::ObjectError;
+use crate::io_util::CompressAlgorithm;
+use crate::io_util::CompressEncoder;
+use crate::io_util::CompressReader;
+use crate::io_util::CompressState;
+use crate::io_util::DecompressDecoder;
+use crate::io_util::DecompressReader;
+use crate::io_util::DecompressState;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpPresign;
+use crate::ops::OpRead;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::Operation;
+use crate::ops::PresignedRequest;
+use crate::Accessor;
+use crate::AccessorCapability;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::Layer;
+use crate::ObjectEntry;
+use crate::ObjectIterator;
+use crate::ObjectMetadata;
+use crate::ObjectPart;
+use crate::ObjectStreamer;
+
+/// Suffix of the sidecar object which carries compress metadata.
+const COMPRESS_META_SUFFIX: &str = ".opendal-compress";
+
+/// CompressionLayer will compress data while writing and decompress
+/// data while reading transparently.
+///
+/// # Notes
+///
+/// The codec and the uncompressed length of every object are recorded in
+/// a sidecar object named `<path>.opendal-compress`:
+///
+/// - `stat` will report the uncompressed length.
+/// - `list` will hide all sidecar objects.
+/// - Objects without sidecar will be read as is, so it's safe to enable
+///   this layer on existing data.
+///
+/// The size of compressed content can't be known before compressing while
+/// `write` requires it. So we will compress the whole content into memory
+/// before writing. Ranged reads will decode from the start of the object
+/// and skip the data before the range.
+///
+/// `presign` and multipart operations can't be compressed transparently,
+/// so they are not supported by this layer.
+///
+/// # Examples
+///
+/// ```
+/// use anyhow::Result;
+/// use opendal::io_util::CompressAlgorithm;
+/// use opendal::layers::CompressionLayer;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// let _ = Operator::from_env(Scheme::Fs)
+///     .expect("must init")
+///     .layer(CompressionLayer::new(CompressAlgorithm::Zstd));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CompressionLayer {
+    algo: CompressAlgorithm,
+}
+
+impl CompressionLayer {
+    /// Create a new compression layer with given compress algorithm.
+    pub fn new(algo: CompressAlgorithm) -> Self {
+        Self { algo }
+    }
+}
+
+impl Layer for CompressionLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(CompressionAccessor {
+            algo: self.algo,
+            inner,
+        })
+    }
+}
+
+/// CompressMeta is the content of sidecar object.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CompressMeta {
+    /// Extension of the compress algorithm, like `gz` and `zstd`.
+    algorithm: String,
+    /// Uncompressed length of the object.
+    content_length: u64,
+}
+
+impl CompressMeta {
+    fn new(algo: CompressAlgorithm, content_length: u64) -> Self {
+        Self {
+            algorithm: algo.extension().to_string(),
+            content_length,
+        }
+    }
+
+    fn algorithm(&self, op: Operation, path: &str) -> Result<CompressAlgorithm> {
+        CompressAlgorithm::from_extension(&self.algorithm).ok_or_else(|| {
+            new_other_object_error(
+                op,
+                path,
+                anyhow!("unsupported compress algorithm: {}", self.algorithm),
+            )
+        })
+    }
+
+    /// Calculate the `(offset, size)` of uncompressed content for given read args.
+    fn range(&self, args: &OpRead) -> (u64, u64) {
+        let total = self.content_length;
+        match (args.offset(), args.size()) {
+            (Some(offset), Some(size)) => {
+                let offset = offset.min(total);
+                (offset, size.min(total - offset))
+            }
+            (Some(offset), None) => {
+                let offset = offset.min(total);
+                (offset, total - offset)
+            }
+            (None, Some(size)) => {
+                let size = size.min(total);
+                (total - size, size)
+            }
+            (None, None) => (0, total),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CompressionAccessor {
+    algo: CompressAlgorithm,
+    inner: Arc<dyn Accessor>,
+}
+
+impl CompressionAccessor {
+    fn meta_path(path: &str) -> String {
+        format!("{path}{COMPRESS_META_SUFFIX}")
+    }
+
+    fn encode_meta(
+        op: Operation,
+        path: &str,
+        size: u64,
+        algo: CompressAlgorithm,
+    ) -> Result<Vec<u8>> {
+        serde_json::to_vec(&CompressMeta::new(algo, size))
+            .map_err(|err| new_other_object_error(op, path, err))
+    }
+
+    fn decode_meta(op: Operation, path: &str, bs: &[u8]) -> Result<CompressMeta> {
+        serde_json::from_slice(bs).map_err(|err| new_other_object_error(op, path, err))
+    }
+
+    async fn read_meta(&self, op: Operation, path: &str) -> Result<Option<CompressMeta>> {
+        if path.ends_with('/') {
+            return Ok(None);
+        }
+
+        match self
+            .inner
+            .read(&Self::meta_path(path), OpRead::new(..))
+            .await
+        {
+            Ok(r) => {
+                let mut bs = Cursor::new(Vec::with_capacity(64));
+                io::copy(r, &mut bs).await?;
+                Ok(Some(Self::decode_meta(op, path, &bs.into_inner())?))
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn blocking_read_meta(&self, op: Operation, path: &str) -> Result<Option<CompressMeta>> {
+        if path.ends_with('/') {
+            return Ok(None);
+        }
+
+        match self
+            .inner
+            .blocking_read(&Self::meta_path(path), OpRead::new(..))
+        {
+            Ok(mut r) => {
+                let mut bs = Vec::with_capacity(64);
+                r.read_to_end(&mut bs)?;
+                Ok(Some(Self::decode_meta(op, path, &bs)?))
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[async_trait]
+impl Accessor for CompressionAccessor {
+    fn inner(&self) -> Option<Arc<dyn Accessor>> {
+        Some(self.inner.clone())
+    }
+
+    fn metadata(&self) -> AccessorMetadata {
+        let mut meta = self.inner.metadata();
+        meta.set_capabilities(
+            meta.capabilities() - AccessorCapability::Presign - AccessorCapability::Multipart,
+        );
+        meta
+    }
+
+    async fn create(&self, path: &str, args: OpCreate) -> Result<()> {
+        self.inner.create(path, args).await?;
+
+        // Empty file created by `create` is not compressed.
+        if !path.ends_with('/') {
+            self.inner
+                .delete(&Self::meta_path(path), OpDelete::new())
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        let meta = match self.read_meta(Operation::Read, path).await? {
+            Some(meta) => meta,
+            None => return self.inner.read(path, args).await,
+        };
+        let algo = meta.algorithm(Operation::Read, path)?;
+        let (offset, size) = meta.range(&args);
+
+        let r = self.inner.read(path, OpRead::new(..)).await?;
+        let mut r = DecompressReader::new(r, algo);
+        // Skip all data before the range.
+        let skipped = io::copy((&mut r).take(offset), &mut io::sink()).await?;
+        if skipped != offset {
+            return Err(new_stale_meta_error(
+                Operation::Read,
+                path,
+                skipped,
+                meta.content_length,
+            ));
+        }
+
+        Ok(Box::new(CompressionReader {
+            inner: r.take(size),
+            path: path.to_string(),
+            remaining: size,
+            content_length: meta.content_length,
+        }))
+    }
+
+    async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<u64> {
+        let size = args.size();
+
+        let mut bs = Cursor::new(Vec::new());
+        io::copy(CompressReader::new(r, self.algo), &mut bs).await?;
+        let bs = bs.into_inner();
+
+        let mut op = OpWrite::new(bs.len() as u64);
+        if let Some(v) = args.content_type() {
+            op = op.with_content_type(v);
+        }
+        self.inner
+            .write(path, op, Box::new(Cursor::new(bs)))
+            .await?;
+
+        let meta = Self::encode_meta(Operation::Write, path, size, self.algo)?;
+        self.inner
+            .write(
+                &Self::meta_path(path),
+                OpWrite::new(meta.len() as u64),
+                Box::new(Cursor::new(meta)),
+            )
+            .await?;
+
+        Ok(size)
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
+        let meta = self.inner.stat(path, args).await?;
+
+        match self.read_meta(Operation::Stat, path).await? {
+            Some(cm) => Ok(logical_metadata(meta, cm.content_length)),
+            None => Ok(meta),
+        }
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<()> {
+        self.inner.delete(path, args).await?;
+
+        if !path.ends_with('/') {
+            self.inner
+                .delete(&Self::meta_path(path), OpDelete::new())
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<ObjectStreamer> {
+        Ok(Box::new(CompressionStreamer::new(
+            Arc::new(self.clone()),
+            self.inner.list(path, args).await?,
+        )))
+    }
+
+    fn presign(&self, path: &str, _: OpPresign) -> Result<PresignedRequest> {
+        Err(new_unsupported_object_error(Operation::Presign, path))
+    }
+
+    async fn create_multipart(&self, path: &str, _: OpCreateMultipart) -> Result<String> {
+        Err(new_unsupported_object_error(
+            Operation::CreateMultipart,
+            path,
+        ))
+    }
+
+    async fn write_multipart(
+        &self,
+        path: &str,
+        _: OpWriteMultipart,
+        _: BytesReader,
+    ) -> Result<ObjectPart> {
+        Err(new_unsupported_object_error(
+            Operation::WriteMultipart,
+            path,
+        ))
+    }
+
+    async fn complete_multipart(&self, path: &str, _: OpCompleteMultipart) -> Result<()> {
+        Err(new_unsupported_object_error(
+            Operation::CompleteMultipart,
+            path,
+        ))
+    }
+
+    async fn abort_multipart(&self, path: &str, _: OpAbortMultipart) -> Result<()> {
+        Err(new_unsupported_object_error(
+            Operation::AbortMultipart,
+            path,
+        ))
+    }
+
+    fn blocking_create(&self, path: &str, args: OpCreate) -> Result<()> {
+        self.inner.blocking_create(path, args)?;
+
+        if !path.ends_with('/') {
+            self.inner
+                .blocking_delete(&Self::meta_path(path), OpDelete::new())?;
+        }
+        Ok(())
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<BlockingBytesReader> {
+        let meta = match self.blocking_read_meta(Operation::BlockingRead, path)? {
+            Some(meta) => meta,
+            None => return self.inner.blocking_read(path, args),
+        };
+        let algo = meta.algorithm(Operation::BlockingRead, path)?;
+        let (offset, size) = meta.range(&args);
+
+        let mut r = self.inner.blocking_read(path, OpRead::new(..))?;
+        let mut bs = Vec::new();
+        r.read_to_end(&mut bs)?;
+        let bs = blocking_decompress(algo, &bs)?;
+        // The sidecar is stale if the object has been overwritten without this layer.
+        if bs.len() as u64 != meta.content_length {
+            return Err(new_stale_meta_error(
+                Operation::BlockingRead,
+                path,
+                bs.len() as u64,
+                meta.content_length,
+            ));
+        }
+
+        let start = offset as usize;
+        let end = (offset + size) as usize;
+        Ok(Box::new(std::io::Cursor::new(bs[start..end].to_vec())))
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite, mut r: BlockingBytesReader) -> Result<u64> {
+        let size = args.size();
+
+        let mut bs = Vec::with_capacity(size as usize);
+        r.read_to_end(&mut bs)?;
+        let bs = blocking_compress(self.algo, &bs)?;
+
+        let mut op = OpWrite::new(bs.len() as u64);
+        if let Some(v) = args.content_type() {
+            op = op.with_content_type(v);
+        }
+        self.inner
+            .blocking_write(path, op, Box::new(std::io::Cursor::new(bs)))?;
+
+        let meta = Self::encode_meta(Operation::BlockingWrite, path, size, self.algo)?;
+        self.inner.blocking_write(
+            &Self::meta_path(path),
+            OpWrite::new(meta.len() as u64),
+            Box::new(std::io::Cursor::new(meta)),
+        )?;
+
+        Ok(size)
+    }
+
+    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
+        let meta = self.inner.blocking_stat(path, args)?;
+
+        match self.blocking_read_meta(Operation::BlockingStat, path)? {
+            Some(cm) => Ok(logical_metadata(meta, cm.content_length)),
+            None => Ok(meta),
+        }
+    }
+
+    fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<()> {
+        self.inner.blocking_delete(path, args)?;
+
+        if !path.ends_with('/') {
+            self.inner
+                .blocking_delete(&Self::meta_path(path), OpDelete::new())?;
+        }
+        Ok(())
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<ObjectIterator> {
+        Ok(Box::new(CompressionIterator::new(
+            Arc::new(self.clone()),
+            self.inner.blocking_list(path, args)?,
+        )))
+    }
+}
+
+/// Build the metadata of uncompressed content.
+///
+/// `content_md5` is dropped because it's the md5 of compressed content.
+fn logical_metadata(meta: ObjectMetadata, content_length: u64) -> ObjectMetadata {
+    let mut m = ObjectMetadata::new(meta.mode()).with_content_length(content_length);
+    if let Some(v) = meta.content_type() {
+        m.set_content_type(v);
+    }
+    if let Some(v) = meta.last_modified() {
+        m.set_last_modified(v);
+    }
+    if let Some(v) = meta.etag() {
+        m.set_etag(v);
+    }
+    m
+}
+
+/// Hide sidecar objects and drop the compressed metadata of entries.
+fn map_entry(acc: &Arc<dyn Accessor>, mut de: ObjectEntry) -> Option<ObjectEntry> {
+    if de.path().ends_with(COMPRESS_META_SUFFIX) {
+        return None;
+    }
+
+    if de.mode().is_dir() {
+        de.set_accessor(acc.clone());
+        return Some(de);
+    }
+
+    // Content length returned by list is the compressed size, make
+    // sure users will stat via our accessor.
+    Some(ObjectEntry::new(
+        acc.clone(),
+        de.path(),
+        ObjectMetadata::new(de.mode()),
+    ))
+}
+
+fn blocking_compress(algo: CompressAlgorithm, input: &[u8]) -> Result<Vec<u8>> {
+    let mut ce = CompressEncoder::new(algo);
+    let mut output = Vec::new();
+    let mut buf = vec![0; 64 * 1024];
+    let mut read = 0;
+
+    loop {
+        match ce.state() {
+            CompressState::Reading => {
+                // Fill an empty slice to indicate EOF.
+                read += ce.fill(&input[read..]);
+            }
+            CompressState::Encoding => {
+                let n = ce.encode(&mut buf)?;
+                output.extend_from_slice(&buf[..n]);
+            }
+            CompressState::Finishing => {
+                let n = ce.finish(&mut buf)?;
+                output.extend_from_slice(&buf[..n]);
+            }
+            CompressState::Done => return Ok(output),
+        }
+    }
+}
+
+fn blocking_decompress(algo: CompressAlgorithm, input: &[u8]) -> Result<Vec<u8>> {
+    let mut de = DecompressDecoder::new(algo);
+    let mut output = Vec::new();
+    let mut buf = vec![0; 64 * 1024];
+    let mut read = 0;
+
+    loop {
+        match de.state() {
+            DecompressState::Reading => {
+                // Fill an empty slice to indicate EOF.
+                read += de.fill(&input[read..]);
+            }
+            DecompressState::Decoding => {
+                let n = de.decode(&mut buf)?;
+                output.extend_from_slice(&buf[..n]);
+            }
+            DecompressState::Flushing => {
+                let n = de.finish(&mut buf)?;
+                output.extend_from_slice(&buf[..n]);
+            }
+            DecompressState::Done => return Ok(output),
+        }
+    }
+}
+
+fn new_stale_meta_error(op: Operation, path: &str, actual: u64, expected: u64) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        ObjectError::new(
+            op,
+            path,
+            anyhow!("decompressed length {actual} mismatches the sidecar length {expected}, sidecar may be stale"),
+        ),
+    )
+}
+
+/// CompressionReader returns an error if the decompressed content ends
+/// before the expected size.
+struct CompressionReader<R> {
+    inner: R,
+    path: String,
+    remaining: u64,
+    content_length: u64,
+}
+
+impl<R: futures::AsyncRead + Unpin> futures::AsyncRead for CompressionReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        let n = ready!(Pin::new(&mut self.inner).poll_read(cx, buf))?;
+        if n == 0 && self.remaining != 0 && !buf.is_empty() {
+            return Poll::Ready(Err(new_stale_meta_error(
+                Operation::Read,
+                &self.path,
+                self.content_length - self.remaining,
+                self.content_length,
+            )));
+        }
+        self.remaining -= n as u64;
+        Poll::Ready(Ok(n))
+    }
+}
+
+struct CompressionStreamer {
+    acc: Arc<dyn Accessor>,
+    inner: ObjectStreamer,
+}
+
+impl CompressionStreamer {
+    fn new(acc: Arc<dyn Accessor>, inner: ObjectStreamer) -> Self {
+        Self { acc, inner }
+    }
+}
+
+impl Stream for CompressionStreamer {
+    type Item = Result<ObjectEntry>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match Pin::new(&mut (*self.inner)).poll_next(cx) {
+                Poll::Ready(Some(Ok(de))) => {
+                    if let Some(de) = map_entry(&self.acc, de) {
+                        return Poll::Ready(Some(Ok(de)));
+                    }
+                }
+                v => return v,
+            }
+        }
+    }
+}
+
+struct CompressionIterator {
+    acc: Arc<dyn Accessor>,
+    inner: ObjectIterator,
+}
+
+impl CompressionIterator {
+    fn new(acc: Arc<dyn Accessor>, inner: ObjectIterator) -> Self {
+        Self { acc, inner }
+    }
+}
+
+impl Iterator for CompressionIterator {
+    type Item = Result<ObjectEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.inner.next() {
+                Some(Ok(de)) => {
+                    if let Some(de) = map_entry(&self.acc, de) {
+                        return Some(Ok(de));
+                    }
+                }
+                v => return v,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::services::fs;
+    use crate::services::memory;
+    use crate::Operator;
+
+    #[tokio::test]
+    async fn test_compression() -> anyhow::Result<()> {
+        let op = Operator::new(memory::Builder::default().build()?);
+        let compressed_op = op
+            .clone()
+            .layer(CompressionLayer::new(CompressAlgorithm::Zstd));
+
+        let content = "Hello, World!".repeat(1024).into_bytes();
+        compressed_op.object("test").write(content.clone()).await?;
+
+        // Data stored in underlying storage should be compressed.
+        let raw = op.object("test").read().await?;
+        assert!(raw.len() < content.len());
+        let bs = op
+            .object("test")
+            .decompress_read_with(CompressAlgorithm::Zstd)
+            .await?;
+        assert_eq!(bs, content);
+
+        // Stat should report the uncompressed length.
+        let meta = compressed_op.object("test").metadata().await?;
+        assert_eq!(meta.content_length(), content.len() as u64);
+
+        // Read should be decompressed.
+        let bs = compressed_op.object("test").read().await?;
+        assert_eq!(bs, content);
+        let bs = compressed_op.object("test").range_read(13..26).await?;
+        assert_eq!(bs, &content[13..26]);
+        let bs = compressed_op.object("test").range_read(..13).await?;
+        assert_eq!(bs, &content[..13]);
+
+        // List should hide the sidecar object.
+        let entries: Vec<_> = compressed_op
+            .object("/")
+            .list()
+            .await?
+            .try_collect()
+            .await?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path(), "test");
+        assert_eq!(entries[0].content_length().await, content.len() as u64);
+
+        // Delete should remove the sidecar object too.
+        compressed_op.object("test").delete().await?;
+        let entries: Vec<_> = op.object("/").list().await?.try_collect().await?;
+        assert!(entries.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_compression_read_uncompressed() -> anyhow::Result<()> {
+        let op = Operator::new(memory::Builder::default().build()?);
+        let compressed_op = op
+            .clone()
+            .layer(CompressionLayer::new(CompressAlgorithm::Gzip));
+
+        // Objects written without the layer should be read as is.
+        op.object("test").write("Hello, World!").await?;
+        let bs = compressed_op.object("test").read().await?;
+        assert_eq!(bs, b"Hello, World!");
+        let meta = compressed_op.object("test").metadata().await?;
+        assert_eq!(meta.content_length(), 13);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_compression_stale_meta() -> anyhow::Result<()> {
+        // Use fs here since memory doesn't support blocking operations.
+        let root = std::env::temp_dir().join(format!("opendal-compression-{}", Uuid::new_v4()));
+        let mut builder = fs::Builder::default();
+        builder.root(&root.to_string_lossy());
+        let op = Operator::new(builder.build()?);
+        let compressed_op = op
+            .clone()
+            .layer(CompressionLayer::new(CompressAlgorithm::Zstd));
+
+        compressed_op
+            .object("test")
+            .write("Hello, World!".repeat(1024))
+            .await?;
+        // Overwrite the object without the layer, the sidecar becomes stale.
+        op.object("test")
+            .compress_write_with(CompressAlgorithm::Zstd, Cursor::new(b"Hello".to_vec()))
+            .await?;
+
+        let err = compressed_op
+            .object("test")
+            .read()
+            .await
+            .expect_err("read must fail");
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        let err = compressed_op
+            .object("test")
+            .range_read(13..26)
+            .await
+            .expect_err("range read must fail");
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        let err = compressed_op
+            .object("test")
+            .blocking_range_read(13..26)
+            .expect_err("blocking read must fail");
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+        std::fs::remove_dir_all(root)?;
+        Ok(())
+    }
+}
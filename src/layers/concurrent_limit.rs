@@ -21,6 +21,7 @@ use std::task::Context;
 use std::task::Poll;
 
 use async_trait::async_trait;
+use bytes::Bytes;
 use futures::AsyncRead;
 use tokio::sync::OwnedSemaphorePermit;
 use tokio::sync::Semaphore;
@@ -35,6 +36,7 @@ use crate::ops::OpDelete;
 use crate::ops::OpList;
 use crate::ops::OpPresign;
 use crate::ops::OpRead;
+use crate::ops::OpReadRanges;
 use crate::ops::OpStat;
 use crate::ops::OpWrite;
 use crate::ops::OpWriteMultipart;
@@ -43,6 +45,7 @@ use crate::Accessor;
 use crate::AccessorMetadata;
 use crate::BlockingBytesReader;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::Layer;
 use crate::ObjectEntry;
 use crate::ObjectIterator;
@@ -134,6 +137,40 @@ impl Accessor for ConcurrentLimitAccessor {
         self.inner.write(path, args, r).await
     }
 
+    async fn read_stream(&self, path: &str, args: OpRead) -> Result<BytesStreamer> {
+        let permit = self
+            .semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("semaphore must be valid");
+
+        self.inner
+            .read_stream(path, args)
+            .await
+            .map(|s| Box::new(ConcurrentLimitBytesStreamer::new(s, permit)) as BytesStreamer)
+    }
+
+    async fn read_ranges(&self, path: &str, args: OpReadRanges) -> Result<Vec<Bytes>> {
+        let _permit = self
+            .semaphore
+            .acquire()
+            .await
+            .expect("semaphore must be valid");
+
+        self.inner.read_ranges(path, args).await
+    }
+
+    async fn write_stream(&self, path: &str, args: OpWrite, s: BytesStreamer) -> Result<u64> {
+        let _permit = self
+            .semaphore
+            .acquire()
+            .await
+            .expect("semaphore must be valid");
+
+        self.inner.write_stream(path, args, s).await
+    }
+
     async fn stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
         let _permit = self
             .semaphore
@@ -328,6 +365,30 @@ impl Read for BlockingConcurrentLimitReader {
     }
 }
 
+struct ConcurrentLimitBytesStreamer {
+    inner: BytesStreamer,
+
+    // Hold on this permit until this streamer has been dropped.
+    _permit: OwnedSemaphorePermit,
+}
+
+impl ConcurrentLimitBytesStreamer {
+    fn new(inner: BytesStreamer, permit: OwnedSemaphorePermit) -> Self {
+        Self {
+            inner,
+            _permit: permit,
+        }
+    }
+}
+
+impl futures::Stream for ConcurrentLimitBytesStreamer {
+    type Item = Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut (*self.inner)).poll_next(cx)
+    }
+}
+
 struct ConcurrentLimitStreamer {
     inner: ObjectStreamer,
 
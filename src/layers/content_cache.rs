@@ -12,15 +12,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp::min;
 use std::fmt::Debug;
+use std::io::Error;
 use std::io::ErrorKind;
+use std::io::Read;
 use std::io::Result;
+use std::ops::Range;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use bytes::Bytes;
+use futures::io::Cursor;
+use futures::stream;
+use futures::AsyncReadExt;
+use futures::StreamExt;
+use futures::TryStreamExt;
 
 use super::util::set_accessor_for_object_iterator;
 use super::util::set_accessor_for_object_steamer;
+use crate::io_util::into_reader;
 use crate::ops::OpCreate;
 use crate::ops::OpDelete;
 use crate::ops::OpList;
@@ -32,10 +43,50 @@ use crate::BlockingBytesReader;
 use crate::BytesReader;
 use crate::Layer;
 use crate::ObjectIterator;
+use crate::ObjectMetadata;
 use crate::ObjectStreamer;
 
+/// Max blocks fetched from inner by a single read.
+const MAX_FETCH_BLOCKS: u64 = 16;
+
+/// Strategy of [`ContentCacheLayer`] to fill the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentCacheStrategy {
+    /// Fetch and cache the whole object on the first read.
+    Whole,
+    /// Cache fixed-size aligned blocks of the given size, only the missing
+    /// blocks of requested range will be fetched.
+    ///
+    /// Blocks are keyed by path, etag (or last modified time and size if
+    /// etag is absent) and block index, so that blocks of different
+    /// versions will never be mixed. Blocks of all versions will be removed
+    /// while writing or deleting the path through this layer if the cache
+    /// supports `list`, otherwise they are left to the eviction of cache.
+    Block(u64),
+}
+
+/// Policy of [`ContentCacheLayer`] while writing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentCacheWritePolicy {
+    /// Remove cached content of the path.
+    Invalidate,
+    /// Fill the cache with written content.
+    ///
+    /// Content will be buffered in memory while writing.
+    Through,
+}
+
 /// ContentCacheLayer will add content data cache support for OpenDAL.
 ///
+/// # Strategy
+///
+/// By default, the whole object will be cached on the first read. Use
+/// [`ContentCacheStrategy::Block`] to cache aligned blocks instead, which
+/// fits for large objects that are read by ranges like parquet files.
+///
+/// Objects larger than [`ContentCacheLayer::with_max_size`] will not be
+/// cached.
+///
 /// # Notes
 ///
 /// This layer only maintains its own states. Users should care about the cache
@@ -49,24 +100,35 @@ use crate::ObjectStreamer;
 /// To make sure content cache consistent across the cluster, please make sure
 /// all nodes in the cluster use the same cache services like redis or tikv.
 ///
+/// Blocks of out-dated versions will not be removed from the cache, please
+/// use a cache service with eviction for [`ContentCacheStrategy::Block`].
+///
 /// # Examples
 ///
 /// ```
 /// use anyhow::Result;
 /// use opendal::layers::ContentCacheLayer;
+/// use opendal::layers::ContentCacheStrategy;
+/// use opendal::layers::ContentCacheWritePolicy;
 /// use opendal::services::memory;
 /// use opendal::Operator;
 /// use opendal::Scheme;
 ///
 /// let _ = Operator::from_env(Scheme::Fs)
 ///     .expect("must init")
-///     .layer(ContentCacheLayer::new(
-///         memory::Builder::default().build().expect("must init"),
-///     ));
+///     .layer(
+///         ContentCacheLayer::new(memory::Builder::default().build().expect("must init"))
+///             .with_strategy(ContentCacheStrategy::Block(4 * 1024 * 1024))
+///             .with_write_policy(ContentCacheWritePolicy::Through)
+///             .with_max_size(64 * 1024 * 1024 * 1024),
+///     );
 /// ```
 #[derive(Debug, Clone)]
 pub struct ContentCacheLayer {
     cache: Arc<dyn Accessor>,
+    strategy: ContentCacheStrategy,
+    write_policy: ContentCacheWritePolicy,
+    max_size: u64,
 }
 
 impl ContentCacheLayer {
@@ -74,8 +136,39 @@ impl ContentCacheLayer {
     pub fn new(acc: impl Accessor + 'static) -> Self {
         Self {
             cache: Arc::new(acc),
+            strategy: ContentCacheStrategy::Whole,
+            write_policy: ContentCacheWritePolicy::Invalidate,
+            max_size: u64::MAX,
         }
     }
+
+    /// Set strategy to fill the cache, default to
+    /// [`ContentCacheStrategy::Whole`].
+    ///
+    /// # Panics
+    ///
+    /// Block size must be larger than `0`.
+    pub fn with_strategy(mut self, strategy: ContentCacheStrategy) -> Self {
+        if let ContentCacheStrategy::Block(size) = strategy {
+            assert!(size > 0, "block size must be larger than 0");
+        }
+        self.strategy = strategy;
+        self
+    }
+
+    /// Set policy while writing, default to
+    /// [`ContentCacheWritePolicy::Invalidate`].
+    pub fn with_write_policy(mut self, policy: ContentCacheWritePolicy) -> Self {
+        self.write_policy = policy;
+        self
+    }
+
+    /// Set max size of objects to cache, larger objects will be read from
+    /// inner directly.
+    pub fn with_max_size(mut self, size: u64) -> Self {
+        self.max_size = size;
+        self
+    }
 }
 
 impl Layer for ContentCacheLayer {
@@ -83,33 +176,165 @@ impl Layer for ContentCacheLayer {
         Arc::new(ContentCacheAccessor {
             cache: self.cache.clone(),
             inner,
+            strategy: self.strategy,
+            write_policy: self.write_policy,
+            max_size: self.max_size,
         })
     }
 }
 
+/// Build the version of object content to key blocks.
+fn content_version(meta: &ObjectMetadata) -> String {
+    match (meta.etag(), meta.last_modified()) {
+        (Some(etag), _) => etag
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+            .collect(),
+        (None, Some(t)) => format!("{}-{}", t.unix_timestamp_nanos(), meta.content_length()),
+        (None, None) => meta.content_length().to_string(),
+    }
+}
+
+/// Blocks of an object in specified version.
+#[derive(Debug, Clone)]
+struct Blocks {
+    path: String,
+    version: String,
+    /// Size of every block.
+    size: u64,
+    /// Size of the object.
+    total: u64,
+}
+
+impl Blocks {
+    fn new(path: &str, meta: &ObjectMetadata, size: u64) -> Self {
+        Self {
+            path: path.to_string(),
+            version: content_version(meta),
+            size,
+            total: meta.content_length(),
+        }
+    }
+
+    /// Dir of blocks in all versions of the object.
+    fn dir(path: &str) -> String {
+        format!(".blocks/{path}/")
+    }
+
+    fn key(&self, idx: u64) -> String {
+        format!("{}{}/{idx}", Self::dir(&self.path), self.version)
+    }
+
+    /// Range of content in blocks `lo..=hi`.
+    fn range(&self, lo: u64, hi: u64) -> Range<u64> {
+        lo * self.size..min((hi + 1) * self.size, self.total)
+    }
+}
+
+/// Build requested range of `OpRead` with total size.
+fn read_range(args: &OpRead, total: u64) -> Range<u64> {
+    match (args.offset(), args.size()) {
+        (Some(offset), Some(size)) => min(offset, total)..min(offset.saturating_add(size), total),
+        (Some(offset), None) => min(offset, total)..total,
+        (None, Some(size)) => total - min(size, total)..total,
+        (None, None) => 0..total,
+    }
+}
+
+/// Blocks dir is not found, or the cache doesn't support `list`.
+fn is_not_listable(err: &Error) -> bool {
+    matches!(err.kind(), ErrorKind::NotFound | ErrorKind::Unsupported)
+}
+
+/// Group blocks into runs of `(first, last, cached)`, missing runs are
+/// capped by [`MAX_FETCH_BLOCKS`].
+fn plan_runs(first: u64, cached: &[bool]) -> Vec<(u64, u64, bool)> {
+    let mut runs: Vec<(u64, u64, bool)> = Vec::new();
+    for (i, hit) in cached.iter().enumerate() {
+        let idx = first + i as u64;
+        match runs.last_mut() {
+            Some((lo, hi, c)) if *c == *hit && (*hit || *hi - *lo + 1 < MAX_FETCH_BLOCKS) => {
+                *hi = idx
+            }
+            _ => runs.push((idx, idx, *hit)),
+        }
+    }
+    runs
+}
+
+/// Trim content of a run starts at `start` to the requested range.
+fn trim_run(bs: Bytes, start: u64, range: Range<u64>) -> Bytes {
+    let end = start + bs.len() as u64;
+    bs.slice((range.start.max(start) - start) as usize..(range.end.min(end) - start) as usize)
+}
+
 #[derive(Debug, Clone)]
 struct ContentCacheAccessor {
     cache: Arc<dyn Accessor>,
     inner: Arc<dyn Accessor>,
+    strategy: ContentCacheStrategy,
+    write_policy: ContentCacheWritePolicy,
+    max_size: u64,
 }
 
-#[async_trait]
-impl Accessor for ContentCacheAccessor {
-    fn inner(&self) -> Option<Arc<dyn Accessor>> {
-        Some(self.inner.clone())
+impl ContentCacheAccessor {
+    /// Remove cached content of the path, including blocks of all versions.
+    async fn invalidate(&self, path: &str) -> Result<()> {
+        self.cache.delete(path, OpDelete::new()).await?;
+        if self.strategy == ContentCacheStrategy::Whole {
+            return Ok(());
+        }
+
+        let versions: Vec<_> = match self.cache.list(&Blocks::dir(path), OpList::new()).await {
+            Ok(s) => s.try_collect().await?,
+            // Blocks are keyed by version, stale blocks will never be read
+            // and could be evicted by cache that can't list.
+            Err(err) if is_not_listable(&err) => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        for version in versions.iter().filter(|de| de.mode().is_dir()) {
+            let blocks: Vec<_> = self
+                .cache
+                .list(version.path(), OpList::new())
+                .await?
+                .try_collect()
+                .await?;
+            for block in blocks.iter().filter(|de| de.mode().is_file()) {
+                self.cache.delete(block.path(), OpDelete::new()).await?;
+            }
+        }
+        Ok(())
     }
 
-    async fn create(&self, path: &str, args: OpCreate) -> Result<()> {
-        self.cache.delete(path, OpDelete::new()).await?;
-        self.inner.create(path, args).await
+    fn blocking_invalidate(&self, path: &str) -> Result<()> {
+        self.cache.blocking_delete(path, OpDelete::new())?;
+        if self.strategy == ContentCacheStrategy::Whole {
+            return Ok(());
+        }
+
+        let versions = match self.cache.blocking_list(&Blocks::dir(path), OpList::new()) {
+            Ok(it) => it.collect::<Result<Vec<_>>>()?,
+            Err(err) if is_not_listable(&err) => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        for version in versions.iter().filter(|de| de.mode().is_dir()) {
+            let blocks = self
+                .cache
+                .blocking_list(version.path(), OpList::new())?
+                .collect::<Result<Vec<_>>>()?;
+            for block in blocks.iter().filter(|de| de.mode().is_file()) {
+                self.cache.blocking_delete(block.path(), OpDelete::new())?;
+            }
+        }
+        Ok(())
     }
 
-    async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+    async fn read_whole(&self, path: &str, args: OpRead) -> Result<BytesReader> {
         match self.cache.read(path, args.clone()).await {
             Ok(r) => Ok(r),
             Err(err) if err.kind() == ErrorKind::NotFound => {
                 let meta = self.inner.stat(path, OpStat::new()).await?;
-                let r = if meta.mode().is_file() {
+                let r = if meta.mode().is_file() && meta.content_length() <= self.max_size {
                     let size = meta.content_length();
                     let reader = self.inner.read(path, OpRead::new(..)).await?;
                     self.cache.write(path, OpWrite::new(size), reader).await?;
@@ -123,34 +348,119 @@ impl Accessor for ContentCacheAccessor {
         }
     }
 
-    async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<u64> {
-        self.cache.delete(path, OpDelete::new()).await?;
-        self.inner.write(path, args, r).await
+    async fn read_blocks(&self, path: &str, args: OpRead, size: u64) -> Result<BytesReader> {
+        let meta = self.inner.stat(path, OpStat::new()).await?;
+        if !meta.mode().is_file() || meta.content_length() > self.max_size {
+            return self.inner.read(path, args).await;
+        }
+
+        let range = read_range(&args, meta.content_length());
+        if range.is_empty() {
+            return Ok(Box::new(Cursor::new(Vec::new())));
+        }
+
+        let blocks = Blocks::new(path, &meta, size);
+        let first = range.start / size;
+        let mut cached = Vec::new();
+        for idx in first..=(range.end - 1) / size {
+            let hit = self
+                .cache
+                .stat(&blocks.key(idx), OpStat::new())
+                .await
+                .is_ok();
+            cached.push(hit);
+        }
+
+        let acc = self.clone();
+        let s = stream::iter(plan_runs(first, &cached))
+            .then(move |(lo, hi, hit)| {
+                let (acc, blocks, range) = (acc.clone(), blocks.clone(), range.clone());
+                async move {
+                    let bs = acc.load_run(&blocks, lo, hi, hit).await?;
+                    Ok(trim_run(bs, lo * size, range))
+                }
+            })
+            .boxed();
+        Ok(Box::new(into_reader(s)))
     }
 
-    async fn delete(&self, path: &str, args: OpDelete) -> Result<()> {
-        self.cache.delete(path, OpDelete::new()).await?;
-        self.inner.delete(path, args).await
+    /// Load blocks `lo..=hi` from cache or inner.
+    async fn load_run(&self, blocks: &Blocks, lo: u64, hi: u64, hit: bool) -> Result<Bytes> {
+        if hit {
+            let mut buf = Vec::new();
+            let mut res = Ok(());
+            for idx in lo..=hi {
+                match self.cache.read(&blocks.key(idx), OpRead::new(..)).await {
+                    Ok(mut r) => {
+                        r.read_to_end(&mut buf).await?;
+                    }
+                    Err(err) => {
+                        res = Err(err);
+                        break;
+                    }
+                }
+            }
+            match res {
+                Ok(()) => return Ok(Bytes::from(buf)),
+                // Blocks could be evicted after checked.
+                Err(err) if err.kind() == ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        let range = blocks.range(lo, hi);
+        let mut buf = Vec::with_capacity((range.end - range.start) as usize);
+        let mut r = self.inner.read(&blocks.path, OpRead::new(range)).await?;
+        r.read_to_end(&mut buf).await?;
+
+        let bs = Bytes::from(buf);
+        self.fill_blocks(blocks, lo, bs.clone()).await?;
+        Ok(bs)
     }
 
-    async fn list(&self, path: &str, args: OpList) -> Result<ObjectStreamer> {
-        self.inner
-            .list(path, args)
-            .await
-            .map(|s| set_accessor_for_object_steamer(s, self.clone()))
+    /// Write content starts from block `first` into cache.
+    async fn fill_blocks(&self, blocks: &Blocks, first: u64, bs: Bytes) -> Result<()> {
+        let size = blocks.size as usize;
+        for (i, start) in (0..bs.len()).step_by(size).enumerate() {
+            let block = bs.slice(start..min(start + size, bs.len()));
+            self.cache
+                .write(
+                    &blocks.key(first + i as u64),
+                    OpWrite::new(block.len() as u64),
+                    Box::new(Cursor::new(block)),
+                )
+                .await?;
+        }
+        Ok(())
     }
 
-    fn blocking_create(&self, path: &str, args: OpCreate) -> Result<()> {
-        self.cache.blocking_delete(path, OpDelete::new())?;
-        self.inner.blocking_create(path, args)
+    /// Fill the cache with written content.
+    async fn fill(&self, path: &str, bs: Bytes) -> Result<()> {
+        match self.strategy {
+            ContentCacheStrategy::Whole => {
+                self.cache
+                    .write(
+                        path,
+                        OpWrite::new(bs.len() as u64),
+                        Box::new(Cursor::new(bs)),
+                    )
+                    .await?;
+            }
+            ContentCacheStrategy::Block(size) => {
+                let meta = self.inner.stat(path, OpStat::new()).await?;
+                self.fill_blocks(&Blocks::new(path, &meta, size), 0, bs)
+                    .await?;
+            }
+        }
+        Ok(())
     }
 
-    fn blocking_read(&self, path: &str, args: OpRead) -> Result<BlockingBytesReader> {
+    fn blocking_read_whole(&self, path: &str, args: OpRead) -> Result<BlockingBytesReader> {
         match self.cache.blocking_read(path, args.clone()) {
             Ok(r) => Ok(r),
             Err(err) if err.kind() == ErrorKind::NotFound => {
                 let meta = self.inner.blocking_stat(path, OpStat::new())?;
-                let r = if meta.mode().is_file() {
+                let r = if meta.mode().is_file() && meta.content_length() <= self.max_size {
                     let size = meta.content_length();
                     let reader = self.inner.blocking_read(path, OpRead::new(..))?;
                     self.cache
@@ -165,13 +475,175 @@ impl Accessor for ContentCacheAccessor {
         }
     }
 
-    fn blocking_write(&self, path: &str, args: OpWrite, r: BlockingBytesReader) -> Result<u64> {
-        self.cache.blocking_delete(path, OpDelete::new())?;
-        self.inner.blocking_write(path, args, r)
+    fn blocking_read_blocks(
+        &self,
+        path: &str,
+        args: OpRead,
+        size: u64,
+    ) -> Result<BlockingBytesReader> {
+        let meta = self.inner.blocking_stat(path, OpStat::new())?;
+        if !meta.mode().is_file() || meta.content_length() > self.max_size {
+            return self.inner.blocking_read(path, args);
+        }
+
+        let range = read_range(&args, meta.content_length());
+        if range.is_empty() {
+            return Ok(Box::new(std::io::Cursor::new(Vec::new())));
+        }
+
+        let blocks = Blocks::new(path, &meta, size);
+        let first = range.start / size;
+        let cached: Vec<_> = (first..=(range.end - 1) / size)
+            .map(|idx| {
+                self.cache
+                    .blocking_stat(&blocks.key(idx), OpStat::new())
+                    .is_ok()
+            })
+            .collect();
+
+        let mut buf = Vec::with_capacity((range.end - range.start) as usize);
+        for (lo, hi, hit) in plan_runs(first, &cached) {
+            let bs = self.blocking_load_run(&blocks, lo, hi, hit)?;
+            buf.extend_from_slice(&trim_run(bs, lo * size, range.clone()));
+        }
+        Ok(Box::new(std::io::Cursor::new(buf)))
+    }
+
+    fn blocking_load_run(&self, blocks: &Blocks, lo: u64, hi: u64, hit: bool) -> Result<Bytes> {
+        if hit {
+            let mut buf = Vec::new();
+            let res = (lo..=hi).try_for_each(|idx| {
+                let mut r = self
+                    .cache
+                    .blocking_read(&blocks.key(idx), OpRead::new(..))?;
+                r.read_to_end(&mut buf).map(|_| ())
+            });
+            match res {
+                Ok(()) => return Ok(Bytes::from(buf)),
+                // Blocks could be evicted after checked.
+                Err(err) if err.kind() == ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        let range = blocks.range(lo, hi);
+        let mut buf = Vec::with_capacity((range.end - range.start) as usize);
+        let mut r = self.inner.blocking_read(&blocks.path, OpRead::new(range))?;
+        r.read_to_end(&mut buf)?;
+
+        let bs = Bytes::from(buf);
+        self.blocking_fill_blocks(blocks, lo, bs.clone())?;
+        Ok(bs)
+    }
+
+    fn blocking_fill_blocks(&self, blocks: &Blocks, first: u64, bs: Bytes) -> Result<()> {
+        let size = blocks.size as usize;
+        for (i, start) in (0..bs.len()).step_by(size).enumerate() {
+            let block = bs.slice(start..min(start + size, bs.len()));
+            self.cache.blocking_write(
+                &blocks.key(first + i as u64),
+                OpWrite::new(block.len() as u64),
+                Box::new(std::io::Cursor::new(block)),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn blocking_fill(&self, path: &str, bs: Bytes) -> Result<()> {
+        match self.strategy {
+            ContentCacheStrategy::Whole => {
+                self.cache.blocking_write(
+                    path,
+                    OpWrite::new(bs.len() as u64),
+                    Box::new(std::io::Cursor::new(bs)),
+                )?;
+            }
+            ContentCacheStrategy::Block(size) => {
+                let meta = self.inner.blocking_stat(path, OpStat::new())?;
+                self.blocking_fill_blocks(&Blocks::new(path, &meta, size), 0, bs)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Accessor for ContentCacheAccessor {
+    fn inner(&self) -> Option<Arc<dyn Accessor>> {
+        Some(self.inner.clone())
+    }
+
+    async fn create(&self, path: &str, args: OpCreate) -> Result<()> {
+        self.invalidate(path).await?;
+        self.inner.create(path, args).await
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        match self.strategy {
+            ContentCacheStrategy::Whole => self.read_whole(path, args).await,
+            ContentCacheStrategy::Block(size) => self.read_blocks(path, args, size).await,
+        }
+    }
+
+    async fn write(&self, path: &str, args: OpWrite, mut r: BytesReader) -> Result<u64> {
+        self.invalidate(path).await?;
+        if self.write_policy == ContentCacheWritePolicy::Invalidate || args.size() > self.max_size {
+            return self.inner.write(path, args, r).await;
+        }
+
+        let mut buf = Vec::with_capacity(args.size() as usize);
+        r.read_to_end(&mut buf).await?;
+        let bs = Bytes::from(buf);
+        let n = self
+            .inner
+            .write(path, args, Box::new(Cursor::new(bs.clone())))
+            .await?;
+        self.fill(path, bs).await?;
+        Ok(n)
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<()> {
+        self.invalidate(path).await?;
+        self.inner.delete(path, args).await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<ObjectStreamer> {
+        self.inner
+            .list(path, args)
+            .await
+            .map(|s| set_accessor_for_object_steamer(s, self.clone()))
+    }
+
+    fn blocking_create(&self, path: &str, args: OpCreate) -> Result<()> {
+        self.blocking_invalidate(path)?;
+        self.inner.blocking_create(path, args)
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<BlockingBytesReader> {
+        match self.strategy {
+            ContentCacheStrategy::Whole => self.blocking_read_whole(path, args),
+            ContentCacheStrategy::Block(size) => self.blocking_read_blocks(path, args, size),
+        }
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite, mut r: BlockingBytesReader) -> Result<u64> {
+        self.blocking_invalidate(path)?;
+        if self.write_policy == ContentCacheWritePolicy::Invalidate || args.size() > self.max_size {
+            return self.inner.blocking_write(path, args, r);
+        }
+
+        let mut buf = Vec::with_capacity(args.size() as usize);
+        r.read_to_end(&mut buf)?;
+        let bs = Bytes::from(buf);
+        let n =
+            self.inner
+                .blocking_write(path, args, Box::new(std::io::Cursor::new(bs.clone())))?;
+        self.blocking_fill(path, bs)?;
+        Ok(n)
     }
 
     fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<()> {
-        self.cache.blocking_delete(path, OpDelete::new())?;
+        self.blocking_invalidate(path)?;
         self.inner.blocking_delete(path, args)
     }
 
@@ -184,10 +656,52 @@ impl Accessor for ContentCacheAccessor {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Mutex;
+
     use super::*;
+    use crate::error::new_unsupported_object_error;
+    use crate::ops::Operation;
     use crate::services::memory;
     use crate::Operator;
 
+    /// MockService will record ranges of all reads.
+    #[derive(Debug)]
+    struct MockService {
+        inner: Arc<dyn Accessor>,
+        reads: Arc<Mutex<Vec<Range<u64>>>>,
+    }
+
+    #[async_trait]
+    impl Accessor for MockService {
+        fn inner(&self) -> Option<Arc<dyn Accessor>> {
+            Some(self.inner.clone())
+        }
+
+        async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+            let start = args.offset().unwrap_or_default();
+            let end = start + args.size().unwrap_or_default();
+            self.reads.lock().unwrap().push(start..end);
+            self.inner.read(path, args).await
+        }
+    }
+
+    /// NoListService is a cache that doesn't support `list`.
+    #[derive(Debug)]
+    struct NoListService {
+        inner: Arc<dyn Accessor>,
+    }
+
+    #[async_trait]
+    impl Accessor for NoListService {
+        fn inner(&self) -> Option<Arc<dyn Accessor>> {
+            Some(self.inner.clone())
+        }
+
+        async fn list(&self, path: &str, _: OpList) -> Result<ObjectStreamer> {
+            Err(new_unsupported_object_error(Operation::List, path))
+        }
+    }
+
     #[tokio::test]
     async fn test_content_cache() -> anyhow::Result<()> {
         let op = Operator::new(memory::Builder::default().build()?);
@@ -221,4 +735,146 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_content_cache_block() -> anyhow::Result<()> {
+        let reads = Arc::new(Mutex::new(Vec::new()));
+        let op = Operator::new(Arc::new(MockService {
+            inner: Arc::new(memory::Builder::default().build()?),
+            reads: reads.clone(),
+        }));
+        let content: Vec<u8> = (0..=255).cycle().take(1000).collect();
+        op.object("test").write(content.clone()).await?;
+
+        let cache_layer = ContentCacheLayer::new(memory::Builder::default().build()?)
+            .with_strategy(ContentCacheStrategy::Block(100));
+        let cached_op = op.layer(cache_layer);
+
+        // Only blocks of the range will be fetched.
+        let data = cached_op.object("test").range_read(150..250).await?;
+        assert_eq!(data, content[150..250]);
+        assert_eq!(*reads.lock().unwrap(), vec![100..300]);
+
+        // Read from cached blocks.
+        let data = cached_op.object("test").range_read(120..180).await?;
+        assert_eq!(data, content[120..180]);
+        assert_eq!(reads.lock().unwrap().len(), 1);
+
+        // Only missing blocks will be fetched.
+        let data = cached_op.object("test").range_read(250..450).await?;
+        assert_eq!(data, content[250..450]);
+        assert_eq!(*reads.lock().unwrap(), vec![100..300, 300..500]);
+
+        let data = cached_op.object("test").read().await?;
+        assert_eq!(data, content);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_content_cache_block_overwrite() -> anyhow::Result<()> {
+        let op = Operator::new(memory::Builder::default().build()?);
+        let cache = Arc::new(memory::Builder::default().build()?);
+
+        let cache_layer =
+            ContentCacheLayer::new(cache.clone()).with_strategy(ContentCacheStrategy::Block(4));
+        let cached_op = op.layer(cache_layer);
+
+        cached_op.object("test").write("Hello, World!").await?;
+        assert_eq!(cached_op.object("test").read().await?, b"Hello, World!");
+
+        // Overwrite with content of the same length should not read old blocks.
+        cached_op.object("test").write("Hello, Xuanw!").await?;
+        assert_eq!(cached_op.object("test").read().await?, b"Hello, Xuanw!");
+
+        // Delete should remove all cached blocks.
+        cached_op.object("test").delete().await?;
+        let cache_op = Operator::new(cache);
+        let versions: Vec<_> = cache_op
+            .object(".blocks/test/")
+            .list()
+            .await?
+            .try_collect()
+            .await?;
+        for version in versions {
+            let blocks: Vec<_> = cache_op
+                .object(version.path())
+                .list()
+                .await?
+                .try_collect()
+                .await?;
+            assert!(
+                blocks.is_empty(),
+                "blocks of {} must be removed",
+                version.path()
+            );
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_content_cache_block_no_list() -> anyhow::Result<()> {
+        let op = Operator::new(memory::Builder::default().build()?);
+        let cache = NoListService {
+            inner: Arc::new(memory::Builder::default().build()?),
+        };
+
+        let cache_layer =
+            ContentCacheLayer::new(cache).with_strategy(ContentCacheStrategy::Block(4));
+        let cached_op = op.layer(cache_layer);
+
+        cached_op.object("test").write("Hello, World!").await?;
+        assert_eq!(cached_op.object("test").read().await?, b"Hello, World!");
+
+        // Blocks of old versions can't be removed, but will not be read.
+        cached_op.object("test").write("Hello, Xuanw!").await?;
+        assert_eq!(cached_op.object("test").read().await?, b"Hello, Xuanw!");
+
+        cached_op.object("dir/").create().await?;
+        cached_op.object("test").delete().await?;
+        assert!(!cached_op.object("test").is_exist().await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_content_cache_write_through() -> anyhow::Result<()> {
+        let op = Operator::new(memory::Builder::default().build()?);
+        let cache = Arc::new(memory::Builder::default().build()?);
+
+        let cache_layer = ContentCacheLayer::new(cache.clone())
+            .with_write_policy(ContentCacheWritePolicy::Through);
+        let cached_op = op.clone().layer(cache_layer);
+
+        cached_op.object("test").write("Hello, World!").await?;
+        assert_eq!(op.object("test").read().await?, b"Hello, World!");
+        assert_eq!(
+            Operator::new(cache).object("test").read().await?,
+            b"Hello, World!"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_plan_runs() {
+        let cases = vec![
+            ("all missing", vec![false; 3], vec![(2, 4, false)]),
+            (
+                "mixed",
+                vec![true, false, false, true],
+                vec![(2, 2, true), (3, 4, false), (5, 5, true)],
+            ),
+            (
+                "capped",
+                vec![false; 20],
+                vec![(2, 17, false), (18, 21, false)],
+            ),
+        ];
+
+        for (name, cached, expect) in cases {
+            assert_eq!(plan_runs(2, &cached), expect, "{name}")
+        }
+    }
 }
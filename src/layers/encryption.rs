@@ -0,0 +1,1133 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::min;
+use std::fmt::Debug;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::AeadInPlace;
+use aes_gcm::aead::KeyInit;
+use aes_gcm::aead::OsRng;
+use aes_gcm::Aes256Gcm;
+use aes_gcm::Nonce;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use chacha20poly1305::ChaCha20Poly1305;
+use futures::io::Cursor;
+use futures::ready;
+use futures::AsyncRead;
+use futures::AsyncReadExt;
+use futures::Stream;
+
+use crate::error::new_unsupported_object_error;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpList;
+use crate::ops::OpPresign;
+use crate::ops::OpRead;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::Operation;
+use crate::ops::PresignedRequest;
+use crate::Accessor;
+use crate::AccessorCapability;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::Layer;
+use crate::ObjectEntry;
+use crate::ObjectIterator;
+use crate::ObjectMetadata;
+use crate::ObjectPart;
+use crate::ObjectStreamer;
+
+/// Magic bytes at the start of every encrypted object, the last byte is
+/// the version of the format.
+const MAGIC: &[u8; 4] = b"ODE\x01";
+/// Size of the fixed part of header:
+///
+/// `magic(4) | algorithm(1) | chunk_size(4) | content_length(8) | wrapped_key_len(2)`
+const FIXED_HEADER_SIZE: usize = 19;
+/// Size of the authentication tag of both AES-256-GCM and ChaCha20-Poly1305.
+const TAG_SIZE: u64 = 16;
+/// Size of the data key of both AES-256-GCM and ChaCha20-Poly1305.
+const KEY_SIZE: usize = 32;
+/// Default plaintext size of every chunk.
+const DEFAULT_CHUNK_SIZE: u32 = 64 * 1024;
+
+/// EncryptionAlgorithm represents all encryption algorithms that
+/// [`EncryptionLayer`] supports.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum EncryptionAlgorithm {
+    /// AES-256 in Galois/Counter Mode.
+    Aes256Gcm,
+    /// ChaCha20 stream cipher with Poly1305 authenticator.
+    ChaCha20Poly1305,
+}
+
+impl EncryptionAlgorithm {
+    fn to_u8(self) -> u8 {
+        match self {
+            EncryptionAlgorithm::Aes256Gcm => 1,
+            EncryptionAlgorithm::ChaCha20Poly1305 => 2,
+        }
+    }
+
+    fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            1 => Some(EncryptionAlgorithm::Aes256Gcm),
+            2 => Some(EncryptionAlgorithm::ChaCha20Poly1305),
+            _ => None,
+        }
+    }
+}
+
+/// KeyProvider wraps and unwraps the per-object data keys of [`EncryptionLayer`].
+///
+/// Every object is encrypted by a random data key, the data key will be
+/// wrapped by `KeyProvider` and stored in the object header.
+///
+/// # Notes
+///
+/// Those functions will be called in both async and blocking operations,
+/// implementor that talks to remote KMS should take care of it.
+pub trait KeyProvider: Send + Sync + Debug + 'static {
+    /// Wrap the data key, returns the wrapped key to be stored.
+    fn wrap_key(&self, key: &[u8]) -> Result<Vec<u8>>;
+
+    /// Unwrap the stored wrapped key, returns the data key.
+    fn unwrap_key(&self, wrapped: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// StaticKeyProvider wraps data keys with a static master key by AES-256-GCM.
+///
+/// # Examples
+///
+/// ```
+/// use opendal::layers::StaticKeyProvider;
+///
+/// let _ = StaticKeyProvider::new([0; 32]);
+/// ```
+#[derive(Clone)]
+pub struct StaticKeyProvider {
+    cipher: Aes256Gcm,
+}
+
+impl StaticKeyProvider {
+    /// Create a new StaticKeyProvider with given master key.
+    pub fn new(master_key: [u8; 32]) -> Self {
+        Self {
+            cipher: Aes256Gcm::new(&master_key.into()),
+        }
+    }
+}
+
+impl Debug for StaticKeyProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StaticKeyProvider")
+            .field("master_key", &"<redacted>")
+            .finish()
+    }
+}
+
+impl KeyProvider for StaticKeyProvider {
+    fn wrap_key(&self, key: &[u8]) -> Result<Vec<u8>> {
+        let mut nonce = [0; 12];
+        OsRng.fill_bytes(&mut nonce);
+
+        let mut bs = key.to_vec();
+        self.cipher
+            .encrypt_in_place(Nonce::from_slice(&nonce), &[], &mut bs)
+            .map_err(|_| Error::new(ErrorKind::Other, anyhow!("wrap data key failed")))?;
+
+        let mut wrapped = nonce.to_vec();
+        wrapped.extend_from_slice(&bs);
+        Ok(wrapped)
+    }
+
+    fn unwrap_key(&self, wrapped: &[u8]) -> Result<Vec<u8>> {
+        if wrapped.len() < 12 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                anyhow!("wrapped data key is too short"),
+            ));
+        }
+
+        let (nonce, bs) = wrapped.split_at(12);
+        let mut bs = bs.to_vec();
+        self.cipher
+            .decrypt_in_place(Nonce::from_slice(nonce), &[], &mut bs)
+            .map_err(|_| Error::new(ErrorKind::InvalidData, anyhow!("unwrap data key failed")))?;
+        Ok(bs)
+    }
+}
+
+/// EncryptionLayer will encrypt object contents before they leave the host
+/// and decrypt them while reading.
+///
+/// # Format
+///
+/// Every object is encrypted by a random data key which is wrapped by the
+/// user-supplied [`KeyProvider`]. The object is stored as:
+///
+/// - A header carries the algorithm, chunk size, plaintext length and the
+///   wrapped data key.
+/// - Fixed-size authenticated chunks, every chunk has a 16 bytes tag.
+///
+/// The chunk index and whether the chunk is the last one are bound into
+/// every chunk's nonce, and the header is bound as associated data. So
+/// reordered, truncated or tampered objects will fail to decrypt.
+///
+/// # Notes
+///
+/// - `read` with range will only fetch and decrypt the chunks it covers.
+/// - `stat` will report the plaintext length by reading the header.
+/// - Objects not written by this layer can't be read.
+/// - `presign` and multipart operations can't be encrypted transparently,
+///   so they are not supported by this layer.
+///
+/// # Examples
+///
+/// ```
+/// use anyhow::Result;
+/// use opendal::layers::EncryptionAlgorithm;
+/// use opendal::layers::EncryptionLayer;
+/// use opendal::layers::StaticKeyProvider;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// let _ = Operator::from_env(Scheme::Fs)
+///     .expect("must init")
+///     .layer(EncryptionLayer::new(
+///         EncryptionAlgorithm::Aes256Gcm,
+///         StaticKeyProvider::new([0; 32]),
+///     ));
+/// ```
+#[derive(Debug, Clone)]
+pub struct EncryptionLayer {
+    algo: EncryptionAlgorithm,
+    provider: Arc<dyn KeyProvider>,
+    chunk_size: u32,
+}
+
+impl EncryptionLayer {
+    /// Create a new encryption layer with given algorithm and key provider.
+    pub fn new(algo: EncryptionAlgorithm, provider: impl KeyProvider) -> Self {
+        Self {
+            algo,
+            provider: Arc::new(provider),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// Set the plaintext size of every chunk, default to 64 KiB.
+    ///
+    /// Smaller chunk makes ranged read fetch less data but adds more tags.
+    ///
+    /// # Panics
+    ///
+    /// Panic if chunk size is zero.
+    pub fn with_chunk_size(mut self, chunk_size: u32) -> Self {
+        assert!(chunk_size > 0, "chunk size must be greater than zero");
+
+        self.chunk_size = chunk_size;
+        self
+    }
+}
+
+impl Layer for EncryptionLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(EncryptionAccessor {
+            algo: self.algo,
+            provider: self.provider.clone(),
+            chunk_size: self.chunk_size,
+            inner,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EncryptionAccessor {
+    algo: EncryptionAlgorithm,
+    provider: Arc<dyn KeyProvider>,
+    chunk_size: u32,
+    inner: Arc<dyn Accessor>,
+}
+
+impl EncryptionAccessor {
+    /// Generate a new data key and build the header for a new object.
+    fn new_header(&self, content_length: u64) -> Result<(Header, ChunkCipher)> {
+        let mut key = [0; KEY_SIZE];
+        OsRng.fill_bytes(&mut key);
+
+        let header = Header {
+            algo: self.algo,
+            chunk_size: self.chunk_size,
+            content_length,
+            wrapped_key: self.provider.wrap_key(&key)?,
+        };
+        let cipher = ChunkCipher::new(self.algo, &key)?;
+        Ok((header, cipher))
+    }
+
+    async fn read_fixed_header(&self, path: &str) -> Result<Header> {
+        let mut r = self
+            .inner
+            .read(path, OpRead::new(..FIXED_HEADER_SIZE as u64))
+            .await?;
+        let mut bs = [0; FIXED_HEADER_SIZE];
+        r.read_exact(&mut bs).await?;
+        Header::parse_fixed(&bs)
+    }
+
+    async fn read_header(&self, path: &str) -> Result<(Header, ChunkCipher)> {
+        let mut header = self.read_fixed_header(path).await?;
+
+        let key_len = header.wrapped_key.len() as u64;
+        let mut r = self
+            .inner
+            .read(
+                path,
+                OpRead::new(FIXED_HEADER_SIZE as u64..FIXED_HEADER_SIZE as u64 + key_len),
+            )
+            .await?;
+        r.read_exact(&mut header.wrapped_key).await?;
+
+        let key = self.provider.unwrap_key(&header.wrapped_key)?;
+        let cipher = ChunkCipher::new(header.algo, &key)?;
+        Ok((header, cipher))
+    }
+
+    fn blocking_read_fixed_header(&self, path: &str) -> Result<Header> {
+        let mut r = self
+            .inner
+            .blocking_read(path, OpRead::new(..FIXED_HEADER_SIZE as u64))?;
+        let mut bs = [0; FIXED_HEADER_SIZE];
+        r.read_exact(&mut bs)?;
+        Header::parse_fixed(&bs)
+    }
+
+    fn blocking_read_header(&self, path: &str) -> Result<(Header, ChunkCipher)> {
+        let mut header = self.blocking_read_fixed_header(path)?;
+
+        let key_len = header.wrapped_key.len() as u64;
+        let mut r = self.inner.blocking_read(
+            path,
+            OpRead::new(FIXED_HEADER_SIZE as u64..FIXED_HEADER_SIZE as u64 + key_len),
+        )?;
+        r.read_exact(&mut header.wrapped_key)?;
+
+        let key = self.provider.unwrap_key(&header.wrapped_key)?;
+        let cipher = ChunkCipher::new(header.algo, &key)?;
+        Ok((header, cipher))
+    }
+}
+
+#[async_trait]
+impl Accessor for EncryptionAccessor {
+    fn inner(&self) -> Option<Arc<dyn Accessor>> {
+        Some(self.inner.clone())
+    }
+
+    fn metadata(&self) -> AccessorMetadata {
+        let mut meta = self.inner.metadata();
+        meta.set_capabilities(
+            meta.capabilities() - AccessorCapability::Presign - AccessorCapability::Multipart,
+        );
+        meta
+    }
+
+    async fn create(&self, path: &str, args: OpCreate) -> Result<()> {
+        if path.ends_with('/') {
+            return self.inner.create(path, args).await;
+        }
+
+        // Empty file still needs a valid header.
+        self.write(path, OpWrite::new(0), Box::new(Cursor::new(vec![])))
+            .await
+            .map(|_| ())
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        let (header, cipher) = self.read_header(path).await?;
+        let (offset, size) = header.plaintext_range(&args);
+        if size == 0 {
+            return Ok(Box::new(Cursor::new(vec![])));
+        }
+
+        let (first, start, end) = header.ciphertext_range(offset, size);
+        let r = self.inner.read(path, OpRead::new(start..end)).await?;
+
+        let state = DecryptState::new(header, cipher, first, offset, size);
+        Ok(Box::new(DecryptReader { inner: r, state }))
+    }
+
+    async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<u64> {
+        let size = args.size();
+        let (header, cipher) = self.new_header(size)?;
+
+        let mut op = OpWrite::new(header.ciphertext_length());
+        if let Some(v) = args.content_type() {
+            op = op.with_content_type(v);
+        }
+
+        let state = EncryptState::new(header, cipher);
+        self.inner
+            .write(path, op, Box::new(EncryptReader { inner: r, state }))
+            .await?;
+        Ok(size)
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
+        let meta = self.inner.stat(path, args).await?;
+        if meta.mode().is_dir() {
+            return Ok(meta);
+        }
+
+        let header = self.read_fixed_header(path).await?;
+        Ok(plaintext_metadata(meta, header.content_length))
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<ObjectStreamer> {
+        Ok(Box::new(EncryptionStreamer::new(
+            Arc::new(self.clone()),
+            self.inner.list(path, args).await?,
+        )))
+    }
+
+    fn presign(&self, path: &str, _: OpPresign) -> Result<PresignedRequest> {
+        Err(new_unsupported_object_error(Operation::Presign, path))
+    }
+
+    async fn create_multipart(&self, path: &str, _: OpCreateMultipart) -> Result<String> {
+        Err(new_unsupported_object_error(
+            Operation::CreateMultipart,
+            path,
+        ))
+    }
+
+    async fn write_multipart(
+        &self,
+        path: &str,
+        _: OpWriteMultipart,
+        _: BytesReader,
+    ) -> Result<ObjectPart> {
+        Err(new_unsupported_object_error(
+            Operation::WriteMultipart,
+            path,
+        ))
+    }
+
+    async fn complete_multipart(&self, path: &str, _: OpCompleteMultipart) -> Result<()> {
+        Err(new_unsupported_object_error(
+            Operation::CompleteMultipart,
+            path,
+        ))
+    }
+
+    async fn abort_multipart(&self, path: &str, _: OpAbortMultipart) -> Result<()> {
+        Err(new_unsupported_object_error(
+            Operation::AbortMultipart,
+            path,
+        ))
+    }
+
+    fn blocking_create(&self, path: &str, args: OpCreate) -> Result<()> {
+        if path.ends_with('/') {
+            return self.inner.blocking_create(path, args);
+        }
+
+        // Empty file still needs a valid header.
+        self.blocking_write(
+            path,
+            OpWrite::new(0),
+            Box::new(std::io::Cursor::new(vec![])),
+        )
+        .map(|_| ())
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<BlockingBytesReader> {
+        let (header, cipher) = self.blocking_read_header(path)?;
+        let (offset, size) = header.plaintext_range(&args);
+        if size == 0 {
+            return Ok(Box::new(std::io::Cursor::new(vec![])));
+        }
+
+        let (first, start, end) = header.ciphertext_range(offset, size);
+        let r = self.inner.blocking_read(path, OpRead::new(start..end))?;
+
+        let state = DecryptState::new(header, cipher, first, offset, size);
+        Ok(Box::new(BlockingDecryptReader { inner: r, state }))
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite, r: BlockingBytesReader) -> Result<u64> {
+        let size = args.size();
+        let (header, cipher) = self.new_header(size)?;
+
+        let mut op = OpWrite::new(header.ciphertext_length());
+        if let Some(v) = args.content_type() {
+            op = op.with_content_type(v);
+        }
+
+        let state = EncryptState::new(header, cipher);
+        self.inner.blocking_write(
+            path,
+            op,
+            Box::new(BlockingEncryptReader { inner: r, state }),
+        )?;
+        Ok(size)
+    }
+
+    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
+        let meta = self.inner.blocking_stat(path, args)?;
+        if meta.mode().is_dir() {
+            return Ok(meta);
+        }
+
+        let header = self.blocking_read_fixed_header(path)?;
+        Ok(plaintext_metadata(meta, header.content_length))
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<ObjectIterator> {
+        Ok(Box::new(EncryptionIterator::new(
+            Arc::new(self.clone()),
+            self.inner.blocking_list(path, args)?,
+        )))
+    }
+}
+
+/// Header of encrypted object.
+#[derive(Debug, Clone)]
+struct Header {
+    algo: EncryptionAlgorithm,
+    chunk_size: u32,
+    content_length: u64,
+    wrapped_key: Vec<u8>,
+}
+
+impl Header {
+    /// Parse the fixed part of header, `wrapped_key` will be filled with
+    /// zero in its length.
+    fn parse_fixed(bs: &[u8; FIXED_HEADER_SIZE]) -> Result<Self> {
+        if &bs[0..4] != MAGIC {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                anyhow!("object is not encrypted by EncryptionLayer"),
+            ));
+        }
+
+        let algo = EncryptionAlgorithm::from_u8(bs[4]).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                anyhow!("unsupported encryption algorithm: {}", bs[4]),
+            )
+        })?;
+        let chunk_size = u32::from_be_bytes(bs[5..9].try_into().expect("must be 4 bytes"));
+        let content_length = u64::from_be_bytes(bs[9..17].try_into().expect("must be 8 bytes"));
+        let key_len = u16::from_be_bytes(bs[17..19].try_into().expect("must be 2 bytes"));
+        if chunk_size == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                anyhow!("chunk size of encrypted object is zero"),
+            ));
+        }
+
+        Ok(Self {
+            algo,
+            chunk_size,
+            content_length,
+            wrapped_key: vec![0; key_len as usize],
+        })
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        let key_len = u16::try_from(self.wrapped_key.len()).map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                anyhow!("wrapped data key is too long"),
+            )
+        })?;
+
+        let mut bs = Vec::with_capacity(self.header_length() as usize);
+        bs.extend_from_slice(MAGIC);
+        bs.push(self.algo.to_u8());
+        bs.extend_from_slice(&self.chunk_size.to_be_bytes());
+        bs.extend_from_slice(&self.content_length.to_be_bytes());
+        bs.extend_from_slice(&key_len.to_be_bytes());
+        bs.extend_from_slice(&self.wrapped_key);
+        Ok(bs)
+    }
+
+    fn header_length(&self) -> u64 {
+        (FIXED_HEADER_SIZE + self.wrapped_key.len()) as u64
+    }
+
+    fn chunk_count(&self) -> u64 {
+        (self.content_length + self.chunk_size as u64 - 1) / self.chunk_size as u64
+    }
+
+    fn plaintext_chunk_length(&self, index: u64) -> u64 {
+        min(
+            self.chunk_size as u64,
+            self.content_length - index * self.chunk_size as u64,
+        )
+    }
+
+    fn ciphertext_chunk_offset(&self, index: u64) -> u64 {
+        self.header_length() + index * (self.chunk_size as u64 + TAG_SIZE)
+    }
+
+    fn ciphertext_length(&self) -> u64 {
+        self.header_length() + self.content_length + self.chunk_count() * TAG_SIZE
+    }
+
+    fn is_last_chunk(&self, index: u64) -> bool {
+        index + 1 == self.chunk_count()
+    }
+
+    /// Calculate the `(offset, size)` of plaintext for given read args.
+    fn plaintext_range(&self, args: &OpRead) -> (u64, u64) {
+        let total = self.content_length;
+        match (args.offset(), args.size()) {
+            (Some(offset), Some(size)) => {
+                let offset = offset.min(total);
+                (offset, size.min(total - offset))
+            }
+            (Some(offset), None) => {
+                let offset = offset.min(total);
+                (offset, total - offset)
+            }
+            (None, Some(size)) => {
+                let size = size.min(total);
+                (total - size, size)
+            }
+            (None, None) => (0, total),
+        }
+    }
+
+    /// Calculate the first chunk index and the `[start, end)` of ciphertext
+    /// that covers given plaintext range.
+    fn ciphertext_range(&self, offset: u64, size: u64) -> (u64, u64, u64) {
+        debug_assert!(size > 0, "size of ciphertext range must be positive");
+
+        let first = offset / self.chunk_size as u64;
+        let last = (offset + size - 1) / self.chunk_size as u64;
+        let start = self.ciphertext_chunk_offset(first);
+        let end = self.ciphertext_chunk_offset(last) + self.plaintext_chunk_length(last) + TAG_SIZE;
+        (first, start, end)
+    }
+}
+
+/// ChunkCipher encrypts and decrypts chunks with the data key.
+enum ChunkCipher {
+    /// Aes256Gcm carries all round keys, wrap into box to reduce the
+    /// total size of the enum.
+    Aes256Gcm(Box<Aes256Gcm>),
+    ChaCha20Poly1305(ChaCha20Poly1305),
+}
+
+impl ChunkCipher {
+    fn new(algo: EncryptionAlgorithm, key: &[u8]) -> Result<Self> {
+        let invalid_key = |_| {
+            Error::new(
+                ErrorKind::InvalidData,
+                anyhow!("data key length is invalid"),
+            )
+        };
+
+        match algo {
+            EncryptionAlgorithm::Aes256Gcm => Ok(ChunkCipher::Aes256Gcm(Box::new(
+                Aes256Gcm::new_from_slice(key).map_err(invalid_key)?,
+            ))),
+            EncryptionAlgorithm::ChaCha20Poly1305 => Ok(ChunkCipher::ChaCha20Poly1305(
+                ChaCha20Poly1305::new_from_slice(key).map_err(invalid_key)?,
+            )),
+        }
+    }
+
+    /// Build the nonce of chunk: `index(8) | last(1) | zero(3)`.
+    ///
+    /// Every object has its own data key, so it's safe to derive nonce
+    /// from chunk index.
+    fn nonce(index: u64, last: bool) -> [u8; 12] {
+        let mut nonce = [0; 12];
+        nonce[0..8].copy_from_slice(&index.to_be_bytes());
+        nonce[8] = last as u8;
+        nonce
+    }
+
+    fn encrypt(&self, index: u64, last: bool, aad: &[u8], buf: &mut Vec<u8>) -> Result<()> {
+        let nonce = Self::nonce(index, last);
+        let nonce = Nonce::from_slice(&nonce);
+        match self {
+            ChunkCipher::Aes256Gcm(c) => c.encrypt_in_place(nonce, aad, buf),
+            ChunkCipher::ChaCha20Poly1305(c) => c.encrypt_in_place(nonce, aad, buf),
+        }
+        .map_err(|_| Error::new(ErrorKind::Other, anyhow!("encrypt chunk {index} failed")))
+    }
+
+    fn decrypt(&self, index: u64, last: bool, aad: &[u8], buf: &mut Vec<u8>) -> Result<()> {
+        let nonce = Self::nonce(index, last);
+        let nonce = Nonce::from_slice(&nonce);
+        match self {
+            ChunkCipher::Aes256Gcm(c) => c.decrypt_in_place(nonce, aad, buf),
+            ChunkCipher::ChaCha20Poly1305(c) => c.decrypt_in_place(nonce, aad, buf),
+        }
+        .map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidData,
+                anyhow!("decrypt chunk {index} failed, object may be tampered"),
+            )
+        })
+    }
+}
+
+/// EncryptState turns plaintext into header and encrypted chunks.
+struct EncryptState {
+    header: Header,
+    cipher: ChunkCipher,
+    aad: Vec<u8>,
+    /// Plaintext that not read from input yet.
+    remaining: u64,
+    index: u64,
+    plaintext: Vec<u8>,
+    filled: usize,
+    output: Vec<u8>,
+    output_pos: usize,
+}
+
+impl EncryptState {
+    fn new(header: Header, cipher: ChunkCipher) -> Self {
+        let aad = header.encode().expect("wrapped key length must be checked");
+
+        Self {
+            remaining: header.content_length,
+            plaintext: vec![0; min(header.chunk_size as u64, header.content_length) as usize],
+            // Header is the first part of output.
+            output: aad.clone(),
+            aad,
+            header,
+            cipher,
+            index: 0,
+            filled: 0,
+            output_pos: 0,
+        }
+    }
+
+    /// Copy encrypted data into buf, returns `None` if more input is needed.
+    fn read_output(&mut self, buf: &mut [u8]) -> Option<usize> {
+        if self.output_pos < self.output.len() {
+            let n = min(buf.len(), self.output.len() - self.output_pos);
+            buf[..n].copy_from_slice(&self.output[self.output_pos..self.output_pos + n]);
+            self.output_pos += n;
+            return Some(n);
+        }
+
+        if self.remaining == 0 && self.filled == 0 {
+            return Some(0);
+        }
+        None
+    }
+
+    /// Buffer to be filled by input.
+    fn input_buf(&mut self) -> &mut [u8] {
+        let want = min(
+            (self.header.chunk_size as usize - self.filled) as u64,
+            self.remaining,
+        ) as usize;
+        &mut self.plaintext[self.filled..self.filled + want]
+    }
+
+    /// Consume `n` bytes filled into `input_buf`.
+    fn consume_input(&mut self, n: usize) -> Result<()> {
+        if n == 0 {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                anyhow!(
+                    "input reader is shorter than expected, {} bytes remaining",
+                    self.remaining
+                ),
+            ));
+        }
+
+        self.filled += n;
+        self.remaining -= n as u64;
+        if self.filled == self.header.chunk_size as usize || self.remaining == 0 {
+            let mut chunk = self.plaintext[..self.filled].to_vec();
+            self.cipher.encrypt(
+                self.index,
+                self.header.is_last_chunk(self.index),
+                &self.aad,
+                &mut chunk,
+            )?;
+
+            self.output = chunk;
+            self.output_pos = 0;
+            self.index += 1;
+            self.filled = 0;
+        }
+        Ok(())
+    }
+}
+
+/// DecryptState turns encrypted chunks into plaintext.
+struct DecryptState {
+    header: Header,
+    cipher: ChunkCipher,
+    aad: Vec<u8>,
+    index: u64,
+    /// Bytes to skip in the first decrypted chunk.
+    skip: usize,
+    /// Plaintext that not returned yet.
+    remaining: u64,
+    ciphertext: Vec<u8>,
+    filled: usize,
+    output: Vec<u8>,
+    output_pos: usize,
+}
+
+impl DecryptState {
+    fn new(header: Header, cipher: ChunkCipher, first: u64, offset: u64, size: u64) -> Self {
+        let aad = header.encode().expect("wrapped key length must be checked");
+
+        Self {
+            ciphertext: vec![0; (header.chunk_size as u64 + TAG_SIZE) as usize],
+            skip: (offset - first * header.chunk_size as u64) as usize,
+            header,
+            cipher,
+            aad,
+            index: first,
+            remaining: size,
+            filled: 0,
+            output: vec![],
+            output_pos: 0,
+        }
+    }
+
+    /// Copy decrypted data into buf, returns `None` if more input is needed.
+    fn read_output(&mut self, buf: &mut [u8]) -> Option<usize> {
+        if self.remaining == 0 {
+            return Some(0);
+        }
+
+        if self.output_pos < self.output.len() {
+            let n = min(buf.len(), self.output.len() - self.output_pos);
+            let n = min(n as u64, self.remaining) as usize;
+            buf[..n].copy_from_slice(&self.output[self.output_pos..self.output_pos + n]);
+            self.output_pos += n;
+            self.remaining -= n as u64;
+            return Some(n);
+        }
+
+        None
+    }
+
+    fn chunk_length(&self) -> usize {
+        (self.header.plaintext_chunk_length(self.index) + TAG_SIZE) as usize
+    }
+
+    /// Buffer to be filled by input.
+    fn input_buf(&mut self) -> &mut [u8] {
+        let end = self.chunk_length();
+        &mut self.ciphertext[self.filled..end]
+    }
+
+    /// Consume `n` bytes filled into `input_buf`.
+    fn consume_input(&mut self, n: usize) -> Result<()> {
+        if n == 0 {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                anyhow!("encrypted object is truncated at chunk {}", self.index),
+            ));
+        }
+
+        self.filled += n;
+        if self.filled == self.chunk_length() {
+            let mut chunk = self.ciphertext[..self.filled].to_vec();
+            self.cipher.decrypt(
+                self.index,
+                self.header.is_last_chunk(self.index),
+                &self.aad,
+                &mut chunk,
+            )?;
+
+            self.output = chunk;
+            self.output_pos = self.skip;
+            self.skip = 0;
+            self.index += 1;
+            self.filled = 0;
+        }
+        Ok(())
+    }
+}
+
+struct EncryptReader {
+    inner: BytesReader,
+    state: EncryptState,
+}
+
+impl AsyncRead for EncryptReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(n) = this.state.read_output(buf) {
+                return Poll::Ready(Ok(n));
+            }
+
+            let n = ready!(Pin::new(&mut this.inner).poll_read(cx, this.state.input_buf()))?;
+            this.state.consume_input(n)?;
+        }
+    }
+}
+
+struct BlockingEncryptReader {
+    inner: BlockingBytesReader,
+    state: EncryptState,
+}
+
+impl Read for BlockingEncryptReader {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        loop {
+            if let Some(n) = self.state.read_output(buf) {
+                return Ok(n);
+            }
+
+            let n = self.inner.read(self.state.input_buf())?;
+            self.state.consume_input(n)?;
+        }
+    }
+}
+
+struct DecryptReader {
+    inner: BytesReader,
+    state: DecryptState,
+}
+
+impl AsyncRead for DecryptReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(n) = this.state.read_output(buf) {
+                return Poll::Ready(Ok(n));
+            }
+
+            let n = ready!(Pin::new(&mut this.inner).poll_read(cx, this.state.input_buf()))?;
+            this.state.consume_input(n)?;
+        }
+    }
+}
+
+struct BlockingDecryptReader {
+    inner: BlockingBytesReader,
+    state: DecryptState,
+}
+
+impl Read for BlockingDecryptReader {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        loop {
+            if let Some(n) = self.state.read_output(buf) {
+                return Ok(n);
+            }
+
+            let n = self.inner.read(self.state.input_buf())?;
+            self.state.consume_input(n)?;
+        }
+    }
+}
+
+/// Build the metadata of plaintext.
+///
+/// `content_md5` is dropped because it's the md5 of ciphertext.
+fn plaintext_metadata(meta: ObjectMetadata, content_length: u64) -> ObjectMetadata {
+    let mut m = ObjectMetadata::new(meta.mode()).with_content_length(content_length);
+    if let Some(v) = meta.content_type() {
+        m.set_content_type(v);
+    }
+    if let Some(v) = meta.last_modified() {
+        m.set_last_modified(v);
+    }
+    if let Some(v) = meta.etag() {
+        m.set_etag(v);
+    }
+    m
+}
+
+/// Drop the ciphertext metadata of entries so that users will stat via
+/// our accessor.
+fn map_entry(acc: &Arc<dyn Accessor>, mut de: ObjectEntry) -> ObjectEntry {
+    if de.mode().is_dir() {
+        de.set_accessor(acc.clone());
+        return de;
+    }
+
+    ObjectEntry::new(acc.clone(), de.path(), ObjectMetadata::new(de.mode()))
+}
+
+struct EncryptionStreamer {
+    acc: Arc<dyn Accessor>,
+    inner: ObjectStreamer,
+}
+
+impl EncryptionStreamer {
+    fn new(acc: Arc<dyn Accessor>, inner: ObjectStreamer) -> Self {
+        Self { acc, inner }
+    }
+}
+
+impl Stream for EncryptionStreamer {
+    type Item = Result<ObjectEntry>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match Pin::new(&mut (*self.inner)).poll_next(cx) {
+            Poll::Ready(Some(Ok(de))) => Poll::Ready(Some(Ok(map_entry(&self.acc, de)))),
+            v => v,
+        }
+    }
+}
+
+struct EncryptionIterator {
+    acc: Arc<dyn Accessor>,
+    inner: ObjectIterator,
+}
+
+impl EncryptionIterator {
+    fn new(acc: Arc<dyn Accessor>, inner: ObjectIterator) -> Self {
+        Self { acc, inner }
+    }
+}
+
+impl Iterator for EncryptionIterator {
+    type Item = Result<ObjectEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.inner.next() {
+            Some(Ok(de)) => Some(Ok(map_entry(&self.acc, de))),
+            v => v,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::prelude::*;
+
+    use super::*;
+    use crate::services::memory;
+    use crate::Operator;
+
+    #[tokio::test]
+    async fn test_encryption() -> anyhow::Result<()> {
+        for algo in [
+            EncryptionAlgorithm::Aes256Gcm,
+            EncryptionAlgorithm::ChaCha20Poly1305,
+        ] {
+            let op = Operator::new(memory::Builder::default().build()?);
+            let encrypted_op = op.clone().layer(
+                EncryptionLayer::new(algo, StaticKeyProvider::new([1; 32])).with_chunk_size(1024),
+            );
+
+            let mut rng = thread_rng();
+            let mut content = vec![0; 10 * 1024 + 100];
+            rng.fill_bytes(&mut content);
+            encrypted_op.object("test").write(content.clone()).await?;
+
+            // Data stored in underlying storage should be encrypted.
+            let raw = op.object("test").read().await?;
+            assert_ne!(&raw[raw.len() - content.len()..], &content[..]);
+
+            // Stat should report the plaintext length.
+            let meta = encrypted_op.object("test").metadata().await?;
+            assert_eq!(meta.content_length(), content.len() as u64);
+
+            // Read should be decrypted.
+            let bs = encrypted_op.object("test").read().await?;
+            assert_eq!(bs, content);
+            for range in [0..1, 1000..1030, 1024..2048, 3000..10340, 10239..10340] {
+                let bs = encrypted_op
+                    .object("test")
+                    .range_read(range.clone())
+                    .await?;
+                assert_eq!(bs, &content[range.start as usize..range.end as usize]);
+            }
+
+            // Empty object.
+            encrypted_op.object("empty").create().await?;
+            let meta = encrypted_op.object("empty").metadata().await?;
+            assert_eq!(meta.content_length(), 0);
+            assert!(encrypted_op.object("empty").read().await?.is_empty());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_encryption_tampered() -> anyhow::Result<()> {
+        let op = Operator::new(memory::Builder::default().build()?);
+        let encrypted_op = op.clone().layer(
+            EncryptionLayer::new(
+                EncryptionAlgorithm::Aes256Gcm,
+                StaticKeyProvider::new([1; 32]),
+            )
+            .with_chunk_size(1024),
+        );
+
+        encrypted_op.object("test").write(vec![1; 4096]).await?;
+
+        // Flip the last byte of underlying object.
+        let mut raw = op.object("test").read().await?;
+        let last = raw.len() - 1;
+        raw[last] ^= 1;
+        op.object("test").write(raw.clone()).await?;
+        let err = encrypted_op.object("test").read().await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+        // Drop the last chunk of underlying object.
+        raw.truncate(raw.len() - 1024 - TAG_SIZE as usize);
+        op.object("test").write(raw).await?;
+        let err = encrypted_op.object("test").read().await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+        // Wrong master key should fail.
+        encrypted_op.object("test").write(vec![1; 4096]).await?;
+        let other_op = op.layer(EncryptionLayer::new(
+            EncryptionAlgorithm::Aes256Gcm,
+            StaticKeyProvider::new([2; 32]),
+        ));
+        let err = other_op.object("test").read().await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,385 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::future::Future;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use log::warn;
+#[cfg(feature = "layers-metrics")]
+use metrics::increment_counter;
+use tokio::time::Instant;
+
+use super::util::set_accessor_for_object_steamer;
+use crate::ops::OpList;
+use crate::ops::OpRead;
+use crate::ops::OpStat;
+use crate::ops::Operation;
+use crate::Accessor;
+use crate::AccessorMetadata;
+use crate::BytesReader;
+use crate::Layer;
+use crate::ObjectMetadata;
+use crate::ObjectStreamer;
+use crate::Operator;
+
+/// Metrics of requests sent to fallbacks.
+#[cfg(feature = "layers-metrics")]
+static METRIC_FAILOVER_TOTAL: &str = "opendal_failover_total";
+
+/// FailoverLayer will send `read`, `stat` and `list` to fallback operators
+/// if the primary is unavailable.
+///
+/// The operator this layer applied on is the primary, fallbacks will be
+/// tried in the order they were added. Only errors that mean the backend
+/// is unavailable will trigger failover, like `Interrupted` and `TimedOut`.
+/// Other errors like `NotFound` will be returned directly.
+///
+/// Failover happens only while starting operations. Errors returned while
+/// reading content or listing entries will not be recovered. Write
+/// operations and blocking operations are only sent to primary.
+///
+/// # Circuit Breaker
+///
+/// Every backend has a circuit breaker. After `failure_threshold`
+/// consecutive failures, the backend will be skipped for `cool_down`. Once
+/// cool down passed, the backend will be tried again and the breaker will
+/// be reset on success. If all backends are skipped, they will be tried
+/// anyway.
+///
+/// # Observability
+///
+/// Requests sent to fallbacks will be logged in `warn` level. With feature
+/// `layers-metrics` enabled, they will also be counted by
+/// `opendal_failover_total` with labels `operation` and `fallback`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use opendal::layers::FailoverLayer;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// let replica = Operator::from_env(Scheme::Memory).expect("must init");
+/// let _ = Operator::from_env(Scheme::Memory)
+///     .expect("must init")
+///     .layer(
+///         FailoverLayer::new()
+///             .with_fallback(replica)
+///             .with_failure_threshold(3)
+///             .with_cool_down(Duration::from_secs(30)),
+///     );
+/// ```
+#[derive(Debug, Clone)]
+pub struct FailoverLayer {
+    fallbacks: Vec<Operator>,
+    failure_threshold: u32,
+    cool_down: Duration,
+}
+
+impl Default for FailoverLayer {
+    fn default() -> Self {
+        Self {
+            fallbacks: Vec::new(),
+            failure_threshold: 3,
+            cool_down: Duration::from_secs(30),
+        }
+    }
+}
+
+impl FailoverLayer {
+    /// Create a new FailoverLayer without fallbacks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a fallback operator.
+    pub fn with_fallback(mut self, op: Operator) -> Self {
+        self.fallbacks.push(op);
+        self
+    }
+
+    /// Set consecutive failures to open the circuit breaker, default to `3`.
+    pub fn with_failure_threshold(mut self, threshold: u32) -> Self {
+        self.failure_threshold = threshold.max(1);
+        self
+    }
+
+    /// Set duration that an unhealthy backend will be skipped, default to
+    /// `30s`.
+    pub fn with_cool_down(mut self, cool_down: Duration) -> Self {
+        self.cool_down = cool_down;
+        self
+    }
+}
+
+impl Layer for FailoverLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        let backends = std::iter::once(inner.clone())
+            .chain(self.fallbacks.iter().map(|op| op.inner()))
+            .map(|accessor| Backend {
+                accessor,
+                breaker: Mutex::new(CircuitBreaker::default()),
+            })
+            .collect();
+
+        Arc::new(FailoverAccessor {
+            inner,
+            backends: Arc::new(backends),
+            failure_threshold: self.failure_threshold,
+            cool_down: self.cool_down,
+        })
+    }
+}
+
+/// Check if the error means backend is unavailable.
+fn is_unavailable(kind: ErrorKind) -> bool {
+    matches!(
+        kind,
+        ErrorKind::Interrupted
+            | ErrorKind::TimedOut
+            | ErrorKind::ConnectionRefused
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::NotConnected
+            | ErrorKind::BrokenPipe
+    )
+}
+
+#[derive(Debug, Default)]
+struct CircuitBreaker {
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+#[derive(Debug)]
+struct Backend {
+    accessor: Arc<dyn Accessor>,
+    breaker: Mutex<CircuitBreaker>,
+}
+
+#[derive(Debug, Clone)]
+struct FailoverAccessor {
+    inner: Arc<dyn Accessor>,
+    /// Primary is the first backend.
+    backends: Arc<Vec<Backend>>,
+    failure_threshold: u32,
+    cool_down: Duration,
+}
+
+impl FailoverAccessor {
+    fn is_available(&self, idx: usize) -> bool {
+        let breaker = self.backends[idx]
+            .breaker
+            .lock()
+            .expect("lock must succeed");
+        match breaker.open_until {
+            Some(t) => Instant::now() >= t,
+            None => true,
+        }
+    }
+
+    fn on_success(&self, idx: usize) {
+        let mut breaker = self.backends[idx]
+            .breaker
+            .lock()
+            .expect("lock must succeed");
+        *breaker = CircuitBreaker::default();
+    }
+
+    fn on_failure(&self, idx: usize) {
+        let mut breaker = self.backends[idx]
+            .breaker
+            .lock()
+            .expect("lock must succeed");
+        breaker.failures += 1;
+        if breaker.failures >= self.failure_threshold {
+            warn!(
+                target: "opendal::layers::failover",
+                "backend={idx} failed {} times, skipped for {:?}",
+                breaker.failures,
+                self.cool_down
+            );
+            breaker.open_until = Some(Instant::now() + self.cool_down);
+        }
+    }
+
+    async fn failover<T, F, Fut>(&self, op: Operation, path: &str, f: F) -> Result<T>
+    where
+        F: Fn(Arc<dyn Accessor>) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut candidates: Vec<usize> = (0..self.backends.len())
+            .filter(|idx| self.is_available(*idx))
+            .collect();
+        if candidates.is_empty() {
+            candidates = (0..self.backends.len()).collect();
+        }
+
+        let mut last_err = None;
+        for idx in candidates {
+            if idx > 0 {
+                warn!(
+                    target: "opendal::layers::failover",
+                    "operation={op} path={path} -> fallback to backend={idx}"
+                );
+                #[cfg(feature = "layers-metrics")]
+                increment_counter!(
+                    METRIC_FAILOVER_TOTAL,
+                    "operation" => op.into_static(),
+                    "fallback" => idx.to_string(),
+                );
+            }
+
+            match f(self.backends[idx].accessor.clone()).await {
+                Ok(v) => {
+                    self.on_success(idx);
+                    return Ok(v);
+                }
+                Err(err) if is_unavailable(err.kind()) => {
+                    self.on_failure(idx);
+                    last_err = Some(err);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        Err(last_err.expect("at least one backend must be tried"))
+    }
+}
+
+#[async_trait]
+impl Accessor for FailoverAccessor {
+    fn inner(&self) -> Option<Arc<dyn Accessor>> {
+        Some(self.inner.clone())
+    }
+
+    fn metadata(&self) -> AccessorMetadata {
+        self.inner.metadata()
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        let args = &args;
+        self.failover(Operation::Read, path, move |acc| async move {
+            acc.read(path, args.clone()).await
+        })
+        .await
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
+        let args = &args;
+        self.failover(Operation::Stat, path, move |acc| async move {
+            acc.stat(path, args.clone()).await
+        })
+        .await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<ObjectStreamer> {
+        let args = &args;
+        self.failover(Operation::List, path, move |acc| async move {
+            acc.list(path, args.clone()).await
+        })
+        .await
+        .map(|s| set_accessor_for_object_steamer(s, self.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+    use crate::services::memory;
+
+    /// MockService is always unavailable.
+    #[derive(Debug)]
+    struct MockService {
+        kind: ErrorKind,
+        attempts: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Accessor for MockService {
+        async fn read(&self, _: &str, _: OpRead) -> Result<BytesReader> {
+            self.attempts.fetch_add(1, Ordering::SeqCst);
+            Err(io::Error::new(self.kind, "mock error"))
+        }
+
+        async fn stat(&self, _: &str, _: OpStat) -> Result<ObjectMetadata> {
+            self.attempts.fetch_add(1, Ordering::SeqCst);
+            Err(io::Error::new(self.kind, "mock error"))
+        }
+    }
+
+    fn new_primary(kind: ErrorKind) -> (Operator, Arc<AtomicUsize>) {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let op = Operator::new(Arc::new(MockService {
+            kind,
+            attempts: attempts.clone(),
+        }));
+        (op, attempts)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_failover() -> anyhow::Result<()> {
+        let replica = Operator::new(memory::Builder::default().build()?);
+        replica.object("test").write("Hello, World!").await?;
+
+        let (primary, attempts) = new_primary(ErrorKind::Interrupted);
+        let op = primary.layer(
+            FailoverLayer::new()
+                .with_fallback(replica)
+                .with_failure_threshold(1)
+                .with_cool_down(Duration::from_secs(60)),
+        );
+
+        assert_eq!(op.object("test").read().await?, b"Hello, World!");
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+        // Primary is skipped while circuit breaker is open.
+        assert_eq!(op.object("test").metadata().await?.content_length(), 13);
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+        // Primary will be tried again after cool down.
+        tokio::time::advance(Duration::from_secs(61)).await;
+        assert_eq!(op.object("test").read().await?, b"Hello, World!");
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_failover_not_unavailable() -> anyhow::Result<()> {
+        let replica = Operator::new(memory::Builder::default().build()?);
+        replica.object("test").write("Hello, World!").await?;
+
+        let (primary, attempts) = new_primary(ErrorKind::PermissionDenied);
+        let op = primary.layer(FailoverLayer::new().with_fallback(replica));
+
+        let err = op.object("test").read().await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,402 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::future::Future;
+use std::io::Result;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::future::select;
+use futures::future::Either;
+use futures::io::Cursor;
+use futures::AsyncReadExt;
+use tokio::time::Instant;
+
+use super::util::set_accessor_for_object_steamer;
+use crate::ops::OpList;
+use crate::ops::OpRead;
+use crate::ops::OpStat;
+use crate::Accessor;
+use crate::AccessorMetadata;
+use crate::BytesReader;
+use crate::Layer;
+use crate::ObjectMetadata;
+use crate::ObjectStreamer;
+
+/// Max latency samples kept for percentile.
+const MAX_SAMPLES: usize = 1024;
+/// Min latency samples required before using percentile.
+const MIN_SAMPLES: usize = 32;
+/// Buffer size used to wait for the first bytes of `read`.
+const FIRST_CHUNK_SIZE: usize = 8 * 1024;
+
+/// HedgeLayer will send a duplicate request if the first one is slow, and
+/// use whichever answers first.
+///
+/// Only idempotent operations `read` and `stat` will be hedged. For `read`,
+/// a request is answered while the first bytes of content arrive.
+///
+/// # Delay
+///
+/// Duplicate requests will be sent after a fixed delay by default. Use
+/// [`HedgeLayer::with_percentile`] to use the percentile of recent latencies
+/// instead, the fixed delay will be used until enough samples collected.
+///
+/// # Budget
+///
+/// Duplicate requests are capped by a budget, which is the max ratio of
+/// duplicate requests to all requests, default to `0.1`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use opendal::layers::HedgeLayer;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// let layer = HedgeLayer::new(Duration::from_millis(100))
+///     .with_percentile(0.95)
+///     .with_budget(0.05);
+/// let stats = layer.stats();
+///
+/// let _ = Operator::from_env(Scheme::Memory)
+///     .expect("must init")
+///     .layer(layer);
+///
+/// println!("hedged requests won: {}", stats.wins());
+/// ```
+#[derive(Debug, Clone)]
+pub struct HedgeLayer {
+    delay: Duration,
+    percentile: Option<f64>,
+    budget: f64,
+    stats: HedgeStats,
+}
+
+impl HedgeLayer {
+    /// Create a new HedgeLayer that sends duplicate requests after delay.
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            percentile: None,
+            budget: 0.1,
+            stats: HedgeStats::default(),
+        }
+    }
+
+    /// Use the percentile of recent latencies as delay, like `0.95`.
+    pub fn with_percentile(mut self, percentile: f64) -> Self {
+        self.percentile = Some(percentile.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Set the max ratio of duplicate requests to all requests.
+    pub fn with_budget(mut self, budget: f64) -> Self {
+        self.budget = budget.max(0.0);
+        self
+    }
+
+    /// Get the stats shared by all accessors built by this layer.
+    pub fn stats(&self) -> HedgeStats {
+        self.stats.clone()
+    }
+}
+
+impl Layer for HedgeLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(HedgeAccessor {
+            inner,
+            delay: self.delay,
+            percentile: self.percentile,
+            budget: self.budget,
+            stats: self.stats.clone(),
+            latencies: Arc::new(Mutex::new(VecDeque::with_capacity(MAX_SAMPLES))),
+        })
+    }
+}
+
+/// Stats of [`HedgeLayer`].
+#[derive(Debug, Clone, Default)]
+pub struct HedgeStats {
+    inner: Arc<HedgeStatsInner>,
+}
+
+#[derive(Debug, Default)]
+struct HedgeStatsInner {
+    requests: AtomicU64,
+    hedged: AtomicU64,
+    wins: AtomicU64,
+    losses: AtomicU64,
+}
+
+impl HedgeStats {
+    /// Requests that could be hedged.
+    pub fn requests(&self) -> u64 {
+        self.inner.requests.load(Ordering::Relaxed)
+    }
+
+    /// Duplicate requests that have been sent.
+    pub fn hedged(&self) -> u64 {
+        self.inner.hedged.load(Ordering::Relaxed)
+    }
+
+    /// Duplicate requests whose results are returned.
+    pub fn wins(&self) -> u64 {
+        self.inner.wins.load(Ordering::Relaxed)
+    }
+
+    /// Duplicate requests whose results are dropped for the original ones.
+    pub fn losses(&self) -> u64 {
+        self.inner.losses.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HedgeAccessor {
+    inner: Arc<dyn Accessor>,
+    delay: Duration,
+    percentile: Option<f64>,
+    budget: f64,
+    stats: HedgeStats,
+    latencies: Arc<Mutex<VecDeque<Duration>>>,
+}
+
+impl HedgeAccessor {
+    fn delay(&self) -> Duration {
+        let percentile = match self.percentile {
+            Some(v) => v,
+            None => return self.delay,
+        };
+
+        let mut samples: Vec<_> = {
+            let latencies = self.latencies.lock().expect("lock must succeed");
+            if latencies.len() < MIN_SAMPLES {
+                return self.delay;
+            }
+            latencies.iter().copied().collect()
+        };
+        samples.sort_unstable();
+
+        let idx = ((samples.len() - 1) as f64 * percentile).round() as usize;
+        samples[idx]
+    }
+
+    fn record(&self, latency: Duration) {
+        let mut latencies = self.latencies.lock().expect("lock must succeed");
+        if latencies.len() >= MAX_SAMPLES {
+            latencies.pop_front();
+        }
+        latencies.push_back(latency);
+    }
+
+    /// Try to take a duplicate request from budget.
+    fn acquire_budget(&self) -> bool {
+        let requests = self.stats.inner.requests.load(Ordering::Relaxed);
+        let hedged = self.stats.inner.hedged.load(Ordering::Relaxed);
+        if (hedged + 1) as f64 > requests as f64 * self.budget {
+            return false;
+        }
+
+        self.stats.inner.hedged.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    async fn hedge<T, F, Fut>(&self, f: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.stats.inner.requests.fetch_add(1, Ordering::Relaxed);
+        let start = Instant::now();
+
+        let mut primary = Box::pin(f());
+        if let Ok(res) = tokio::time::timeout(self.delay(), &mut primary).await {
+            self.record(start.elapsed());
+            return res;
+        }
+        if !self.acquire_budget() {
+            let res = primary.await;
+            self.record(start.elapsed());
+            return res;
+        }
+
+        // The loser will be cancelled while dropped.
+        let hedged = Box::pin(f());
+        // Count the side whose result is actually returned.
+        let (res, won) = match select(primary, hedged).await {
+            Either::Left((Ok(v), _)) => (Ok(v), false),
+            Either::Left((Err(_), hedged)) => (hedged.await, true),
+            Either::Right((Ok(v), _)) => (Ok(v), true),
+            Either::Right((Err(_), primary)) => (primary.await, false),
+        };
+        if won {
+            self.stats.inner.wins.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.stats.inner.losses.fetch_add(1, Ordering::Relaxed);
+        }
+        self.record(start.elapsed());
+        res
+    }
+}
+
+#[async_trait]
+impl Accessor for HedgeAccessor {
+    fn inner(&self) -> Option<Arc<dyn Accessor>> {
+        Some(self.inner.clone())
+    }
+
+    fn metadata(&self) -> AccessorMetadata {
+        self.inner.metadata()
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        let (inner, args) = (&self.inner, &args);
+        self.hedge(move || async move {
+            let mut r = inner.read(path, args.clone()).await?;
+
+            // Wait for the first bytes of content.
+            let mut buf = vec![0; FIRST_CHUNK_SIZE];
+            let n = r.read(&mut buf).await?;
+            buf.truncate(n);
+            Ok(Box::new(Cursor::new(buf).chain(r)) as BytesReader)
+        })
+        .await
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
+        let (inner, args) = (&self.inner, &args);
+        self.hedge(move || inner.stat(path, args.clone())).await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<ObjectStreamer> {
+        self.inner
+            .list(path, args)
+            .await
+            .map(|s| set_accessor_for_object_steamer(s, self.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Error;
+    use std::io::ErrorKind;
+    use std::sync::atomic::AtomicUsize;
+
+    use anyhow::anyhow;
+
+    use super::*;
+    use crate::ObjectMode;
+    use crate::Operator;
+
+    /// MockService will hang on the first request.
+    #[derive(Debug, Default)]
+    struct MockService {
+        attempt: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Accessor for MockService {
+        async fn read(&self, _: &str, _: OpRead) -> Result<BytesReader> {
+            if self.attempt.fetch_add(1, Ordering::SeqCst) == 0 {
+                tokio::time::sleep(Duration::from_secs(3600)).await;
+            }
+            Ok(Box::new(Cursor::new(b"Hello, World!".to_vec())))
+        }
+
+        async fn stat(&self, _: &str, _: OpStat) -> Result<ObjectMetadata> {
+            if self.attempt.fetch_add(1, Ordering::SeqCst) == 0 {
+                tokio::time::sleep(Duration::from_secs(3600)).await;
+            }
+            Ok(ObjectMetadata::new(ObjectMode::FILE).with_content_length(13))
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_hedge() -> anyhow::Result<()> {
+        let layer = HedgeLayer::new(Duration::from_millis(100)).with_budget(1.0);
+        let stats = layer.stats();
+        let op = Operator::new(Arc::new(MockService::default())).layer(layer);
+
+        let start = Instant::now();
+        assert_eq!(op.object("test").read().await?, b"Hello, World!");
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        assert_eq!(stats.requests(), 1);
+        assert_eq!(stats.hedged(), 1);
+        assert_eq!(stats.wins(), 1);
+        assert_eq!(stats.losses(), 0);
+
+        Ok(())
+    }
+
+    /// MockFailService will answer the first request slowly and fail others.
+    #[derive(Debug, Default)]
+    struct MockFailService {
+        attempt: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Accessor for MockFailService {
+        async fn stat(&self, _: &str, _: OpStat) -> Result<ObjectMetadata> {
+            if self.attempt.fetch_add(1, Ordering::SeqCst) == 0 {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                return Ok(ObjectMetadata::new(ObjectMode::FILE).with_content_length(13));
+            }
+            Err(Error::new(
+                ErrorKind::Other,
+                anyhow!("hedged request failed"),
+            ))
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_hedge_failed() -> anyhow::Result<()> {
+        let layer = HedgeLayer::new(Duration::from_millis(100)).with_budget(1.0);
+        let stats = layer.stats();
+        let op = Operator::new(Arc::new(MockFailService::default())).layer(layer);
+
+        // Hedged request finished first but failed, primary result is returned.
+        assert_eq!(op.object("test").metadata().await?.content_length(), 13);
+
+        assert_eq!(stats.hedged(), 1);
+        assert_eq!(stats.wins(), 0);
+        assert_eq!(stats.losses(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_hedge_budget() -> anyhow::Result<()> {
+        let layer = HedgeLayer::new(Duration::from_millis(100)).with_budget(0.0);
+        let stats = layer.stats();
+        let op = Operator::new(Arc::new(MockService::default())).layer(layer);
+
+        let start = Instant::now();
+        assert_eq!(op.object("test").metadata().await?.content_length(), 13);
+        assert!(start.elapsed() >= Duration::from_secs(3600));
+
+        assert_eq!(stats.requests(), 1);
+        assert_eq!(stats.hedged(), 0);
+
+        Ok(())
+    }
+}
@@ -23,11 +23,16 @@ use std::task::Poll;
 use std::vec::IntoIter;
 
 use async_trait::async_trait;
+use bytes::Bytes;
 
 use crate::accessor::AccessorCapability;
 use crate::ops::OpList;
+use crate::ops::OpRead;
+use crate::ops::OpReadRanges;
+use crate::ops::OpWrite;
 use crate::Accessor;
 use crate::AccessorMetadata;
+use crate::BytesStreamer;
 use crate::Layer;
 use crate::ObjectEntry;
 use crate::ObjectIterator;
@@ -145,6 +150,18 @@ impl Accessor for ImmutableIndexAccessor {
         meta
     }
 
+    async fn read_stream(&self, path: &str, args: OpRead) -> Result<BytesStreamer> {
+        self.inner.read_stream(path, args).await
+    }
+
+    async fn read_ranges(&self, path: &str, args: OpReadRanges) -> Result<Vec<Bytes>> {
+        self.inner.read_ranges(path, args).await
+    }
+
+    async fn write_stream(&self, path: &str, args: OpWrite, s: BytesStreamer) -> Result<u64> {
+        self.inner.write_stream(path, args, s).await
+    }
+
     async fn list(&self, path: &str, _: OpList) -> Result<ObjectStreamer> {
         let mut path = path;
         if path == "/" {
@@ -22,6 +22,7 @@ use std::task::Context;
 use std::task::Poll;
 
 use async_trait::async_trait;
+use bytes::Bytes;
 use futures::AsyncRead;
 use futures::Stream;
 use log::debug;
@@ -37,6 +38,7 @@ use crate::ops::OpDelete;
 use crate::ops::OpList;
 use crate::ops::OpPresign;
 use crate::ops::OpRead;
+use crate::ops::OpReadRanges;
 use crate::ops::OpStat;
 use crate::ops::OpWrite;
 use crate::ops::OpWriteMultipart;
@@ -46,6 +48,7 @@ use crate::Accessor;
 use crate::AccessorMetadata;
 use crate::BlockingBytesReader;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::Layer;
 use crate::ObjectEntry;
 use crate::ObjectIterator;
@@ -235,6 +238,117 @@ impl Accessor for LoggingAccessor {
             })
     }
 
+    async fn read_stream(&self, path: &str, args: OpRead) -> Result<BytesStreamer> {
+        debug!(
+            target: "opendal::services",
+            "service={} operation={} path={} offset={:?} size={:?} -> started",
+            self.scheme, Operation::Read, path, args.offset(), args.size()
+        );
+
+        self.inner
+            .read_stream(path, args.clone())
+            .await
+            .map(|v| {
+                debug!(
+                    target: "opendal::services",
+                    "service={} operation={} path={} offset={:?} size={:?} -> got stream",
+                    self.scheme, Operation::Read, path,
+                    args.offset(), args.size()
+                );
+                let s =
+                    LoggingBytesStreamer::new(self.scheme, Operation::Read, path, args.size(), v);
+                Box::new(s) as BytesStreamer
+            })
+            .map_err(|err| {
+                if err.kind() == ErrorKind::Other {
+                    error!(
+                        target: "opendal::services",
+                        "service={} operation={} path={} offset={:?} size={:?} -> failed: {err:?}",
+                        self.scheme, Operation::Read, path, args.offset(), args.size());
+                } else {
+                    warn!(
+                        target: "opendal::services",
+                        "service={} operation={} path={} offset={:?} size={:?} -> errored: {err:?}",
+                        self.scheme, Operation::Read, path, args.offset(), args.size());
+                };
+                err
+            })
+    }
+
+    async fn read_ranges(&self, path: &str, args: OpReadRanges) -> Result<Vec<Bytes>> {
+        debug!(
+            target: "opendal::services",
+            "service={} operation={} path={} ranges={:?} -> started",
+            self.scheme, Operation::Read, path, args.ranges()
+        );
+
+        self.inner
+            .read_ranges(path, args.clone())
+            .await
+            .map(|v| {
+                debug!(
+                    target: "opendal::services",
+                    "service={} operation={} path={} ranges={:?} -> finished",
+                    self.scheme, Operation::Read, path, args.ranges()
+                );
+                v
+            })
+            .map_err(|err| {
+                if err.kind() == ErrorKind::Other {
+                    error!(
+                        target: "opendal::services",
+                        "service={} operation={} path={} ranges={:?} -> failed: {err:?}",
+                        self.scheme, Operation::Read, path, args.ranges());
+                } else {
+                    warn!(
+                        target: "opendal::services",
+                        "service={} operation={} path={} ranges={:?} -> errored: {err:?}",
+                        self.scheme, Operation::Read, path, args.ranges());
+                };
+                err
+            })
+    }
+
+    async fn write_stream(&self, path: &str, args: OpWrite, s: BytesStreamer) -> Result<u64> {
+        debug!(
+            target: "opendal::services",
+            "service={} operation={} path={} size={:?} -> started",
+            self.scheme, Operation::Write, path, args.size()
+        );
+
+        let stream =
+            LoggingBytesStreamer::new(self.scheme, Operation::Write, path, Some(args.size()), s);
+        let s = Box::new(stream) as BytesStreamer;
+
+        self.inner
+            .write_stream(path, args.clone(), s)
+            .await
+            .map(|v| {
+                debug!(
+                    target: "opendal::services",
+                    "service={} operation={} path={} size={:?} -> written",
+                    self.scheme, Operation::Write, path, args.size()
+                );
+                v
+            })
+            .map_err(|err| {
+                if err.kind() == ErrorKind::Other {
+                    error!(
+                        target: "opendal::services",
+                        "service={} operation={} path={} size={:?} -> failed: {err:?}",
+                        self.scheme, Operation::Write, path, args.size()
+                    );
+                } else {
+                    warn!(
+                        target: "opendal::services",
+                        "service={} operation={} path={} size={:?} -> errored: {err:?}",
+                        self.scheme, Operation::Write, path, args.size()
+                    );
+                };
+                err
+            })
+    }
+
     async fn stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
         debug!(
             target: "opendal::services",
@@ -906,6 +1020,98 @@ impl AsyncRead for LoggingReader {
     }
 }
 
+/// `LoggingBytesStreamer` is a wrapper of `BytesStreamer`, with logging functionality.
+struct LoggingBytesStreamer {
+    scheme: Scheme,
+    path: String,
+    op: Operation,
+
+    size: Option<u64>,
+    has_read: u64,
+
+    inner: BytesStreamer,
+}
+
+impl LoggingBytesStreamer {
+    fn new(
+        scheme: Scheme,
+        op: Operation,
+        path: &str,
+        size: Option<u64>,
+        stream: BytesStreamer,
+    ) -> Self {
+        Self {
+            scheme,
+            op,
+            path: path.to_string(),
+
+            size,
+            has_read: 0,
+
+            inner: stream,
+        }
+    }
+}
+
+impl Drop for LoggingBytesStreamer {
+    fn drop(&mut self) {
+        if let Some(size) = self.size {
+            if size == self.has_read {
+                debug!(
+                target: "opendal::services",
+                "service={} operation={} path={} has_read={} -> consumed stream fully",
+                self.scheme, self.op, self.path, self.has_read);
+
+                return;
+            }
+        }
+
+        debug!(
+            target: "opendal::services",
+            "service={} operation={} path={} has_read={} -> dropped stream",
+            self.scheme, self.op, self.path, self.has_read);
+    }
+}
+
+impl Stream for LoggingBytesStreamer {
+    type Item = Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match Pin::new(&mut (*self.inner)).poll_next(cx) {
+            Poll::Ready(Some(Ok(bs))) => {
+                self.has_read += bs.len() as u64;
+                trace!(
+                    target: "opendal::services",
+                    "service={} operation={} path={} has_read={} -> {}: {}B",
+                    self.scheme, self.op, self.path, self.has_read, self.op, bs.len());
+                Poll::Ready(Some(Ok(bs)))
+            }
+            Poll::Ready(Some(Err(e))) => {
+                if e.kind() == ErrorKind::Other {
+                    error!(
+                        target: "opendal::services",
+                        "service={} operation={} path={} has_read={} -> failed: {:?}",
+                        self.scheme, self.op, self.path, self.has_read, e);
+                } else {
+                    warn!(
+                        target: "opendal::services",
+                        "service={} operation={} path={} has_read={} -> errored: {:?}",
+                        self.scheme, self.op, self.path, self.has_read, e);
+                }
+                Poll::Ready(Some(Err(e)))
+            }
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => {
+                trace!(
+                    target: "opendal::services",
+                    "service={} operation={} path={} has_read={} -> pending",
+                    self.scheme, self.op, self.path, self.has_read);
+                Poll::Pending
+            }
+        }
+    }
+}
+
 /// `BlockingLoggingReader` is a wrapper of `BlockingBytesReader`, with logging functionality.
 struct BlockingLoggingReader {
     scheme: Scheme,
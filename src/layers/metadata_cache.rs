@@ -13,41 +13,79 @@
 // limitations under the License.
 
 use std::fmt::Debug;
+use std::io::Error;
 use std::io::ErrorKind;
 use std::io::Read;
 use std::io::Result;
 use std::sync::Arc;
+use std::time::Duration;
 
+use anyhow::anyhow;
 use async_trait::async_trait;
+use bytes::Bytes;
 use futures::io;
 use futures::io::Cursor;
+use futures::stream;
+use futures::StreamExt;
+use futures::TryStreamExt;
+use log::debug;
+use serde::Deserialize;
+use serde::Serialize;
+use time::OffsetDateTime;
 
 use super::util::set_accessor_for_object_iterator;
 use super::util::set_accessor_for_object_steamer;
 use crate::error::new_other_object_error;
+use crate::error::ObjectError;
 use crate::ops::OpCompleteMultipart;
 use crate::ops::OpCreate;
 use crate::ops::OpDelete;
 use crate::ops::OpList;
 use crate::ops::OpRead;
+use crate::ops::OpReadRanges;
 use crate::ops::OpStat;
 use crate::ops::OpWrite;
 use crate::ops::Operation;
+use crate::path::get_parent;
 use crate::Accessor;
 use crate::BlockingBytesReader;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::Layer;
+use crate::ObjectEntry;
 use crate::ObjectIterator;
 use crate::ObjectMetadata;
 use crate::ObjectStreamer;
 
 /// MetadataCacheLayer will add metadata cache support for OpenDAL.
 ///
+/// # Cache
+///
+/// - `stat` results are cached, `NotFound` will also be cached if
+///   [`MetadataCacheLayer::with_negative_ttl`] is set.
+/// - `list` results are cached per dir, dirs that have more entries than
+///   [`MetadataCacheLayer::with_max_list_entries`] will not be cached.
+/// - `create`, `write`, `delete` and `complete_multipart` will invalidate
+///   the cached `stat` of the path and `list` of all its ancestors, both
+///   before and after the operation.
+///
+/// Entries never expire by default, use [`MetadataCacheLayer::with_ttl`] to
+/// set the TTL. Expiration is checked by wall clock, so that entries can be
+/// shared across nodes.
+///
+/// # Size
+///
+/// This layer doesn't bound the size of cache by itself. Every cached result
+/// is written into the cache service, and expired entries are ignored but not
+/// removed. Please use a cache service with capacity and eviction like
+/// [`disk_cache`][crate::services::disk_cache] or `moka`, cache services
+/// without eviction like `memory` will grow without bound.
+///
 /// # Notes
 ///
 /// This layer only maintains its own states. Users should care about the cache
 /// consistency by themselves. For example, in the following situations, users
-/// could get out-dated metadata cache:
+/// could get out-dated metadata cache before entries expired:
 ///
 /// - Users have operations on underlying operator directly.
 /// - Other nodes have operations on underlying storage directly.
@@ -59,6 +97,8 @@ use crate::ObjectStreamer;
 /// # Examples
 ///
 /// ```
+/// use std::time::Duration;
+///
 /// use anyhow::Result;
 /// use opendal::layers::MetadataCacheLayer;
 /// use opendal::services::memory;
@@ -67,13 +107,18 @@ use crate::ObjectStreamer;
 ///
 /// let _ = Operator::from_env(Scheme::Fs)
 ///     .expect("must init")
-///     .layer(MetadataCacheLayer::new(
-///         memory::Builder::default().build().expect("must init"),
-///     ));
+///     .layer(
+///         MetadataCacheLayer::new(memory::Builder::default().build().expect("must init"))
+///             .with_ttl(Duration::from_secs(60))
+///             .with_negative_ttl(Duration::from_secs(5)),
+///     );
 /// ```
 #[derive(Debug, Clone)]
 pub struct MetadataCacheLayer {
     cache: Arc<dyn Accessor>,
+    ttl: Option<Duration>,
+    negative_ttl: Option<Duration>,
+    max_list_entries: usize,
 }
 
 impl MetadataCacheLayer {
@@ -81,8 +126,31 @@ impl MetadataCacheLayer {
     pub fn new(acc: impl Accessor + 'static) -> Self {
         Self {
             cache: Arc::new(acc),
+            ttl: None,
+            negative_ttl: None,
+            max_list_entries: 1000,
         }
     }
+
+    /// Set TTL of cached `stat` and `list` results.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Cache `NotFound` of `stat` with TTL.
+    pub fn with_negative_ttl(mut self, ttl: Duration) -> Self {
+        self.negative_ttl = Some(ttl);
+        self
+    }
+
+    /// Set max entries of a dir to cache `list` results, default to `1000`.
+    ///
+    /// Set to `0` to disable `list` cache.
+    pub fn with_max_list_entries(mut self, max: usize) -> Self {
+        self.max_list_entries = max;
+        self
+    }
 }
 
 impl Layer for MetadataCacheLayer {
@@ -90,14 +158,217 @@ impl Layer for MetadataCacheLayer {
         Arc::new(MetadataCacheAccessor {
             cache: self.cache.clone(),
             inner,
+            ttl: self.ttl,
+            negative_ttl: self.negative_ttl,
+            max_list_entries: self.max_list_entries,
         })
     }
 }
 
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    /// Unix timestamp in milliseconds, never expire if `None`.
+    expire_at: Option<i64>,
+    value: CacheValue,
+}
+
+#[derive(Serialize, Deserialize)]
+enum CacheValue {
+    Stat(ObjectMetadata),
+    NotFound,
+    List(Vec<CacheListEntry>),
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheListEntry {
+    path: String,
+    meta: ObjectMetadata,
+    complete: bool,
+}
+
+fn now_millis() -> i64 {
+    (OffsetDateTime::now_utc().unix_timestamp_nanos() / 1_000_000) as i64
+}
+
+/// Build cache key for path.
+///
+/// Trailing `/` will be escaped so that dirs can be stored as files in
+/// cache services.
+fn cache_key(kind: &str, path: &str) -> String {
+    let path = path.replace('%', "%25");
+    match path.strip_suffix('/') {
+        Some(p) => format!("{kind}/{p}%2F"),
+        None => format!("{kind}/{path}"),
+    }
+}
+
+fn stat_key(path: &str) -> String {
+    cache_key("stat", path)
+}
+
+fn list_key(path: &str) -> String {
+    cache_key("list", path)
+}
+
+/// Keys to invalidate while path changed: `stat` of the path, `list` of the
+/// path if it's a dir, and `list` of all its ancestors.
+fn invalidate_keys(path: &str) -> Vec<String> {
+    let mut keys = vec![stat_key(path)];
+    if path.ends_with('/') {
+        keys.push(list_key(path));
+    }
+
+    let mut path = path;
+    while path != "/" {
+        path = get_parent(path);
+        keys.push(list_key(path));
+    }
+    keys
+}
+
+fn encode(op: Operation, path: &str, value: CacheValue, ttl: Option<Duration>) -> Result<Vec<u8>> {
+    let entry = CacheEntry {
+        expire_at: ttl.map(|v| now_millis() + v.as_millis() as i64),
+        value,
+    };
+    bincode::serde::encode_to_vec(&entry, bincode::config::standard())
+        .map_err(|err| new_other_object_error(op, path, err))
+}
+
+/// Decode cached value, returns `None` if it's expired or broken.
+fn decode(key: &str, bs: &[u8]) -> Option<CacheValue> {
+    let entry: CacheEntry = match bincode::serde::decode_from_slice(bs, bincode::config::standard())
+    {
+        Ok((v, _)) => v,
+        Err(err) => {
+            debug!(
+                target: "opendal::layers::metadata_cache",
+                "key={key} -> decode failed: {err:?}"
+            );
+            return None;
+        }
+    };
+
+    match entry.expire_at {
+        Some(t) if now_millis() >= t => None,
+        _ => Some(entry.value),
+    }
+}
+
+fn new_cached_not_found_error(op: Operation, path: &str) -> Error {
+    Error::new(
+        ErrorKind::NotFound,
+        ObjectError::new(op, path, anyhow!("object not found, cached")),
+    )
+}
+
 #[derive(Debug, Clone)]
 struct MetadataCacheAccessor {
     cache: Arc<dyn Accessor>,
     inner: Arc<dyn Accessor>,
+    ttl: Option<Duration>,
+    negative_ttl: Option<Duration>,
+    max_list_entries: usize,
+}
+
+impl MetadataCacheAccessor {
+    async fn get(&self, key: &str) -> Result<Option<CacheValue>> {
+        match self.cache.read(key, OpRead::new(..)).await {
+            Ok(r) => {
+                let buffer = Vec::with_capacity(1024);
+                let mut bs = Cursor::new(buffer);
+                io::copy(r, &mut bs).await?;
+                Ok(decode(key, &bs.into_inner()))
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn put(
+        &self,
+        op: Operation,
+        key: &str,
+        value: CacheValue,
+        ttl: Option<Duration>,
+    ) -> Result<()> {
+        let bs = encode(op, key, value, ttl)?;
+        self.cache
+            .write(
+                key,
+                OpWrite::new(bs.len() as u64),
+                Box::new(Cursor::new(bs)),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Invalidate cached `stat` of path and `list` of all its ancestors.
+    async fn invalidate(&self, path: &str) -> Result<()> {
+        for key in invalidate_keys(path) {
+            self.cache.delete(&key, OpDelete::new()).await?;
+        }
+        Ok(())
+    }
+
+    fn blocking_get(&self, key: &str) -> Result<Option<CacheValue>> {
+        match self.cache.blocking_read(key, OpRead::new(..)) {
+            Ok(mut r) => {
+                let mut bs = Vec::with_capacity(1024);
+                r.read_to_end(&mut bs)?;
+                Ok(decode(key, &bs))
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn blocking_put(
+        &self,
+        op: Operation,
+        key: &str,
+        value: CacheValue,
+        ttl: Option<Duration>,
+    ) -> Result<()> {
+        let bs = encode(op, key, value, ttl)?;
+        self.cache.blocking_write(
+            key,
+            OpWrite::new(bs.len() as u64),
+            Box::new(std::io::Cursor::new(bs)),
+        )?;
+        Ok(())
+    }
+
+    fn blocking_invalidate(&self, path: &str) -> Result<()> {
+        for key in invalidate_keys(path) {
+            self.cache.blocking_delete(&key, OpDelete::new())?;
+        }
+        Ok(())
+    }
+
+    fn to_cache_value(entries: &[ObjectEntry]) -> CacheValue {
+        CacheValue::List(
+            entries
+                .iter()
+                .map(|de| CacheListEntry {
+                    path: de.path().to_string(),
+                    meta: de.metadata_raw(),
+                    complete: de.is_complete(),
+                })
+                .collect(),
+        )
+    }
+
+    fn build_entries(&self, entries: Vec<CacheListEntry>) -> Vec<Result<ObjectEntry>> {
+        let acc: Arc<dyn Accessor> = Arc::new(self.clone());
+        entries
+            .into_iter()
+            .map(|v| {
+                let de = ObjectEntry::new(acc.clone(), &v.path, v.meta);
+                Ok(if v.complete { de.with_complete() } else { de })
+            })
+            .collect()
+    }
 }
 
 #[async_trait]
@@ -107,106 +378,190 @@ impl Accessor for MetadataCacheAccessor {
     }
 
     async fn create(&self, path: &str, args: OpCreate) -> Result<()> {
-        self.cache.delete(path, OpDelete::new()).await?;
-        self.inner.create(path, args).await
+        self.invalidate(path).await?;
+        self.inner.create(path, args).await?;
+        self.invalidate(path).await
     }
 
     async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<u64> {
-        self.cache.delete(path, OpDelete::new()).await?;
-        self.inner.write(path, args, r).await
+        self.invalidate(path).await?;
+        let n = self.inner.write(path, args, r).await?;
+        self.invalidate(path).await?;
+        Ok(n)
+    }
+
+    async fn read_stream(&self, path: &str, args: OpRead) -> Result<BytesStreamer> {
+        self.inner.read_stream(path, args).await
+    }
+
+    async fn read_ranges(&self, path: &str, args: OpReadRanges) -> Result<Vec<Bytes>> {
+        self.inner.read_ranges(path, args).await
+    }
+
+    async fn write_stream(&self, path: &str, args: OpWrite, s: BytesStreamer) -> Result<u64> {
+        self.invalidate(path).await?;
+        let n = self.inner.write_stream(path, args, s).await?;
+        self.invalidate(path).await?;
+        Ok(n)
     }
 
     async fn stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
-        match self.cache.read(path, OpRead::new(..)).await {
-            Ok(r) => {
-                let buffer = Vec::with_capacity(1024);
-                let mut bs = Cursor::new(buffer);
-                io::copy(r, &mut bs).await?;
+        let key = stat_key(path);
+        match self.get(&key).await? {
+            Some(CacheValue::Stat(meta)) => return Ok(meta),
+            Some(CacheValue::NotFound) => {
+                return Err(new_cached_not_found_error(Operation::Stat, path));
+            }
+            _ => {}
+        }
 
-                let (meta, _) = bincode::serde::decode_from_slice(
-                    &bs.into_inner(),
-                    bincode::config::standard(),
+        match self.inner.stat(path, args).await {
+            Ok(meta) => {
+                self.put(
+                    Operation::Stat,
+                    &key,
+                    CacheValue::Stat(meta.clone()),
+                    self.ttl,
                 )
-                .map_err(|err| new_other_object_error(Operation::Stat, path, err))?;
+                .await?;
                 Ok(meta)
             }
             Err(err) if err.kind() == ErrorKind::NotFound => {
-                let meta = self.inner.stat(path, args).await?;
-                let bs = bincode::serde::encode_to_vec(&meta, bincode::config::standard())
-                    .map_err(|err| new_other_object_error(Operation::Stat, path, err))?;
-                self.cache
-                    .write(
-                        path,
-                        OpWrite::new(bs.len() as u64),
-                        Box::new(Cursor::new(bs)),
-                    )
-                    .await?;
-                Ok(meta)
+                if let Some(ttl) = self.negative_ttl {
+                    self.put(Operation::Stat, &key, CacheValue::NotFound, Some(ttl))
+                        .await?;
+                }
+                Err(err)
             }
             Err(err) => Err(err),
         }
     }
 
     async fn delete(&self, path: &str, args: OpDelete) -> Result<()> {
-        self.cache.delete(path, OpDelete::new()).await?;
-        self.inner.delete(path, args).await
+        self.invalidate(path).await?;
+        self.inner.delete(path, args).await?;
+        self.invalidate(path).await
     }
 
     async fn list(&self, path: &str, args: OpList) -> Result<ObjectStreamer> {
-        self.inner
-            .list(path, args)
-            .await
-            .map(|s| set_accessor_for_object_steamer(s, self.clone()))
+        let key = list_key(path);
+        if self.max_list_entries > 0 {
+            if let Some(CacheValue::List(entries)) = self.get(&key).await? {
+                return Ok(Box::new(stream::iter(self.build_entries(entries))));
+            }
+        }
+
+        let mut s = self.inner.list(path, args).await?;
+        let mut entries = Vec::new();
+        while self.max_list_entries > 0 && entries.len() <= self.max_list_entries {
+            match s.try_next().await? {
+                Some(de) => entries.push(de),
+                None => {
+                    self.put(
+                        Operation::List,
+                        &key,
+                        Self::to_cache_value(&entries),
+                        self.ttl,
+                    )
+                    .await?;
+                    break;
+                }
+            }
+        }
+
+        // Dirs that have too many entries will be streamed without cache.
+        let s = stream::iter(entries.into_iter().map(Ok)).chain(s);
+        Ok(set_accessor_for_object_steamer(Box::new(s), self.clone()))
     }
 
     async fn complete_multipart(&self, path: &str, args: OpCompleteMultipart) -> Result<()> {
-        self.cache.delete(path, OpDelete::new()).await?;
-        self.inner.complete_multipart(path, args).await
+        self.invalidate(path).await?;
+        self.inner.complete_multipart(path, args).await?;
+        self.invalidate(path).await
     }
 
     fn blocking_create(&self, path: &str, args: OpCreate) -> Result<()> {
-        self.cache.blocking_delete(path, OpDelete::new())?;
-        self.inner.blocking_create(path, args)
+        self.blocking_invalidate(path)?;
+        self.inner.blocking_create(path, args)?;
+        self.blocking_invalidate(path)
     }
 
     fn blocking_write(&self, path: &str, args: OpWrite, r: BlockingBytesReader) -> Result<u64> {
-        self.cache.blocking_delete(path, OpDelete::new())?;
-        self.inner.blocking_write(path, args, r)
+        self.blocking_invalidate(path)?;
+        let n = self.inner.blocking_write(path, args, r)?;
+        self.blocking_invalidate(path)?;
+        Ok(n)
     }
 
     fn blocking_stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
-        match self.cache.blocking_read(path, OpRead::new(..)) {
-            Ok(mut r) => {
-                let mut bs = Vec::with_capacity(1024);
-                r.read_to_end(&mut bs)?;
-                let (meta, _) = bincode::serde::decode_from_slice(&bs, bincode::config::standard())
-                    .map_err(|err| new_other_object_error(Operation::BlockingStat, path, err))?;
-                Ok(meta)
+        let key = stat_key(path);
+        match self.blocking_get(&key)? {
+            Some(CacheValue::Stat(meta)) => return Ok(meta),
+            Some(CacheValue::NotFound) => {
+                return Err(new_cached_not_found_error(Operation::BlockingStat, path));
             }
-            Err(err) if err.kind() == ErrorKind::NotFound => {
-                let meta = self.inner.blocking_stat(path, args)?;
-                let bs = bincode::serde::encode_to_vec(&meta, bincode::config::standard())
-                    .map_err(|err| new_other_object_error(Operation::BlockingStat, path, err))?;
-                self.cache.blocking_write(
-                    path,
-                    OpWrite::new(bs.len() as u64),
-                    Box::new(std::io::Cursor::new(bs)),
+            _ => {}
+        }
+
+        match self.inner.blocking_stat(path, args) {
+            Ok(meta) => {
+                self.blocking_put(
+                    Operation::BlockingStat,
+                    &key,
+                    CacheValue::Stat(meta.clone()),
+                    self.ttl,
                 )?;
                 Ok(meta)
             }
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                if let Some(ttl) = self.negative_ttl {
+                    self.blocking_put(
+                        Operation::BlockingStat,
+                        &key,
+                        CacheValue::NotFound,
+                        Some(ttl),
+                    )?;
+                }
+                Err(err)
+            }
             Err(err) => Err(err),
         }
     }
 
     fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<()> {
-        self.cache.blocking_delete(path, OpDelete::new())?;
-        self.inner.blocking_delete(path, args)
+        self.blocking_invalidate(path)?;
+        self.inner.blocking_delete(path, args)?;
+        self.blocking_invalidate(path)
     }
 
     fn blocking_list(&self, path: &str, args: OpList) -> Result<ObjectIterator> {
-        self.inner
-            .blocking_list(path, args)
-            .map(|s| set_accessor_for_object_iterator(s, self.clone()))
+        let key = list_key(path);
+        if self.max_list_entries > 0 {
+            if let Some(CacheValue::List(entries)) = self.blocking_get(&key)? {
+                return Ok(Box::new(self.build_entries(entries).into_iter()));
+            }
+        }
+
+        let mut it = self.inner.blocking_list(path, args)?;
+        let mut entries = Vec::new();
+        while self.max_list_entries > 0 && entries.len() <= self.max_list_entries {
+            match it.next().transpose()? {
+                Some(de) => entries.push(de),
+                None => {
+                    self.blocking_put(
+                        Operation::BlockingList,
+                        &key,
+                        Self::to_cache_value(&entries),
+                        self.ttl,
+                    )?;
+                    break;
+                }
+            }
+        }
+
+        // Dirs that have too many entries will be iterated without cache.
+        let it = entries.into_iter().map(Ok).chain(it);
+        Ok(set_accessor_for_object_iterator(Box::new(it), self.clone()))
     }
 }
 
@@ -248,4 +603,139 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_metadata_cache_ttl() -> anyhow::Result<()> {
+        let op = Operator::new(memory::Builder::default().build()?);
+        let cache_layer =
+            MetadataCacheLayer::new(memory::Builder::default().build()?).with_ttl(Duration::ZERO);
+        let cached_op = op.clone().layer(cache_layer);
+
+        op.object("test").write("Hello, World!").await?;
+        let meta = cached_op.object("test").metadata().await?;
+        assert_eq!(meta.content_length(), 13);
+
+        // Expired entries will be refreshed.
+        op.object("test").write("Hello, Xuanwo!").await?;
+        let meta = cached_op.object("test").metadata().await?;
+        assert_eq!(meta.content_length(), 14);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_metadata_cache_negative() -> anyhow::Result<()> {
+        let op = Operator::new(memory::Builder::default().build()?);
+        let cache_layer = MetadataCacheLayer::new(memory::Builder::default().build()?)
+            .with_negative_ttl(Duration::from_secs(60));
+        let cached_op = op.clone().layer(cache_layer);
+
+        let meta = cached_op.object("test").metadata().await;
+        assert_eq!(meta.unwrap_err().kind(), ErrorKind::NotFound);
+
+        // NotFound has been cached.
+        op.object("test").write("Hello, World!").await?;
+        let meta = cached_op.object("test").metadata().await;
+        assert_eq!(meta.unwrap_err().kind(), ErrorKind::NotFound);
+
+        // Write via cached op will invalidate it.
+        cached_op.object("test").write("Hello, World!").await?;
+        let meta = cached_op.object("test").metadata().await?;
+        assert_eq!(meta.content_length(), 13);
+
+        Ok(())
+    }
+
+    /// List paths in `dir/` sorted.
+    async fn list(op: &Operator) -> anyhow::Result<Vec<String>> {
+        let mut paths = Vec::new();
+        let mut s = op.object("dir/").list().await?;
+        while let Some(de) = s.try_next().await? {
+            paths.push(de.path().to_string());
+        }
+        paths.sort();
+        Ok(paths)
+    }
+
+    #[tokio::test]
+    async fn test_metadata_cache_list() -> anyhow::Result<()> {
+        let op = Operator::new(memory::Builder::default().build()?);
+        let cache_layer = MetadataCacheLayer::new(memory::Builder::default().build()?);
+        let cached_op = op.clone().layer(cache_layer);
+
+        op.object("dir/a").write("a").await?;
+        assert_eq!(list(&cached_op).await?, vec!["dir/a"]);
+
+        // List has been cached.
+        op.object("dir/b").write("b").await?;
+        assert_eq!(list(&cached_op).await?, vec!["dir/a"]);
+
+        // Write via cached op will invalidate the parent.
+        cached_op.object("dir/c").write("c").await?;
+        assert_eq!(list(&cached_op).await?, vec!["dir/a", "dir/b", "dir/c"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_metadata_cache_list_ancestors() -> anyhow::Result<()> {
+        let op = Operator::new(memory::Builder::default().build()?);
+        let cache_layer = MetadataCacheLayer::new(memory::Builder::default().build()?);
+        let cached_op = op.clone().layer(cache_layer);
+
+        op.object("dir/a").write("a").await?;
+        assert_eq!(list(&cached_op).await?, vec!["dir/a"]);
+
+        // Write to a nested path will invalidate all ancestors.
+        cached_op.object("dir/sub/b").write("b").await?;
+        assert_eq!(list(&cached_op).await?, vec!["dir/a", "dir/sub/"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let cases = vec![
+            ("root", "/", "stat/%2F"),
+            ("file", "abc", "stat/abc"),
+            ("dir", "abc/", "stat/abc%2F"),
+            ("escape", "abc%2F", "stat/abc%252F"),
+        ];
+
+        for (name, input, expect) in cases {
+            assert_eq!(stat_key(input), expect, "{name}")
+        }
+    }
+
+    #[test]
+    fn test_invalidate_keys() {
+        let cases = vec![
+            ("root", "/", vec!["stat/%2F", "list/%2F"]),
+            ("file", "abc", vec!["stat/abc", "list/%2F"]),
+            (
+                "nested file",
+                "abc/def/ghi",
+                vec![
+                    "stat/abc/def/ghi",
+                    "list/abc/def%2F",
+                    "list/abc%2F",
+                    "list/%2F",
+                ],
+            ),
+            (
+                "nested dir",
+                "abc/def/",
+                vec![
+                    "stat/abc/def%2F",
+                    "list/abc/def%2F",
+                    "list/abc%2F",
+                    "list/%2F",
+                ],
+            ),
+        ];
+
+        for (name, input, expect) in cases {
+            assert_eq!(invalidate_keys(input), expect, "{name}")
+        }
+    }
 }
@@ -24,7 +24,9 @@ use std::task::Poll;
 use std::time::Instant;
 
 use async_trait::async_trait;
+use bytes::Bytes;
 use futures::AsyncRead;
+use futures::Stream;
 use metrics::register_counter;
 use metrics::register_histogram;
 use metrics::Counter;
@@ -40,6 +42,7 @@ use crate::ops::OpDelete;
 use crate::ops::OpList;
 use crate::ops::OpPresign;
 use crate::ops::OpRead;
+use crate::ops::OpReadRanges;
 use crate::ops::OpStat;
 use crate::ops::OpWrite;
 use crate::ops::OpWriteMultipart;
@@ -49,6 +52,7 @@ use crate::Accessor;
 use crate::AccessorMetadata;
 use crate::BlockingBytesReader;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::Layer;
 use crate::ObjectIterator;
 use crate::ObjectMetadata;
@@ -716,6 +720,85 @@ impl Accessor for MetricsAccessor {
         })
     }
 
+    async fn read_stream(&self, path: &str, args: OpRead) -> Result<BytesStreamer> {
+        self.handle.requests_total_read.increment(1);
+
+        let start = Instant::now();
+
+        let result = self.inner.read_stream(path, args).await.map(|s| {
+            Box::new(MetricStreamer::new(
+                s,
+                self.handle.bytes_total_read.clone(),
+                self.handle.failures_total_read.clone(),
+                self.handle.errors_total_read.clone(),
+                self.handle.requests_duration_seconds_read.clone(),
+                Some(start),
+            )) as BytesStreamer
+        });
+
+        result.map_err(|e| {
+            if e.kind() == ErrorKind::Other {
+                self.handle.failures_total_read.increment(1);
+            } else {
+                self.handle.errors_total_read.increment(1);
+            }
+            e
+        })
+    }
+
+    async fn read_ranges(&self, path: &str, args: OpReadRanges) -> Result<Vec<Bytes>> {
+        self.handle.requests_total_read.increment(1);
+
+        let start = Instant::now();
+        let result = self.inner.read_ranges(path, args).await;
+        let dur = start.elapsed().as_secs_f64();
+
+        self.handle.requests_duration_seconds_read.record(dur);
+
+        result
+            .map(|bs| {
+                let n: usize = bs.iter().map(|v| v.len()).sum();
+                self.handle.bytes_total_read.increment(n as u64);
+                bs
+            })
+            .map_err(|e| {
+                if e.kind() == ErrorKind::Other {
+                    self.handle.failures_total_read.increment(1);
+                } else {
+                    self.handle.errors_total_read.increment(1);
+                }
+                e
+            })
+    }
+
+    async fn write_stream(&self, path: &str, args: OpWrite, s: BytesStreamer) -> Result<u64> {
+        self.handle.requests_total_write.increment(1);
+
+        let s = Box::new(MetricStreamer::new(
+            s,
+            self.handle.bytes_total_write.clone(),
+            self.handle.failures_total_write.clone(),
+            self.handle.errors_total_write.clone(),
+            self.handle.requests_duration_seconds_write.clone(),
+            None,
+        ));
+
+        let start = Instant::now();
+        let result = self.inner.write_stream(path, args, s).await;
+        let dur = start.elapsed().as_secs_f64();
+
+        self.handle.requests_duration_seconds_write.record(dur);
+
+        result.map_err(|e| {
+            if e.kind() == ErrorKind::Other {
+                self.handle.failures_total_write.increment(1);
+            } else {
+                self.handle.errors_total_write.increment(1);
+            }
+            e
+        })
+    }
+
     async fn stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
         self.handle.requests_total_stat.increment(1);
 
@@ -1105,6 +1188,74 @@ impl Drop for MetricReader {
     }
 }
 
+struct MetricStreamer {
+    inner: BytesStreamer,
+
+    bytes_counter: Counter,
+    failures_counter: Counter,
+    errors_counter: Counter,
+    requests_duration_seconds: Histogram,
+
+    start: Option<Instant>,
+    bytes: u64,
+}
+
+impl MetricStreamer {
+    fn new(
+        inner: BytesStreamer,
+        bytes_counter: Counter,
+        failures_counter: Counter,
+        errors_counter: Counter,
+        requests_duration_seconds: Histogram,
+        start: Option<Instant>,
+    ) -> Self {
+        Self {
+            inner,
+            bytes_counter,
+            failures_counter,
+            errors_counter,
+            requests_duration_seconds,
+
+            start,
+            bytes: 0,
+        }
+    }
+}
+
+impl Stream for MetricStreamer {
+    type Item = Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut (*self.inner))
+            .poll_next(cx)
+            .map(|res| match res {
+                Some(Ok(bs)) => {
+                    self.bytes += bs.len() as u64;
+                    Some(Ok(bs))
+                }
+                Some(Err(e)) => {
+                    if e.kind() == ErrorKind::Other {
+                        self.failures_counter.increment(1);
+                    } else {
+                        self.errors_counter.increment(1);
+                    }
+                    Some(Err(e))
+                }
+                None => None,
+            })
+    }
+}
+
+impl Drop for MetricStreamer {
+    fn drop(&mut self) {
+        self.bytes_counter.increment(self.bytes);
+        if let Some(instant) = self.start {
+            let dur = instant.elapsed().as_secs_f64();
+            self.requests_duration_seconds.record(dur);
+        }
+    }
+}
+
 struct BlockingMetricReader {
     inner: BlockingBytesReader,
 
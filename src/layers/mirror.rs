@@ -0,0 +1,582 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::channel::mpsc;
+use futures::future::join;
+use futures::future::join3;
+use futures::future::join_all;
+use futures::stream;
+use futures::AsyncReadExt;
+use futures::SinkExt;
+use log::warn;
+
+use super::util::set_accessor_for_object_steamer;
+use crate::error::new_unsupported_object_error;
+use crate::error::ObjectError;
+use crate::io_util::into_reader;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpPresign;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::Operation;
+use crate::ops::PresignOperation;
+use crate::ops::PresignedRequest;
+use crate::Accessor;
+use crate::AccessorCapability;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::Layer;
+use crate::ObjectPart;
+use crate::ObjectStreamer;
+use crate::Operator;
+
+/// Size of chunks read from input while teeing.
+const TEE_CHUNK_SIZE: usize = 64 * 1024;
+/// Chunks buffered for every target while teeing.
+const TEE_CHANNEL_SIZE: usize = 4;
+
+/// Consistency of [`MirrorLayer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MirrorConsistency {
+    /// Operations succeed only if all targets succeed.
+    All,
+    /// Operations succeed once the primary succeeds, secondaries will be
+    /// updated in background after that.
+    ///
+    /// Input of `write` will be buffered in memory for secondaries.
+    PrimaryAsync,
+}
+
+/// MirrorFailure describes a failed operation on secondary target.
+#[derive(Debug)]
+pub struct MirrorFailure {
+    target: usize,
+    op: Operation,
+    path: String,
+    error: Error,
+}
+
+impl MirrorFailure {
+    /// Index of the secondary in the order they were added.
+    pub fn target(&self) -> usize {
+        self.target
+    }
+
+    /// Operation that failed.
+    pub fn operation(&self) -> Operation {
+        self.op
+    }
+
+    /// Path of the failed operation.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Error returned by secondary.
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+}
+
+type FailureListener = Arc<dyn Fn(MirrorFailure) + Send + Sync>;
+
+/// MirrorLayer will replicate `write`, `create` and `delete` to secondary
+/// operators.
+///
+/// The operator this layer applied on is the primary, all other operations
+/// will only be sent to primary.
+///
+/// - Input of `write` is read only once and teed to all targets.
+/// - Failures of secondaries are logged and sent to the failure listener,
+///   primary results are never affected by them in
+///   [`MirrorConsistency::PrimaryAsync`] mode.
+/// - Multipart, presigned and blocking writes can't be mirrored, so they
+///   are not supported and `Blocking` capability is cleared. Other blocking
+///   operations are still sent to primary.
+///
+/// # Examples
+///
+/// ```
+/// use opendal::layers::MirrorConsistency;
+/// use opendal::layers::MirrorLayer;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// let secondary = Operator::from_env(Scheme::Memory).expect("must init");
+/// let _ = Operator::from_env(Scheme::Memory)
+///     .expect("must init")
+///     .layer(
+///         MirrorLayer::new()
+///             .with_secondary(secondary)
+///             .with_consistency(MirrorConsistency::All)
+///             .with_failure_listener(|f| eprintln!("mirror failed: {f:?}")),
+///     );
+/// ```
+#[derive(Clone)]
+pub struct MirrorLayer {
+    secondaries: Vec<Operator>,
+    consistency: MirrorConsistency,
+    listener: Option<FailureListener>,
+}
+
+impl Default for MirrorLayer {
+    fn default() -> Self {
+        Self {
+            secondaries: Vec::new(),
+            consistency: MirrorConsistency::All,
+            listener: None,
+        }
+    }
+}
+
+impl Debug for MirrorLayer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MirrorLayer")
+            .field("secondaries", &self.secondaries)
+            .field("consistency", &self.consistency)
+            .finish_non_exhaustive()
+    }
+}
+
+impl MirrorLayer {
+    /// Create a new MirrorLayer without secondaries.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a secondary operator.
+    pub fn with_secondary(mut self, op: Operator) -> Self {
+        self.secondaries.push(op);
+        self
+    }
+
+    /// Set consistency, default to [`MirrorConsistency::All`].
+    pub fn with_consistency(mut self, consistency: MirrorConsistency) -> Self {
+        self.consistency = consistency;
+        self
+    }
+
+    /// Set a listener that will be called on every secondary failure.
+    pub fn with_failure_listener(
+        mut self,
+        f: impl Fn(MirrorFailure) + Send + Sync + 'static,
+    ) -> Self {
+        self.listener = Some(Arc::new(f));
+        self
+    }
+}
+
+impl Layer for MirrorLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(MirrorAccessor {
+            inner,
+            secondaries: Arc::new(self.secondaries.clone()),
+            consistency: self.consistency,
+            listener: self.listener.clone(),
+        })
+    }
+}
+
+#[derive(Clone)]
+struct MirrorAccessor {
+    inner: Arc<dyn Accessor>,
+    secondaries: Arc<Vec<Operator>>,
+    consistency: MirrorConsistency,
+    listener: Option<FailureListener>,
+}
+
+impl Debug for MirrorAccessor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MirrorAccessor")
+            .field("inner", &self.inner)
+            .field("secondaries", &self.secondaries)
+            .field("consistency", &self.consistency)
+            .finish_non_exhaustive()
+    }
+}
+
+impl MirrorAccessor {
+    /// Report failures of secondaries, returns error if any of them failed.
+    fn report(
+        listener: Option<&FailureListener>,
+        op: Operation,
+        path: &str,
+        results: Vec<Result<()>>,
+    ) -> Result<()> {
+        let mut failed = 0;
+        for (target, res) in results.into_iter().enumerate() {
+            if let Err(err) = res {
+                warn!(
+                    target: "opendal::layers::mirror",
+                    "operation={op} path={path} secondary={target} -> failed: {err:?}"
+                );
+                failed += 1;
+
+                if let Some(f) = listener {
+                    f(MirrorFailure {
+                        target,
+                        op,
+                        path: path.to_string(),
+                        error: err,
+                    })
+                }
+            }
+        }
+
+        if failed == 0 {
+            Ok(())
+        } else {
+            Err(Error::new(
+                ErrorKind::Other,
+                ObjectError::new(
+                    op,
+                    path,
+                    anyhow!("primary succeeded but {failed} secondaries failed"),
+                ),
+            ))
+        }
+    }
+
+    /// Run `f` on all secondaries according to consistency.
+    ///
+    /// Must be called after primary succeeded.
+    async fn mirror<F, Fut>(&self, op: Operation, path: &str, f: F) -> Result<()>
+    where
+        F: Fn(Operator, String) -> Fut,
+        Fut: std::future::Future<Output = Result<()>> + Send + 'static,
+    {
+        let futs: Vec<_> = self
+            .secondaries
+            .iter()
+            .map(|sop| f(sop.clone(), path.to_string()))
+            .collect();
+
+        match self.consistency {
+            MirrorConsistency::All => {
+                let results = join_all(futs).await;
+                Self::report(self.listener.as_ref(), op, path, results)
+            }
+            MirrorConsistency::PrimaryAsync => {
+                let listener = self.listener.clone();
+                let path = path.to_string();
+                tokio::spawn(async move {
+                    let results = join_all(futs).await;
+                    let _ = Self::report(listener.as_ref(), op, &path, results);
+                });
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Read input once and send chunks to all senders.
+///
+/// Chunks will be collected and returned if `collect` is true.
+async fn tee(
+    mut r: BytesReader,
+    mut txs: Vec<mpsc::Sender<Result<Bytes>>>,
+    collect: bool,
+) -> Result<Vec<Bytes>> {
+    let mut chunks = Vec::new();
+    let mut buf = vec![0; TEE_CHUNK_SIZE];
+
+    loop {
+        let n = match r.read(&mut buf).await {
+            Ok(n) => n,
+            Err(err) => {
+                for tx in txs.iter_mut() {
+                    let _ = tx.send(Err(Error::new(err.kind(), err.to_string()))).await;
+                }
+                return Err(err);
+            }
+        };
+        if n == 0 {
+            return Ok(chunks);
+        }
+
+        let bs = Bytes::copy_from_slice(&buf[..n]);
+        // Targets that failed will close their receivers, just ignore them.
+        for tx in txs.iter_mut() {
+            let _ = tx.send(Ok(bs.clone())).await;
+        }
+        if collect {
+            chunks.push(bs);
+        }
+    }
+}
+
+#[async_trait]
+impl Accessor for MirrorAccessor {
+    fn inner(&self) -> Option<Arc<dyn Accessor>> {
+        Some(self.inner.clone())
+    }
+
+    fn metadata(&self) -> AccessorMetadata {
+        let mut meta = self.inner.metadata();
+        // Blocking reads are still sent to primary, but blocking writes
+        // can't be mirrored.
+        meta.set_capabilities(
+            meta.capabilities() - AccessorCapability::Multipart - AccessorCapability::Blocking,
+        );
+        meta
+    }
+
+    async fn create(&self, path: &str, args: OpCreate) -> Result<()> {
+        self.inner.create(path, args).await?;
+
+        self.mirror(Operation::Create, path, |op, path| async move {
+            op.object(&path).create().await
+        })
+        .await
+    }
+
+    async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<u64> {
+        if self.secondaries.is_empty() {
+            return self.inner.write(path, args, r).await;
+        }
+
+        let size = args.size();
+        let (tx, rx) = mpsc::channel(TEE_CHANNEL_SIZE);
+        let primary = self.inner.write(path, args, Box::new(into_reader(rx)));
+
+        match self.consistency {
+            MirrorConsistency::All => {
+                let mut txs = vec![tx];
+                let mut futs = Vec::with_capacity(self.secondaries.len());
+                for sop in self.secondaries.iter() {
+                    let (tx, rx) = mpsc::channel(TEE_CHANNEL_SIZE);
+                    txs.push(tx);
+                    let o = sop.object(path);
+                    futs.push(async move { o.write_from(size, into_reader(rx)).await });
+                }
+
+                let (tee_res, primary_res, results) =
+                    join3(tee(r, txs, false), primary, join_all(futs)).await;
+                tee_res?;
+                let written = primary_res?;
+                Self::report(self.listener.as_ref(), Operation::Write, path, results)?;
+                Ok(written)
+            }
+            MirrorConsistency::PrimaryAsync => {
+                let (tee_res, primary_res) = join(tee(r, vec![tx], true), primary).await;
+                let chunks = tee_res?;
+                let written = primary_res?;
+
+                self.mirror(Operation::Write, path, |op, path| {
+                    let chunks = chunks.clone();
+                    async move {
+                        let r = into_reader(stream::iter(chunks.into_iter().map(Ok)));
+                        op.object(&path).write_from(size, r).await
+                    }
+                })
+                .await?;
+                Ok(written)
+            }
+        }
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<()> {
+        self.inner.delete(path, args).await?;
+
+        self.mirror(Operation::Delete, path, |op, path| async move {
+            op.object(&path).delete().await
+        })
+        .await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<ObjectStreamer> {
+        self.inner
+            .list(path, args)
+            .await
+            .map(|s| set_accessor_for_object_steamer(s, self.clone()))
+    }
+
+    fn presign(&self, path: &str, args: OpPresign) -> Result<PresignedRequest> {
+        match args.operation() {
+            PresignOperation::Read(_) => self.inner.presign(path, args),
+            _ => Err(new_unsupported_object_error(Operation::Presign, path)),
+        }
+    }
+
+    async fn create_multipart(&self, path: &str, _: OpCreateMultipart) -> Result<String> {
+        Err(new_unsupported_object_error(
+            Operation::CreateMultipart,
+            path,
+        ))
+    }
+
+    async fn write_multipart(
+        &self,
+        path: &str,
+        _: OpWriteMultipart,
+        _: BytesReader,
+    ) -> Result<ObjectPart> {
+        Err(new_unsupported_object_error(
+            Operation::WriteMultipart,
+            path,
+        ))
+    }
+
+    async fn complete_multipart(&self, path: &str, _: OpCompleteMultipart) -> Result<()> {
+        Err(new_unsupported_object_error(
+            Operation::CompleteMultipart,
+            path,
+        ))
+    }
+
+    async fn abort_multipart(&self, path: &str, _: OpAbortMultipart) -> Result<()> {
+        Err(new_unsupported_object_error(
+            Operation::AbortMultipart,
+            path,
+        ))
+    }
+
+    fn blocking_create(&self, path: &str, _: OpCreate) -> Result<()> {
+        Err(new_unsupported_object_error(
+            Operation::BlockingCreate,
+            path,
+        ))
+    }
+
+    fn blocking_write(&self, path: &str, _: OpWrite, _: BlockingBytesReader) -> Result<u64> {
+        Err(new_unsupported_object_error(Operation::BlockingWrite, path))
+    }
+
+    fn blocking_delete(&self, path: &str, _: OpDelete) -> Result<()> {
+        Err(new_unsupported_object_error(
+            Operation::BlockingDelete,
+            path,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::layers::PolicyLayer;
+    use crate::services::fs;
+    use crate::services::memory;
+
+    #[tokio::test]
+    async fn test_mirror_all() -> anyhow::Result<()> {
+        let primary = Operator::new(memory::Builder::default().build()?);
+        let secondary = Operator::new(memory::Builder::default().build()?);
+        let op = primary
+            .clone()
+            .layer(MirrorLayer::new().with_secondary(secondary.clone()));
+
+        let content: Vec<u8> = (0..=255).cycle().take(256 * 1024).collect();
+        op.object("test").write(content.clone()).await?;
+        assert_eq!(primary.object("test").read().await?, content);
+        assert_eq!(secondary.object("test").read().await?, content);
+
+        op.object("dir/").create().await?;
+        assert!(secondary.object("dir/").is_exist().await?);
+
+        op.object("test").delete().await?;
+        assert!(!secondary.object("test").is_exist().await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_mirror_failure() -> anyhow::Result<()> {
+        let primary = Operator::new(memory::Builder::default().build()?);
+        let secondary =
+            Operator::new(memory::Builder::default().build()?).layer(PolicyLayer::read_only());
+
+        let failures = Arc::new(Mutex::new(Vec::new()));
+        let layer = {
+            let failures = failures.clone();
+            MirrorLayer::new()
+                .with_secondary(secondary)
+                .with_failure_listener(move |f| {
+                    failures
+                        .lock()
+                        .unwrap()
+                        .push((f.target(), f.operation(), f.error().kind()))
+                })
+        };
+
+        // All targets must succeed.
+        let op = primary.clone().layer(layer.clone());
+        assert!(op.object("test").write("Hello").await.is_err());
+        assert_eq!(primary.object("test").read().await?, b"Hello");
+        assert_eq!(
+            *failures.lock().unwrap(),
+            vec![(0, Operation::Write, ErrorKind::PermissionDenied)]
+        );
+
+        // Primary result will not be affected.
+        let op = primary.layer(layer.with_consistency(MirrorConsistency::PrimaryAsync));
+        op.object("test").write("World").await?;
+        for _ in 0..100 {
+            if failures.lock().unwrap().len() == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(failures.lock().unwrap().len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mirror_blocking_unsupported() -> anyhow::Result<()> {
+        // Use fs here since memory doesn't support blocking operations.
+        let root = std::env::temp_dir().join(format!("opendal-mirror-{}", Uuid::new_v4()));
+        let mut builder = fs::Builder::default();
+        builder.root(&root.to_string_lossy());
+        let primary = Operator::new(builder.build()?);
+        let op = primary.clone().layer(
+            MirrorLayer::new().with_secondary(Operator::new(memory::Builder::default().build()?)),
+        );
+
+        let err = op.object("test").blocking_write("Hello").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+        let err = op.object("dir/").blocking_create().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+        let err = op.object("test").blocking_delete().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+        // Nothing has been sent to primary.
+        assert!(!primary.object("test").blocking_is_exist()?);
+        assert!(!primary.object("dir/").blocking_is_exist()?);
+        assert!(!op.metadata().can_blocking());
+
+        std::fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+}
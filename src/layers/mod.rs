@@ -17,11 +17,51 @@
 mod layer;
 pub use layer::Layer;
 
+mod audit;
+pub use audit::AuditLayer;
+pub use audit::AuditOutcome;
+pub use audit::AuditRecord;
+pub use audit::AuditSink;
+pub use audit::LogAuditSink;
+pub use audit::OperatorAuditSink;
+
+#[cfg(feature = "layers-chaos")]
+mod chaos;
+#[cfg(feature = "layers-chaos")]
+pub use chaos::ChaosLayer;
+#[cfg(feature = "layers-chaos")]
+pub use chaos::ChaosRule;
+
+#[cfg(feature = "compress")]
+mod compression;
+#[cfg(feature = "compress")]
+pub use compression::CompressionLayer;
+
 mod concurrent_limit;
 pub use concurrent_limit::ConcurrentLimitLayer;
 
 mod content_cache;
 pub use content_cache::ContentCacheLayer;
+pub use content_cache::ContentCacheStrategy;
+pub use content_cache::ContentCacheWritePolicy;
+
+#[cfg(feature = "layers-encryption")]
+mod encryption;
+#[cfg(feature = "layers-encryption")]
+pub use encryption::EncryptionAlgorithm;
+#[cfg(feature = "layers-encryption")]
+pub use encryption::EncryptionLayer;
+#[cfg(feature = "layers-encryption")]
+pub use encryption::KeyProvider;
+#[cfg(feature = "layers-encryption")]
+pub use encryption::StaticKeyProvider;
+
+mod failover;
+pub use failover::FailoverLayer;
+
+mod hedge;
+pub use hedge::HedgeLayer;
+pub use hedge::HedgeStats;
 
 mod immutable_index;
 pub use immutable_index::ImmutableIndexLayer;
@@ -37,12 +77,38 @@ mod metrics;
 #[cfg(feature = "layers-metrics")]
 pub use self::metrics::MetricsLayer;
 
+mod mirror;
+pub use mirror::MirrorConsistency;
+pub use mirror::MirrorFailure;
+pub use mirror::MirrorLayer;
+
+mod overlay;
+pub use overlay::OverlayLayer;
+
+mod policy;
+pub use policy::PolicyLayer;
+
+mod rate_limit;
+pub use rate_limit::RateLimitLayer;
+
+mod record;
+pub use record::Cassette;
+pub use record::RecordLayer;
+pub use record::ReplayAccessor;
+
 mod retry;
 pub use self::retry::RetryLayer;
 
+mod router;
+pub use router::MountTable;
+pub use router::RouterLayer;
+
 mod subdir;
 pub use subdir::SubdirLayer;
 
+mod timeout;
+pub use timeout::TimeoutLayer;
+
 #[cfg(feature = "layers-tracing")]
 mod tracing;
 #[cfg(feature = "layers-tracing")]
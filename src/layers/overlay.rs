@@ -0,0 +1,378 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use futures::io::Cursor;
+use futures::stream;
+use futures::TryStreamExt;
+
+use crate::error::new_unsupported_object_error;
+use crate::error::ObjectError;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpPresign;
+use crate::ops::OpRead;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::Operation;
+use crate::ops::PresignedRequest;
+use crate::path::get_basename;
+use crate::path::get_parent;
+use crate::Accessor;
+use crate::AccessorCapability;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::Layer;
+use crate::ObjectEntry;
+use crate::ObjectIterator;
+use crate::ObjectMetadata;
+use crate::ObjectPart;
+use crate::ObjectStreamer;
+use crate::Operator;
+
+/// Prefix of whiteout markers, the same as overlayfs.
+const WHITEOUT_PREFIX: &str = ".wh.";
+
+/// OverlayLayer will overlay a writable upper operator on the operator
+/// this layer applied on, which is the lower one and will never be changed.
+///
+/// - `read` and `stat` will hit upper first and then lower.
+/// - `write`, `create` and multipart writes always go to upper.
+/// - `delete` removes the object from upper, and records a whiteout marker
+///   `.wh.<name>` besides it in upper if lower has the object, so that it
+///   disappears from the overlay.
+/// - `list` merges both sides in sorted order. Entries of upper win if both
+///   sides have them, entries of lower with whiteouts are hidden.
+///
+/// Whiteouts only hide the exact object, objects under a deleted dir of
+/// lower are still visible by path. `list` collects both sides before
+/// returning, which means large dirs will be kept in memory.
+///
+/// Presign and blocking operations are not supported, since they can't
+/// decide which side to use.
+///
+/// # Examples
+///
+/// ```
+/// use opendal::layers::OverlayLayer;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// let upper = Operator::from_env(Scheme::Memory).expect("must init");
+/// let _ = Operator::from_env(Scheme::Fs)
+///     .expect("must init")
+///     .layer(OverlayLayer::new(upper));
+/// ```
+#[derive(Debug, Clone)]
+pub struct OverlayLayer {
+    upper: Operator,
+}
+
+impl OverlayLayer {
+    /// Create a new OverlayLayer with upper operator.
+    pub fn new(upper: Operator) -> Self {
+        Self { upper }
+    }
+}
+
+impl Layer for OverlayLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(OverlayAccessor {
+            inner,
+            upper: self.upper.inner(),
+        })
+    }
+}
+
+/// Build the whiteout path of given path.
+///
+/// - `abc` => `.wh.abc`
+/// - `abc/def/` => `abc/.wh.def`
+fn whiteout_path(path: &str) -> String {
+    let name = get_basename(path).trim_end_matches('/');
+    match get_parent(path) {
+        "/" => format!("{WHITEOUT_PREFIX}{name}"),
+        parent => format!("{parent}{WHITEOUT_PREFIX}{name}"),
+    }
+}
+
+/// Collect all entries of a list result, dirs that not exist are empty.
+async fn collect_entries(res: Result<ObjectStreamer>) -> Result<Vec<ObjectEntry>> {
+    match res {
+        Ok(s) => s.try_collect().await,
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err),
+    }
+}
+
+#[derive(Debug, Clone)]
+struct OverlayAccessor {
+    /// The lower one.
+    inner: Arc<dyn Accessor>,
+    upper: Arc<dyn Accessor>,
+}
+
+impl OverlayAccessor {
+    /// Check if the object has been deleted in overlay.
+    async fn is_whiteout(&self, path: &str) -> Result<bool> {
+        match self.upper.stat(&whiteout_path(path), OpStat::new()).await {
+            Ok(_) => Ok(true),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn remove_whiteout(&self, path: &str) -> Result<()> {
+        self.upper
+            .delete(&whiteout_path(path), OpDelete::new())
+            .await
+    }
+
+    /// Check if the object is visible in lower.
+    async fn check_lower(&self, op: Operation, path: &str) -> Result<()> {
+        if self.is_whiteout(path).await? {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                ObjectError::new(op, path, anyhow!("object has been deleted in overlay")),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Accessor for OverlayAccessor {
+    fn inner(&self) -> Option<Arc<dyn Accessor>> {
+        Some(self.inner.clone())
+    }
+
+    fn metadata(&self) -> AccessorMetadata {
+        let mut meta = self.inner.metadata();
+        let lower = meta.capabilities() & (AccessorCapability::Read | AccessorCapability::List);
+        let upper = self.upper.metadata().capabilities()
+            - AccessorCapability::Presign
+            - AccessorCapability::Blocking;
+        meta.set_capabilities(lower | upper);
+        meta
+    }
+
+    async fn create(&self, path: &str, args: OpCreate) -> Result<()> {
+        self.upper.create(path, args).await?;
+        self.remove_whiteout(path).await
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        match self.upper.read(path, args.clone()).await {
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                self.check_lower(Operation::Read, path).await?;
+                self.inner.read(path, args).await
+            }
+            v => v,
+        }
+    }
+
+    async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<u64> {
+        let n = self.upper.write(path, args, r).await?;
+        self.remove_whiteout(path).await?;
+        Ok(n)
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
+        match self.upper.stat(path, args.clone()).await {
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                self.check_lower(Operation::Stat, path).await?;
+                self.inner.stat(path, args).await
+            }
+            v => v,
+        }
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<()> {
+        self.upper.delete(path, args).await?;
+
+        match self.inner.stat(path, OpStat::new()).await {
+            Ok(_) => {
+                self.upper
+                    .write(
+                        &whiteout_path(path),
+                        OpWrite::new(0),
+                        Box::new(Cursor::new(Vec::new())),
+                    )
+                    .await?;
+                Ok(())
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<ObjectStreamer> {
+        let upper = collect_entries(self.upper.list(path, args.clone()).await).await?;
+        let lower = collect_entries(self.inner.list(path, args).await).await?;
+
+        let mut entries = BTreeMap::new();
+        let mut whiteouts = HashSet::new();
+        for de in upper {
+            match de.name().strip_prefix(WHITEOUT_PREFIX) {
+                Some(name) => {
+                    whiteouts.insert(name.trim_end_matches('/').to_string());
+                }
+                None => {
+                    entries.insert(de.path().to_string(), de);
+                }
+            }
+        }
+        for de in lower {
+            if whiteouts.contains(de.name().trim_end_matches('/')) {
+                continue;
+            }
+            entries.entry(de.path().to_string()).or_insert(de);
+        }
+
+        let acc: Arc<dyn Accessor> = Arc::new(self.clone());
+        let entries: Vec<_> = entries
+            .into_values()
+            .map(|mut de| {
+                de.set_accessor(acc.clone());
+                Ok(de)
+            })
+            .collect();
+        Ok(Box::new(stream::iter(entries)))
+    }
+
+    fn presign(&self, path: &str, _: OpPresign) -> Result<PresignedRequest> {
+        Err(new_unsupported_object_error(Operation::Presign, path))
+    }
+
+    async fn create_multipart(&self, path: &str, args: OpCreateMultipart) -> Result<String> {
+        self.upper.create_multipart(path, args).await
+    }
+
+    async fn write_multipart(
+        &self,
+        path: &str,
+        args: OpWriteMultipart,
+        r: BytesReader,
+    ) -> Result<ObjectPart> {
+        self.upper.write_multipart(path, args, r).await
+    }
+
+    async fn complete_multipart(&self, path: &str, args: OpCompleteMultipart) -> Result<()> {
+        self.upper.complete_multipart(path, args).await?;
+        self.remove_whiteout(path).await
+    }
+
+    async fn abort_multipart(&self, path: &str, args: OpAbortMultipart) -> Result<()> {
+        self.upper.abort_multipart(path, args).await
+    }
+
+    fn blocking_create(&self, path: &str, _: OpCreate) -> Result<()> {
+        Err(new_unsupported_object_error(
+            Operation::BlockingCreate,
+            path,
+        ))
+    }
+
+    fn blocking_read(&self, path: &str, _: OpRead) -> Result<BlockingBytesReader> {
+        Err(new_unsupported_object_error(Operation::BlockingRead, path))
+    }
+
+    fn blocking_write(&self, path: &str, _: OpWrite, _: BlockingBytesReader) -> Result<u64> {
+        Err(new_unsupported_object_error(Operation::BlockingWrite, path))
+    }
+
+    fn blocking_stat(&self, path: &str, _: OpStat) -> Result<ObjectMetadata> {
+        Err(new_unsupported_object_error(Operation::BlockingStat, path))
+    }
+
+    fn blocking_delete(&self, path: &str, _: OpDelete) -> Result<()> {
+        Err(new_unsupported_object_error(
+            Operation::BlockingDelete,
+            path,
+        ))
+    }
+
+    fn blocking_list(&self, path: &str, _: OpList) -> Result<ObjectIterator> {
+        Err(new_unsupported_object_error(Operation::BlockingList, path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use super::*;
+    use crate::services::memory;
+
+    #[test]
+    fn test_whiteout_path() {
+        let cases = vec![
+            ("file", "abc", ".wh.abc"),
+            ("dir", "abc/", ".wh.abc"),
+            ("nested file", "abc/def", "abc/.wh.def"),
+            ("nested dir", "abc/def/", "abc/.wh.def"),
+        ];
+
+        for (name, input, expect) in cases {
+            assert_eq!(whiteout_path(input), expect, "{name}")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_overlay() -> anyhow::Result<()> {
+        let lower = Operator::new(memory::Builder::default().build()?);
+        lower.object("dir/a").write("lower a").await?;
+        lower.object("dir/b").write("lower b").await?;
+        let upper = Operator::new(memory::Builder::default().build()?);
+
+        let op = lower.clone().layer(OverlayLayer::new(upper.clone()));
+        assert_eq!(op.object("dir/a").read().await?, b"lower a");
+
+        op.object("dir/a").write("upper a").await?;
+        op.object("dir/c").write("upper c").await?;
+        assert_eq!(op.object("dir/a").read().await?, b"upper a");
+        assert_eq!(lower.object("dir/a").read().await?, b"lower a");
+
+        op.object("dir/b").delete().await?;
+        assert!(!op.object("dir/b").is_exist().await?);
+        assert!(lower.object("dir/b").is_exist().await?);
+
+        let mut paths = Vec::new();
+        let mut s = op.object("dir/").list().await?;
+        while let Some(de) = s.next().await {
+            paths.push(de?.path().to_string());
+        }
+        assert_eq!(paths, vec!["dir/a", "dir/c"]);
+
+        // Write again will remove the whiteout.
+        op.object("dir/b").write("upper b").await?;
+        assert_eq!(op.object("dir/b").read().await?, b"upper b");
+
+        Ok(())
+    }
+}
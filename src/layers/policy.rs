@@ -0,0 +1,494 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use super::util::set_accessor_for_object_iterator;
+use super::util::set_accessor_for_object_steamer;
+use crate::error::ObjectError;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpPresign;
+use crate::ops::OpRead;
+use crate::ops::OpReadRanges;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::Operation;
+use crate::ops::PresignOperation;
+use crate::ops::PresignedRequest;
+use crate::Accessor;
+use crate::AccessorCapability;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::BytesStreamer;
+use crate::Layer;
+use crate::ObjectIterator;
+use crate::ObjectMetadata;
+use crate::ObjectPart;
+use crate::ObjectStreamer;
+
+/// PolicyLayer will allow or deny operations by [`Operation`] and path glob.
+///
+/// Rules are checked in order and the first matched rule wins. Operations
+/// that don't match any rule are allowed. Denied operations will return
+/// [`ErrorKind::PermissionDenied`] without reaching underlying services.
+///
+/// Blocking operations are checked as their async versions, for example,
+/// rules for [`Operation::Read`] apply to [`Operation::BlockingRead`] too.
+///
+/// # Glob
+///
+/// - `?` matches any single character except `/`.
+/// - `*` matches any characters except `/`.
+/// - `**` matches any characters including `/`.
+///
+/// # Examples
+///
+/// Read only:
+///
+/// ```
+/// use opendal::layers::PolicyLayer;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// let _ = Operator::from_env(Scheme::Memory)
+///     .expect("must init")
+///     .layer(PolicyLayer::read_only());
+/// ```
+///
+/// Writes only under `tmp/`:
+///
+/// ```
+/// use opendal::layers::PolicyLayer;
+/// use opendal::ops::Operation;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// let writes = [Operation::Create, Operation::Write, Operation::Delete];
+/// let _ = Operator::from_env(Scheme::Memory)
+///     .expect("must init")
+///     .layer(
+///         PolicyLayer::new()
+///             .allow(&writes, "tmp/**")
+///             .deny(&writes, "**"),
+///     );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PolicyLayer {
+    rules: Vec<PolicyRule>,
+}
+
+#[derive(Debug, Clone)]
+struct PolicyRule {
+    allow: bool,
+    /// Empty means all operations.
+    ops: Vec<Operation>,
+    glob: Vec<char>,
+}
+
+impl PolicyRule {
+    fn matches_op(&self, op: Operation) -> bool {
+        self.ops.is_empty() || self.ops.contains(&op)
+    }
+
+    fn matches(&self, op: Operation, path: &str) -> bool {
+        self.matches_op(op) && glob_match(&self.glob, &path.chars().collect::<Vec<_>>())
+    }
+
+    /// Returns true if this rule matches all paths.
+    fn matches_all_paths(&self) -> bool {
+        self.glob == ['*', '*']
+    }
+}
+
+impl PolicyLayer {
+    /// Create a new PolicyLayer that allows all operations.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new PolicyLayer that only allows read operations.
+    pub fn read_only() -> Self {
+        Self::new()
+            .allow(
+                &[
+                    Operation::Read,
+                    Operation::Stat,
+                    Operation::List,
+                    Operation::Presign,
+                ],
+                "**",
+            )
+            .deny(&[], "**")
+    }
+
+    /// Allow operations on paths matching glob, empty `ops` means all
+    /// operations.
+    pub fn allow(mut self, ops: &[Operation], glob: &str) -> Self {
+        self.rules.push(PolicyRule {
+            allow: true,
+            ops: ops.to_vec(),
+            glob: glob.chars().collect(),
+        });
+        self
+    }
+
+    /// Deny operations on paths matching glob, empty `ops` means all
+    /// operations.
+    pub fn deny(mut self, ops: &[Operation], glob: &str) -> Self {
+        self.rules.push(PolicyRule {
+            allow: false,
+            ops: ops.to_vec(),
+            glob: glob.chars().collect(),
+        });
+        self
+    }
+}
+
+impl Layer for PolicyLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(PolicyAccessor {
+            inner,
+            rules: Arc::new(self.rules.clone()),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PolicyAccessor {
+    inner: Arc<dyn Accessor>,
+    rules: Arc<Vec<PolicyRule>>,
+}
+
+impl PolicyAccessor {
+    fn check(&self, op: Operation, path: &str) -> Result<()> {
+        let normalized = normalize_operation(op);
+        let allowed = self
+            .rules
+            .iter()
+            .find(|rule| rule.matches(normalized, path))
+            .map(|rule| rule.allow)
+            .unwrap_or(true);
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(Error::new(
+                ErrorKind::PermissionDenied,
+                ObjectError::new(op, path, anyhow!("operation is denied by policy")),
+            ))
+        }
+    }
+
+    /// Returns true if operation could be allowed on some paths.
+    fn maybe_allowed(&self, op: Operation) -> bool {
+        for rule in self.rules.iter().filter(|rule| rule.matches_op(op)) {
+            if rule.allow {
+                return true;
+            }
+            if rule.matches_all_paths() {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Convert blocking operations to their async versions.
+fn normalize_operation(op: Operation) -> Operation {
+    match op {
+        Operation::BlockingCreate => Operation::Create,
+        Operation::BlockingRead => Operation::Read,
+        Operation::BlockingWrite => Operation::Write,
+        Operation::BlockingStat => Operation::Stat,
+        Operation::BlockingDelete => Operation::Delete,
+        Operation::BlockingList => Operation::List,
+        op => op,
+    }
+}
+
+fn glob_match(pattern: &[char], path: &[char]) -> bool {
+    match pattern {
+        [] => path.is_empty(),
+        ['*', '*', rest @ ..] => (0..=path.len()).any(|i| glob_match(rest, &path[i..])),
+        ['*', rest @ ..] => {
+            for i in 0..=path.len() {
+                if glob_match(rest, &path[i..]) {
+                    return true;
+                }
+                if path.get(i) == Some(&'/') {
+                    return false;
+                }
+            }
+            false
+        }
+        ['?', rest @ ..] => match path {
+            [c, path @ ..] if *c != '/' => glob_match(rest, path),
+            _ => false,
+        },
+        [p, rest @ ..] => match path {
+            [c, path @ ..] if c == p => glob_match(rest, path),
+            _ => false,
+        },
+    }
+}
+
+#[async_trait]
+impl Accessor for PolicyAccessor {
+    fn inner(&self) -> Option<Arc<dyn Accessor>> {
+        Some(self.inner.clone())
+    }
+
+    fn metadata(&self) -> AccessorMetadata {
+        let mut meta = self.inner.metadata();
+
+        let mut caps = meta.capabilities();
+        for (cap, op) in [
+            (AccessorCapability::Read, Operation::Read),
+            (AccessorCapability::Write, Operation::Write),
+            (AccessorCapability::List, Operation::List),
+            (AccessorCapability::Presign, Operation::Presign),
+            (AccessorCapability::Multipart, Operation::CreateMultipart),
+        ] {
+            if !self.maybe_allowed(op) {
+                caps -= cap;
+            }
+        }
+        meta.set_capabilities(caps);
+
+        meta
+    }
+
+    async fn create(&self, path: &str, args: OpCreate) -> Result<()> {
+        self.check(Operation::Create, path)?;
+
+        self.inner.create(path, args).await
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        self.check(Operation::Read, path)?;
+
+        self.inner.read(path, args).await
+    }
+
+    async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<u64> {
+        self.check(Operation::Write, path)?;
+
+        self.inner.write(path, args, r).await
+    }
+
+    async fn read_stream(&self, path: &str, args: OpRead) -> Result<BytesStreamer> {
+        self.check(Operation::Read, path)?;
+
+        self.inner.read_stream(path, args).await
+    }
+
+    async fn read_ranges(&self, path: &str, args: OpReadRanges) -> Result<Vec<Bytes>> {
+        self.check(Operation::Read, path)?;
+
+        self.inner.read_ranges(path, args).await
+    }
+
+    async fn write_stream(&self, path: &str, args: OpWrite, s: BytesStreamer) -> Result<u64> {
+        self.check(Operation::Write, path)?;
+
+        self.inner.write_stream(path, args, s).await
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
+        self.check(Operation::Stat, path)?;
+
+        self.inner.stat(path, args).await
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<()> {
+        self.check(Operation::Delete, path)?;
+
+        self.inner.delete(path, args).await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<ObjectStreamer> {
+        self.check(Operation::List, path)?;
+
+        self.inner
+            .list(path, args)
+            .await
+            .map(|s| set_accessor_for_object_steamer(s, self.clone()))
+    }
+
+    fn presign(&self, path: &str, args: OpPresign) -> Result<PresignedRequest> {
+        self.check(Operation::Presign, path)?;
+        // Presigned requests will bypass us, check the real operation too.
+        match args.operation() {
+            PresignOperation::Read(_) => self.check(Operation::Read, path)?,
+            PresignOperation::Write(_) => self.check(Operation::Write, path)?,
+            PresignOperation::WriteMultipart(_) => self.check(Operation::WriteMultipart, path)?,
+        }
+
+        self.inner.presign(path, args)
+    }
+
+    async fn create_multipart(&self, path: &str, args: OpCreateMultipart) -> Result<String> {
+        self.check(Operation::CreateMultipart, path)?;
+
+        self.inner.create_multipart(path, args).await
+    }
+
+    async fn write_multipart(
+        &self,
+        path: &str,
+        args: OpWriteMultipart,
+        r: BytesReader,
+    ) -> Result<ObjectPart> {
+        self.check(Operation::WriteMultipart, path)?;
+
+        self.inner.write_multipart(path, args, r).await
+    }
+
+    async fn complete_multipart(&self, path: &str, args: OpCompleteMultipart) -> Result<()> {
+        self.check(Operation::CompleteMultipart, path)?;
+
+        self.inner.complete_multipart(path, args).await
+    }
+
+    async fn abort_multipart(&self, path: &str, args: OpAbortMultipart) -> Result<()> {
+        self.check(Operation::AbortMultipart, path)?;
+
+        self.inner.abort_multipart(path, args).await
+    }
+
+    fn blocking_create(&self, path: &str, args: OpCreate) -> Result<()> {
+        self.check(Operation::BlockingCreate, path)?;
+
+        self.inner.blocking_create(path, args)
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<BlockingBytesReader> {
+        self.check(Operation::BlockingRead, path)?;
+
+        self.inner.blocking_read(path, args)
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite, r: BlockingBytesReader) -> Result<u64> {
+        self.check(Operation::BlockingWrite, path)?;
+
+        self.inner.blocking_write(path, args, r)
+    }
+
+    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
+        self.check(Operation::BlockingStat, path)?;
+
+        self.inner.blocking_stat(path, args)
+    }
+
+    fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<()> {
+        self.check(Operation::BlockingDelete, path)?;
+
+        self.inner.blocking_delete(path, args)
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<ObjectIterator> {
+        self.check(Operation::BlockingList, path)?;
+
+        self.inner
+            .blocking_list(path, args)
+            .map(|it| set_accessor_for_object_iterator(it, self.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::memory;
+    use crate::Operator;
+
+    #[test]
+    fn test_glob_match() {
+        let cases = vec![
+            ("**", "a/b/c", true),
+            ("**", "", true),
+            ("tmp/**", "tmp/", true),
+            ("tmp/**", "tmp/a/b", true),
+            ("tmp/**", "tmpx/a", false),
+            ("tmp/*", "tmp/a", true),
+            ("tmp/*", "tmp/a/b", false),
+            ("*.csv", "a.csv", true),
+            ("*.csv", "dir/a.csv", false),
+            ("**/*.csv", "dir/a.csv", true),
+            ("a?c", "abc", true),
+            ("a?c", "a/c", false),
+        ];
+
+        for (pattern, path, expected) in cases {
+            let pattern: Vec<char> = pattern.chars().collect();
+            let path: Vec<char> = path.chars().collect();
+            assert_eq!(
+                glob_match(&pattern, &path),
+                expected,
+                "{pattern:?} {path:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_only() -> anyhow::Result<()> {
+        let op = Operator::new(memory::Builder::default().build()?);
+        op.object("test").write("Hello").await?;
+
+        let op = op.layer(PolicyLayer::read_only());
+        assert!(!op.metadata().can_write());
+        assert!(op.metadata().can_read());
+
+        assert_eq!(op.object("test").read().await?, b"Hello");
+        let err = op.object("test").write("World").await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        let err = op.object("test").delete().await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_under_prefix() -> anyhow::Result<()> {
+        let writes = [Operation::Create, Operation::Write, Operation::Delete];
+        let op = Operator::new(memory::Builder::default().build()?).layer(
+            PolicyLayer::new()
+                .allow(&writes, "tmp/**")
+                .deny(&writes, "**"),
+        );
+        assert!(op.metadata().can_write());
+
+        op.object("tmp/test").write("Hello").await?;
+        let err = op.object("test").write("Hello").await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        let err = op.object("dir/").create().await.unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+
+        Ok(())
+    }
+}
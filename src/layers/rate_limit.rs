@@ -0,0 +1,467 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::future::Future;
+use std::io::Read;
+use std::io::Result;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Context;
+use std::task::Poll;
+use std::thread;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::ready;
+use futures::AsyncRead;
+use tokio::time::Instant;
+use tokio::time::Sleep;
+
+use super::util::set_accessor_for_object_iterator;
+use super::util::set_accessor_for_object_steamer;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpPresign;
+use crate::ops::OpRead;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::Operation;
+use crate::ops::PresignedRequest;
+use crate::Accessor;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::Layer;
+use crate::ObjectIterator;
+use crate::ObjectMetadata;
+use crate::ObjectPart;
+use crate::ObjectStreamer;
+
+/// RateLimitLayer will add rate limit for OpenDAL.
+///
+/// Two kinds of limits are supported, both of them are token buckets that
+/// allow bursting up to one second of tokens:
+///
+/// - Operations per second, for all operations or per [`Operation`].
+///   An operation must pass both limits if both of them are set.
+/// - Bytes per second, shared by data read from and written to services.
+///
+/// Blocking operations are limited by their own [`Operation`], like
+/// [`Operation::BlockingRead`].
+///
+/// # Examples
+///
+/// ```
+/// use anyhow::Result;
+/// use opendal::layers::RateLimitLayer;
+/// use opendal::ops::Operation;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// let _ = Operator::from_env(Scheme::Fs)
+///     .expect("must init")
+///     .layer(
+///         RateLimitLayer::new()
+///             .with_ops_per_second(1000)
+///             .with_operation_limit(Operation::Write, 100)
+///             .with_bytes_per_second(64 * 1024 * 1024),
+///     );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RateLimitLayer {
+    ops_per_second: Option<u32>,
+    operation_limits: HashMap<Operation, u32>,
+    bytes_per_second: Option<u64>,
+}
+
+impl RateLimitLayer {
+    /// Create a new RateLimitLayer without any limit.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit operations per second for all operations.
+    pub fn with_ops_per_second(mut self, ops: u32) -> Self {
+        self.ops_per_second = Some(ops);
+        self
+    }
+
+    /// Limit operations per second for given operation.
+    pub fn with_operation_limit(mut self, op: Operation, ops: u32) -> Self {
+        self.operation_limits.insert(op, ops);
+        self
+    }
+
+    /// Limit bytes per second that read from or written to services.
+    pub fn with_bytes_per_second(mut self, bytes: u64) -> Self {
+        self.bytes_per_second = Some(bytes);
+        self
+    }
+}
+
+impl Layer for RateLimitLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(RateLimitAccessor {
+            inner,
+            ops: self
+                .ops_per_second
+                .map(|v| Arc::new(TokenBucket::new(v as f64))),
+            operations: Arc::new(
+                self.operation_limits
+                    .iter()
+                    .map(|(op, v)| (*op, TokenBucket::new(*v as f64)))
+                    .collect(),
+            ),
+            bytes: self
+                .bytes_per_second
+                .map(|v| Arc::new(TokenBucket::new(v as f64))),
+        })
+    }
+}
+
+/// TokenBucket refills `rate` tokens every second and holds at most `rate`
+/// tokens.
+///
+/// Acquiring is never rejected. Instead, tokens can be borrowed from the
+/// future and callers need to wait for the returned duration.
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    /// Available tokens and the last refill time.
+    state: Mutex<(f64, Instant)>,
+}
+
+impl TokenBucket {
+    fn new(rate: f64) -> Self {
+        Self {
+            rate,
+            state: Mutex::new((rate, Instant::now())),
+        }
+    }
+
+    /// Acquire `n` tokens, returns the duration to wait before going on.
+    fn acquire(&self, n: f64) -> Duration {
+        if self.rate <= 0.0 {
+            return Duration::ZERO;
+        }
+
+        let mut state = self.state.lock().expect("lock must succeed");
+        let now = Instant::now();
+        let refilled = now.duration_since(state.1).as_secs_f64() * self.rate;
+        state.0 = (state.0 + refilled).min(self.rate) - n;
+        state.1 = now;
+
+        if state.0 >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.0 / self.rate)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RateLimitAccessor {
+    inner: Arc<dyn Accessor>,
+    ops: Option<Arc<TokenBucket>>,
+    operations: Arc<HashMap<Operation, TokenBucket>>,
+    bytes: Option<Arc<TokenBucket>>,
+}
+
+impl RateLimitAccessor {
+    /// Returns the duration to wait before sending this operation.
+    fn acquire_op(&self, op: Operation) -> Duration {
+        let global = self.ops.as_ref().map(|b| b.acquire(1.0));
+        let local = self.operations.get(&op).map(|b| b.acquire(1.0));
+
+        global.max(local).unwrap_or_default()
+    }
+
+    async fn wait_op(&self, op: Operation) {
+        let dur = self.acquire_op(op);
+        if !dur.is_zero() {
+            tokio::time::sleep(dur).await;
+        }
+    }
+
+    fn blocking_wait_op(&self, op: Operation) {
+        let dur = self.acquire_op(op);
+        if !dur.is_zero() {
+            thread::sleep(dur);
+        }
+    }
+
+    fn limit_reader(&self, r: BytesReader) -> BytesReader {
+        match &self.bytes {
+            Some(bucket) => Box::new(RateLimitReader::new(r, bucket.clone())),
+            None => r,
+        }
+    }
+
+    fn limit_blocking_reader(&self, r: BlockingBytesReader) -> BlockingBytesReader {
+        match &self.bytes {
+            Some(bucket) => Box::new(BlockingRateLimitReader {
+                inner: r,
+                bucket: bucket.clone(),
+            }),
+            None => r,
+        }
+    }
+}
+
+#[async_trait]
+impl Accessor for RateLimitAccessor {
+    fn inner(&self) -> Option<Arc<dyn Accessor>> {
+        Some(self.inner.clone())
+    }
+
+    fn metadata(&self) -> AccessorMetadata {
+        self.inner.metadata()
+    }
+
+    async fn create(&self, path: &str, args: OpCreate) -> Result<()> {
+        self.wait_op(Operation::Create).await;
+
+        self.inner.create(path, args).await
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        self.wait_op(Operation::Read).await;
+
+        self.inner
+            .read(path, args)
+            .await
+            .map(|r| self.limit_reader(r))
+    }
+
+    async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<u64> {
+        self.wait_op(Operation::Write).await;
+
+        self.inner.write(path, args, self.limit_reader(r)).await
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
+        self.wait_op(Operation::Stat).await;
+
+        self.inner.stat(path, args).await
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<()> {
+        self.wait_op(Operation::Delete).await;
+
+        self.inner.delete(path, args).await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<ObjectStreamer> {
+        self.wait_op(Operation::List).await;
+
+        self.inner
+            .list(path, args)
+            .await
+            .map(|s| set_accessor_for_object_steamer(s, self.clone()))
+    }
+
+    fn presign(&self, path: &str, args: OpPresign) -> Result<PresignedRequest> {
+        self.inner.presign(path, args)
+    }
+
+    async fn create_multipart(&self, path: &str, args: OpCreateMultipart) -> Result<String> {
+        self.wait_op(Operation::CreateMultipart).await;
+
+        self.inner.create_multipart(path, args).await
+    }
+
+    async fn write_multipart(
+        &self,
+        path: &str,
+        args: OpWriteMultipart,
+        r: BytesReader,
+    ) -> Result<ObjectPart> {
+        self.wait_op(Operation::WriteMultipart).await;
+
+        self.inner
+            .write_multipart(path, args, self.limit_reader(r))
+            .await
+    }
+
+    async fn complete_multipart(&self, path: &str, args: OpCompleteMultipart) -> Result<()> {
+        self.wait_op(Operation::CompleteMultipart).await;
+
+        self.inner.complete_multipart(path, args).await
+    }
+
+    async fn abort_multipart(&self, path: &str, args: OpAbortMultipart) -> Result<()> {
+        self.wait_op(Operation::AbortMultipart).await;
+
+        self.inner.abort_multipart(path, args).await
+    }
+
+    fn blocking_create(&self, path: &str, args: OpCreate) -> Result<()> {
+        self.blocking_wait_op(Operation::BlockingCreate);
+
+        self.inner.blocking_create(path, args)
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<BlockingBytesReader> {
+        self.blocking_wait_op(Operation::BlockingRead);
+
+        self.inner
+            .blocking_read(path, args)
+            .map(|r| self.limit_blocking_reader(r))
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite, r: BlockingBytesReader) -> Result<u64> {
+        self.blocking_wait_op(Operation::BlockingWrite);
+
+        self.inner
+            .blocking_write(path, args, self.limit_blocking_reader(r))
+    }
+
+    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
+        self.blocking_wait_op(Operation::BlockingStat);
+
+        self.inner.blocking_stat(path, args)
+    }
+
+    fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<()> {
+        self.blocking_wait_op(Operation::BlockingDelete);
+
+        self.inner.blocking_delete(path, args)
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<ObjectIterator> {
+        self.blocking_wait_op(Operation::BlockingList);
+
+        self.inner
+            .blocking_list(path, args)
+            .map(|it| set_accessor_for_object_iterator(it, self.clone()))
+    }
+}
+
+/// RateLimitReader will pay for the bytes after reading and wait before
+/// the next read if tokens are exhausted.
+struct RateLimitReader {
+    inner: BytesReader,
+    bucket: Arc<TokenBucket>,
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl RateLimitReader {
+    fn new(inner: BytesReader, bucket: Arc<TokenBucket>) -> Self {
+        Self {
+            inner,
+            bucket,
+            sleep: None,
+        }
+    }
+}
+
+impl AsyncRead for RateLimitReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        if let Some(fut) = &mut self.sleep {
+            ready!(fut.as_mut().poll(cx));
+            self.sleep = None;
+        }
+
+        let n = ready!(Pin::new(&mut (*self.inner)).poll_read(cx, buf))?;
+        if n > 0 {
+            let dur = self.bucket.acquire(n as f64);
+            if !dur.is_zero() {
+                self.sleep = Some(Box::pin(tokio::time::sleep(dur)));
+            }
+        }
+        Poll::Ready(Ok(n))
+    }
+}
+
+struct BlockingRateLimitReader {
+    inner: BlockingBytesReader,
+    bucket: Arc<TokenBucket>,
+}
+
+impl Read for BlockingRateLimitReader {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let n = self.inner.read(buf)?;
+        if n > 0 {
+            let dur = self.bucket.acquire(n as f64);
+            if !dur.is_zero() {
+                thread::sleep(dur);
+            }
+        }
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::AsyncReadExt;
+
+    use super::*;
+    use crate::services::memory;
+    use crate::Operator;
+
+    #[test]
+    fn test_token_bucket() {
+        let bucket = TokenBucket::new(10.0);
+
+        // Burst is allowed up to rate.
+        for _ in 0..10 {
+            assert_eq!(bucket.acquire(1.0), Duration::ZERO);
+        }
+        // Tokens are borrowed from the future.
+        let dur = bucket.acquire(5.0);
+        assert!(dur > Duration::from_millis(400), "{dur:?}");
+        assert!(dur <= Duration::from_millis(500), "{dur:?}");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit() -> anyhow::Result<()> {
+        let op = Operator::new(memory::Builder::default().build()?).layer(
+            RateLimitLayer::new()
+                .with_operation_limit(Operation::Stat, 10)
+                .with_bytes_per_second(1024),
+        );
+
+        let content = vec![1; 2048];
+        op.object("test").write(content.clone()).await?;
+
+        let now = Instant::now();
+        for _ in 0..15 {
+            op.object("test").metadata().await?;
+        }
+        // 10 ops are allowed in burst, the rest 5 needs 0.5s.
+        assert!(now.elapsed() >= Duration::from_millis(500));
+
+        // Write has paid for 2048 bytes, the read must wait for them.
+        let now = Instant::now();
+        let mut r = op.object("test").reader().await?;
+        let mut bs = Vec::new();
+        r.read_to_end(&mut bs).await?;
+        assert_eq!(bs, content);
+        assert!(now.elapsed() >= Duration::from_secs(1));
+
+        Ok(())
+    }
+}
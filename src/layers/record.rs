@@ -0,0 +1,1047 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Cursor;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use flagset::FlagSet;
+use futures::stream;
+use futures::AsyncReadExt;
+use futures::StreamExt;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::error::ObjectError;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpRead;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::Operation;
+use crate::Accessor;
+use crate::AccessorCapability;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::Layer;
+use crate::ObjectEntry;
+use crate::ObjectIterator;
+use crate::ObjectMetadata;
+use crate::ObjectMode;
+use crate::ObjectPart;
+use crate::ObjectStreamer;
+use crate::Scheme;
+
+/// All capabilities that could be recorded, presign is not included.
+const CAPABILITIES: [AccessorCapability; 5] = [
+    AccessorCapability::Read,
+    AccessorCapability::Write,
+    AccessorCapability::List,
+    AccessorCapability::Multipart,
+    AccessorCapability::Blocking,
+];
+
+/// All error kinds that could be restored from cassette, others will be
+/// restored as [`ErrorKind::Other`].
+const ERROR_KINDS: [ErrorKind; 19] = [
+    ErrorKind::NotFound,
+    ErrorKind::PermissionDenied,
+    ErrorKind::ConnectionRefused,
+    ErrorKind::ConnectionReset,
+    ErrorKind::ConnectionAborted,
+    ErrorKind::NotConnected,
+    ErrorKind::AddrInUse,
+    ErrorKind::AddrNotAvailable,
+    ErrorKind::BrokenPipe,
+    ErrorKind::AlreadyExists,
+    ErrorKind::WouldBlock,
+    ErrorKind::InvalidInput,
+    ErrorKind::InvalidData,
+    ErrorKind::TimedOut,
+    ErrorKind::WriteZero,
+    ErrorKind::Interrupted,
+    ErrorKind::Unsupported,
+    ErrorKind::UnexpectedEof,
+    ErrorKind::OutOfMemory,
+];
+
+/// Cassette holds requests and responses captured by [`RecordLayer`], which
+/// can be served by [`ReplayAccessor`] later.
+///
+/// Cassette is stored as pretty printed JSON:
+///
+/// - Interactions are kept in the order they finished.
+/// - Fields of requests that are not used by the operation are omitted.
+/// - Bodies are stored as `{"text": "..."}` if they are valid UTF-8,
+///   otherwise as `{"base64": "..."}`.
+///
+/// Cloned cassettes share the same interactions.
+#[derive(Debug, Clone, Default)]
+pub struct Cassette {
+    data: Arc<Mutex<CassetteData>>,
+}
+
+impl Cassette {
+    /// Create a new empty cassette.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load cassette from JSON.
+    pub fn from_json(s: &str) -> Result<Self> {
+        let data: CassetteData = serde_json::from_str(s)?;
+        Ok(Self {
+            data: Arc::new(Mutex::new(data)),
+        })
+    }
+
+    /// Dump cassette as JSON.
+    pub fn to_json(&self) -> String {
+        let mut s = serde_json::to_string_pretty(&*self.data.lock())
+            .expect("cassette must be serializable");
+        s.push('\n');
+        s
+    }
+
+    /// Load cassette from file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Save cassette to file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        std::fs::write(path, self.to_json())
+    }
+
+    /// Returns the count of captured interactions.
+    pub fn len(&self) -> usize {
+        self.data.lock().interactions.len()
+    }
+
+    /// Returns true if no interaction has been captured.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn push(&self, request: Request, response: Response) {
+        self.data
+            .lock()
+            .interactions
+            .push(Interaction { request, response })
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CassetteData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<CassetteMetadata>,
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CassetteMetadata {
+    scheme: String,
+    root: String,
+    name: String,
+    capabilities: Vec<String>,
+}
+
+impl From<&AccessorMetadata> for CassetteMetadata {
+    fn from(meta: &AccessorMetadata) -> Self {
+        Self {
+            scheme: meta.scheme().to_string(),
+            root: meta.root().to_string(),
+            name: meta.name().to_string(),
+            capabilities: CAPABILITIES
+                .iter()
+                .filter(|c| meta.capabilities().contains(**c))
+                .map(|c| format!("{c:?}"))
+                .collect(),
+        }
+    }
+}
+
+impl CassetteMetadata {
+    fn to_metadata(&self) -> AccessorMetadata {
+        let mut capabilities = FlagSet::<AccessorCapability>::default();
+        for c in CAPABILITIES {
+            if self.capabilities.contains(&format!("{c:?}")) {
+                capabilities |= c;
+            }
+        }
+
+        let mut am = AccessorMetadata::default();
+        am.set_scheme(Scheme::from_str(&self.scheme).expect("scheme parse must succeed"))
+            .set_root(&self.root)
+            .set_name(&self.name)
+            .set_capabilities(capabilities);
+        am
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: Request,
+    response: Response,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct Request {
+    operation: String,
+    path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mode: Option<ObjectMode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    offset: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    size: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    upload_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    part_number: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    parts: Option<Vec<Part>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<Body>,
+}
+
+impl Request {
+    fn new(op: Operation, path: &str) -> Self {
+        Self {
+            operation: op.to_string(),
+            path: path.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn create(op: Operation, path: &str, args: &OpCreate) -> Self {
+        Self {
+            mode: Some(args.mode()),
+            ..Self::new(op, path)
+        }
+    }
+
+    fn read(op: Operation, path: &str, args: &OpRead) -> Self {
+        Self {
+            offset: args.offset(),
+            size: args.size(),
+            ..Self::new(op, path)
+        }
+    }
+
+    fn write(op: Operation, path: &str, args: &OpWrite, bs: &[u8]) -> Self {
+        Self {
+            size: Some(args.size()),
+            body: Some(Body::new(bs)),
+            ..Self::new(op, path)
+        }
+    }
+
+    fn write_multipart(path: &str, args: &OpWriteMultipart, bs: &[u8]) -> Self {
+        Self {
+            upload_id: Some(args.upload_id().to_string()),
+            part_number: Some(args.part_number()),
+            size: Some(args.size()),
+            body: Some(Body::new(bs)),
+            ..Self::new(Operation::WriteMultipart, path)
+        }
+    }
+
+    fn complete_multipart(path: &str, args: &OpCompleteMultipart) -> Self {
+        Self {
+            upload_id: Some(args.upload_id().to_string()),
+            parts: Some(args.parts().iter().map(Part::from).collect()),
+            ..Self::new(Operation::CompleteMultipart, path)
+        }
+    }
+
+    fn abort_multipart(path: &str, args: &OpAbortMultipart) -> Self {
+        Self {
+            upload_id: Some(args.upload_id().to_string()),
+            ..Self::new(Operation::AbortMultipart, path)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Part {
+    part_number: usize,
+    etag: String,
+}
+
+impl From<&ObjectPart> for Part {
+    fn from(part: &ObjectPart) -> Self {
+        Self {
+            part_number: part.part_number(),
+            etag: part.etag().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Body {
+    Text(String),
+    Base64(String),
+}
+
+impl Body {
+    fn new(bs: &[u8]) -> Self {
+        match std::str::from_utf8(bs) {
+            Ok(s) => Body::Text(s.to_string()),
+            Err(_) => Body::Base64(base64::encode(bs)),
+        }
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        match self {
+            Body::Text(s) => Ok(s.as_bytes().to_vec()),
+            Body::Base64(s) => {
+                base64::decode(s).map_err(|err| Error::new(ErrorKind::InvalidData, err))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ErrorRecord {
+    kind: String,
+    message: String,
+}
+
+impl From<&Error> for ErrorRecord {
+    fn from(err: &Error) -> Self {
+        Self {
+            kind: format!("{:?}", err.kind()),
+            message: err.to_string(),
+        }
+    }
+}
+
+impl ErrorRecord {
+    fn to_error(&self) -> Error {
+        let kind = ERROR_KINDS
+            .into_iter()
+            .find(|k| format!("{k:?}") == self.kind)
+            .unwrap_or(ErrorKind::Other);
+        Error::new(kind, self.message.clone())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ListEntry {
+    path: String,
+    metadata: ObjectMetadata,
+    complete: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Response {
+    Ok,
+    Error(ErrorRecord),
+    Size(u64),
+    Body(Body),
+    Metadata(ObjectMetadata),
+    List {
+        entries: Vec<ListEntry>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<ErrorRecord>,
+    },
+    UploadId(String),
+    Part(Part),
+}
+
+impl Response {
+    fn from_result<T>(res: &Result<T>, f: impl FnOnce(&T) -> Response) -> Response {
+        match res {
+            Ok(v) => f(v),
+            Err(err) => Response::Error(err.into()),
+        }
+    }
+}
+
+/// RecordLayer will capture every request sent to the underlying service
+/// and its response into a [`Cassette`].
+///
+/// The captured cassette can be saved and served by [`ReplayAccessor`]
+/// without accessing the underlying service, so that tests could run
+/// against real services once and run offline after that.
+///
+/// - Bodies of `read` and `write` are fully buffered in memory.
+/// - Entries returned by `list` are fully consumed before returning.
+/// - `presign` can't be replayed, so it's not supported.
+///
+/// # Examples
+///
+/// Record:
+///
+/// ```no_run
+/// use anyhow::Result;
+/// use opendal::layers::Cassette;
+/// use opendal::layers::RecordLayer;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///     let cassette = Cassette::new();
+///     let op = Operator::from_env(Scheme::S3)?.layer(RecordLayer::new(cassette.clone()));
+///
+///     op.object("test").write("Hello, World!").await?;
+///     let _ = op.object("test").read().await?;
+///
+///     cassette.save("tests/data/s3.json")?;
+///     Ok(())
+/// }
+/// ```
+///
+/// Replay:
+///
+/// ```no_run
+/// use anyhow::Result;
+/// use opendal::layers::Cassette;
+/// use opendal::layers::ReplayAccessor;
+/// use opendal::Operator;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<()> {
+///     let cassette = Cassette::load("tests/data/s3.json")?;
+///     let op = Operator::new(ReplayAccessor::new(&cassette));
+///
+///     op.object("test").write("Hello, World!").await?;
+///     let _ = op.object("test").read().await?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RecordLayer {
+    cassette: Cassette,
+}
+
+impl RecordLayer {
+    /// Create a new RecordLayer that captures into `cassette`.
+    pub fn new(cassette: Cassette) -> Self {
+        Self { cassette }
+    }
+}
+
+impl Layer for RecordLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        let acc = RecordAccessor {
+            inner,
+            cassette: self.cassette.clone(),
+        };
+        self.cassette.data.lock().metadata = Some((&acc.metadata()).into());
+
+        Arc::new(acc)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RecordAccessor {
+    inner: Arc<dyn Accessor>,
+    cassette: Cassette,
+}
+
+impl RecordAccessor {
+    fn record<T>(&self, req: Request, res: &Result<T>, f: impl FnOnce(&T) -> Response) {
+        self.cassette.push(req, Response::from_result(res, f))
+    }
+
+    fn record_list(
+        &self,
+        req: Request,
+        entries: Vec<ObjectEntry>,
+        error: Option<Error>,
+    ) -> Vec<Result<ObjectEntry>> {
+        self.cassette.push(
+            req,
+            Response::List {
+                entries: entries
+                    .iter()
+                    .map(|de| ListEntry {
+                        path: de.path().to_string(),
+                        metadata: de.metadata_raw(),
+                        complete: de.is_complete(),
+                    })
+                    .collect(),
+                error: error.as_ref().map(ErrorRecord::from),
+            },
+        );
+
+        let acc: Arc<dyn Accessor> = Arc::new(self.clone());
+        entries
+            .into_iter()
+            .map(|mut de| {
+                de.set_accessor(acc.clone());
+                Ok(de)
+            })
+            .chain(error.map(Err))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Accessor for RecordAccessor {
+    fn inner(&self) -> Option<Arc<dyn Accessor>> {
+        Some(self.inner.clone())
+    }
+
+    fn metadata(&self) -> AccessorMetadata {
+        let mut meta = self.inner.metadata();
+        meta.set_capabilities(meta.capabilities() - AccessorCapability::Presign);
+        meta
+    }
+
+    async fn create(&self, path: &str, args: OpCreate) -> Result<()> {
+        let req = Request::create(Operation::Create, path, &args);
+        let res = self.inner.create(path, args).await;
+        self.record(req, &res, |_| Response::Ok);
+        res
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        let req = Request::read(Operation::Read, path, &args);
+        let res = match self.inner.read(path, args).await {
+            Ok(mut r) => {
+                let mut bs = Vec::new();
+                r.read_to_end(&mut bs).await.map(|_| bs)
+            }
+            Err(err) => Err(err),
+        };
+        self.record(req, &res, |bs| Response::Body(Body::new(bs)));
+
+        res.map(|bs| Box::new(futures::io::Cursor::new(bs)) as BytesReader)
+    }
+
+    async fn write(&self, path: &str, args: OpWrite, mut r: BytesReader) -> Result<u64> {
+        let mut bs = Vec::new();
+        r.read_to_end(&mut bs).await?;
+
+        let req = Request::write(Operation::Write, path, &args, &bs);
+        let res = self
+            .inner
+            .write(path, args, Box::new(futures::io::Cursor::new(bs)))
+            .await;
+        self.record(req, &res, |n| Response::Size(*n));
+        res
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
+        let req = Request::new(Operation::Stat, path);
+        let res = self.inner.stat(path, args).await;
+        self.record(req, &res, |meta| Response::Metadata(meta.clone()));
+        res
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<()> {
+        let req = Request::new(Operation::Delete, path);
+        let res = self.inner.delete(path, args).await;
+        self.record(req, &res, |_| Response::Ok);
+        res
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<ObjectStreamer> {
+        let req = Request::new(Operation::List, path);
+        let mut s = match self.inner.list(path, args).await {
+            Ok(s) => s,
+            Err(err) => {
+                self.cassette.push(req, Response::Error((&err).into()));
+                return Err(err);
+            }
+        };
+
+        let mut entries = Vec::new();
+        let mut error = None;
+        while let Some(de) = s.next().await {
+            match de {
+                Ok(de) => entries.push(de),
+                Err(err) => {
+                    error = Some(err);
+                    break;
+                }
+            }
+        }
+
+        let entries = self.record_list(req, entries, error);
+        Ok(Box::new(stream::iter(entries)))
+    }
+
+    async fn create_multipart(&self, path: &str, args: OpCreateMultipart) -> Result<String> {
+        let req = Request::new(Operation::CreateMultipart, path);
+        let res = self.inner.create_multipart(path, args).await;
+        self.record(req, &res, |id| Response::UploadId(id.clone()));
+        res
+    }
+
+    async fn write_multipart(
+        &self,
+        path: &str,
+        args: OpWriteMultipart,
+        mut r: BytesReader,
+    ) -> Result<ObjectPart> {
+        let mut bs = Vec::new();
+        r.read_to_end(&mut bs).await?;
+
+        let req = Request::write_multipart(path, &args, &bs);
+        let res = self
+            .inner
+            .write_multipart(path, args, Box::new(futures::io::Cursor::new(bs)))
+            .await;
+        self.record(req, &res, |part| Response::Part(part.into()));
+        res
+    }
+
+    async fn complete_multipart(&self, path: &str, args: OpCompleteMultipart) -> Result<()> {
+        let req = Request::complete_multipart(path, &args);
+        let res = self.inner.complete_multipart(path, args).await;
+        self.record(req, &res, |_| Response::Ok);
+        res
+    }
+
+    async fn abort_multipart(&self, path: &str, args: OpAbortMultipart) -> Result<()> {
+        let req = Request::abort_multipart(path, &args);
+        let res = self.inner.abort_multipart(path, args).await;
+        self.record(req, &res, |_| Response::Ok);
+        res
+    }
+
+    fn blocking_create(&self, path: &str, args: OpCreate) -> Result<()> {
+        let req = Request::create(Operation::BlockingCreate, path, &args);
+        let res = self.inner.blocking_create(path, args);
+        self.record(req, &res, |_| Response::Ok);
+        res
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<BlockingBytesReader> {
+        let req = Request::read(Operation::BlockingRead, path, &args);
+        let res = match self.inner.blocking_read(path, args) {
+            Ok(mut r) => {
+                let mut bs = Vec::new();
+                r.read_to_end(&mut bs).map(|_| bs)
+            }
+            Err(err) => Err(err),
+        };
+        self.record(req, &res, |bs| Response::Body(Body::new(bs)));
+
+        res.map(|bs| Box::new(Cursor::new(bs)) as BlockingBytesReader)
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite, mut r: BlockingBytesReader) -> Result<u64> {
+        let mut bs = Vec::new();
+        r.read_to_end(&mut bs)?;
+
+        let req = Request::write(Operation::BlockingWrite, path, &args, &bs);
+        let res = self
+            .inner
+            .blocking_write(path, args, Box::new(Cursor::new(bs)));
+        self.record(req, &res, |n| Response::Size(*n));
+        res
+    }
+
+    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
+        let req = Request::new(Operation::BlockingStat, path);
+        let res = self.inner.blocking_stat(path, args);
+        self.record(req, &res, |meta| Response::Metadata(meta.clone()));
+        res
+    }
+
+    fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<()> {
+        let req = Request::new(Operation::BlockingDelete, path);
+        let res = self.inner.blocking_delete(path, args);
+        self.record(req, &res, |_| Response::Ok);
+        res
+    }
+
+    fn blocking_list(&self, path: &str, args: OpList) -> Result<ObjectIterator> {
+        let req = Request::new(Operation::BlockingList, path);
+        let it = match self.inner.blocking_list(path, args) {
+            Ok(it) => it,
+            Err(err) => {
+                self.cassette.push(req, Response::Error((&err).into()));
+                return Err(err);
+            }
+        };
+
+        let mut entries = Vec::new();
+        let mut error = None;
+        for de in it {
+            match de {
+                Ok(de) => entries.push(de),
+                Err(err) => {
+                    error = Some(err);
+                    break;
+                }
+            }
+        }
+
+        let entries = self.record_list(req, entries, error);
+        Ok(Box::new(entries.into_iter()))
+    }
+}
+
+/// ReplayAccessor serves responses captured by [`RecordLayer`] in a
+/// [`Cassette`] without accessing any service.
+///
+/// Every request is matched against captured requests by operation, path,
+/// arguments and body of writes. The first captured interaction that
+/// matches and hasn't been replayed will be served, so the same request
+/// could get different responses in the captured order. Requests that
+/// don't match any interaction will fail with [`ErrorKind::Other`].
+///
+/// Blocking operations only match interactions captured by blocking
+/// operations.
+///
+/// Refer to [`RecordLayer`] for examples.
+#[derive(Debug, Clone)]
+pub struct ReplayAccessor {
+    metadata: AccessorMetadata,
+    interactions: Arc<Mutex<Vec<Option<Interaction>>>>,
+}
+
+impl ReplayAccessor {
+    /// Create a new ReplayAccessor from the interactions in `cassette`.
+    ///
+    /// Interactions captured into `cassette` after this call will not be
+    /// served.
+    pub fn new(cassette: &Cassette) -> Self {
+        let data = cassette.data.lock();
+        Self {
+            metadata: data
+                .metadata
+                .as_ref()
+                .map(|meta| meta.to_metadata())
+                .unwrap_or_default(),
+            interactions: Arc::new(Mutex::new(
+                data.interactions.iter().cloned().map(Some).collect(),
+            )),
+        }
+    }
+
+    /// Take the response of first unused interaction that matches `req`.
+    fn replay(&self, op: Operation, req: Request) -> Result<Response> {
+        let found = self
+            .interactions
+            .lock()
+            .iter_mut()
+            .find(|i| matches!(i, Some(i) if i.request == req))
+            .and_then(Option::take);
+
+        match found {
+            None => Err(Error::new(
+                ErrorKind::Other,
+                ObjectError::new(
+                    op,
+                    &req.path,
+                    anyhow!("no matching interaction in cassette"),
+                ),
+            )),
+            Some(Interaction {
+                response: Response::Error(err),
+                ..
+            }) => Err(err.to_error()),
+            Some(i) => Ok(i.response),
+        }
+    }
+
+    fn replay_ok(&self, op: Operation, req: Request) -> Result<()> {
+        let path = req.path.clone();
+        match self.replay(op, req)? {
+            Response::Ok => Ok(()),
+            resp => Err(new_unexpected_response_error(op, &path, resp)),
+        }
+    }
+
+    fn replay_read(&self, op: Operation, req: Request) -> Result<Vec<u8>> {
+        let path = req.path.clone();
+        match self.replay(op, req)? {
+            Response::Body(body) => body.to_bytes(),
+            resp => Err(new_unexpected_response_error(op, &path, resp)),
+        }
+    }
+
+    fn replay_size(&self, op: Operation, req: Request) -> Result<u64> {
+        let path = req.path.clone();
+        match self.replay(op, req)? {
+            Response::Size(n) => Ok(n),
+            resp => Err(new_unexpected_response_error(op, &path, resp)),
+        }
+    }
+
+    fn replay_stat(&self, op: Operation, req: Request) -> Result<ObjectMetadata> {
+        let path = req.path.clone();
+        match self.replay(op, req)? {
+            Response::Metadata(meta) => Ok(meta),
+            resp => Err(new_unexpected_response_error(op, &path, resp)),
+        }
+    }
+
+    fn replay_list(&self, op: Operation, req: Request) -> Result<Vec<Result<ObjectEntry>>> {
+        let path = req.path.clone();
+        let (entries, error) = match self.replay(op, req)? {
+            Response::List { entries, error } => (entries, error),
+            resp => return Err(new_unexpected_response_error(op, &path, resp)),
+        };
+
+        let acc: Arc<dyn Accessor> = Arc::new(self.clone());
+        Ok(entries
+            .into_iter()
+            .map(|de| {
+                let entry = ObjectEntry::new(acc.clone(), &de.path, de.metadata);
+                Ok(if de.complete {
+                    entry.with_complete()
+                } else {
+                    entry
+                })
+            })
+            .chain(error.map(|err| Err(err.to_error())))
+            .collect())
+    }
+}
+
+fn new_unexpected_response_error(op: Operation, path: &str, resp: Response) -> Error {
+    Error::new(
+        ErrorKind::Other,
+        ObjectError::new(
+            op,
+            path,
+            anyhow!("unexpected response {resp:?} in cassette"),
+        ),
+    )
+}
+
+#[async_trait]
+impl Accessor for ReplayAccessor {
+    fn metadata(&self) -> AccessorMetadata {
+        self.metadata.clone()
+    }
+
+    async fn create(&self, path: &str, args: OpCreate) -> Result<()> {
+        self.replay_ok(
+            Operation::Create,
+            Request::create(Operation::Create, path, &args),
+        )
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        let bs = self.replay_read(Operation::Read, Request::read(Operation::Read, path, &args))?;
+        Ok(Box::new(futures::io::Cursor::new(bs)))
+    }
+
+    async fn write(&self, path: &str, args: OpWrite, mut r: BytesReader) -> Result<u64> {
+        let mut bs = Vec::new();
+        r.read_to_end(&mut bs).await?;
+
+        self.replay_size(
+            Operation::Write,
+            Request::write(Operation::Write, path, &args, &bs),
+        )
+    }
+
+    async fn stat(&self, path: &str, _: OpStat) -> Result<ObjectMetadata> {
+        self.replay_stat(Operation::Stat, Request::new(Operation::Stat, path))
+    }
+
+    async fn delete(&self, path: &str, _: OpDelete) -> Result<()> {
+        self.replay_ok(Operation::Delete, Request::new(Operation::Delete, path))
+    }
+
+    async fn list(&self, path: &str, _: OpList) -> Result<ObjectStreamer> {
+        let entries = self.replay_list(Operation::List, Request::new(Operation::List, path))?;
+        Ok(Box::new(stream::iter(entries)))
+    }
+
+    async fn create_multipart(&self, path: &str, _: OpCreateMultipart) -> Result<String> {
+        let op = Operation::CreateMultipart;
+        match self.replay(op, Request::new(op, path))? {
+            Response::UploadId(id) => Ok(id),
+            resp => Err(new_unexpected_response_error(op, path, resp)),
+        }
+    }
+
+    async fn write_multipart(
+        &self,
+        path: &str,
+        args: OpWriteMultipart,
+        mut r: BytesReader,
+    ) -> Result<ObjectPart> {
+        let mut bs = Vec::new();
+        r.read_to_end(&mut bs).await?;
+
+        let op = Operation::WriteMultipart;
+        match self.replay(op, Request::write_multipart(path, &args, &bs))? {
+            Response::Part(part) => Ok(ObjectPart::new(part.part_number, &part.etag)),
+            resp => Err(new_unexpected_response_error(op, path, resp)),
+        }
+    }
+
+    async fn complete_multipart(&self, path: &str, args: OpCompleteMultipart) -> Result<()> {
+        self.replay_ok(
+            Operation::CompleteMultipart,
+            Request::complete_multipart(path, &args),
+        )
+    }
+
+    async fn abort_multipart(&self, path: &str, args: OpAbortMultipart) -> Result<()> {
+        self.replay_ok(
+            Operation::AbortMultipart,
+            Request::abort_multipart(path, &args),
+        )
+    }
+
+    fn blocking_create(&self, path: &str, args: OpCreate) -> Result<()> {
+        self.replay_ok(
+            Operation::BlockingCreate,
+            Request::create(Operation::BlockingCreate, path, &args),
+        )
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<BlockingBytesReader> {
+        let bs = self.replay_read(
+            Operation::BlockingRead,
+            Request::read(Operation::BlockingRead, path, &args),
+        )?;
+        Ok(Box::new(Cursor::new(bs)))
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite, mut r: BlockingBytesReader) -> Result<u64> {
+        let mut bs = Vec::new();
+        r.read_to_end(&mut bs)?;
+
+        self.replay_size(
+            Operation::BlockingWrite,
+            Request::write(Operation::BlockingWrite, path, &args, &bs),
+        )
+    }
+
+    fn blocking_stat(&self, path: &str, _: OpStat) -> Result<ObjectMetadata> {
+        self.replay_stat(
+            Operation::BlockingStat,
+            Request::new(Operation::BlockingStat, path),
+        )
+    }
+
+    fn blocking_delete(&self, path: &str, _: OpDelete) -> Result<()> {
+        self.replay_ok(
+            Operation::BlockingDelete,
+            Request::new(Operation::BlockingDelete, path),
+        )
+    }
+
+    fn blocking_list(&self, path: &str, _: OpList) -> Result<ObjectIterator> {
+        let entries = self.replay_list(
+            Operation::BlockingList,
+            Request::new(Operation::BlockingList, path),
+        )?;
+        Ok(Box::new(entries.into_iter()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::services::memory;
+    use crate::Operator;
+
+    async fn run(op: &Operator) -> anyhow::Result<Vec<String>> {
+        let mut results = Vec::new();
+
+        op.object("dir/a").write("Hello, World!").await?;
+        op.object("dir/b").write(vec![0xff, 0x00, 0xfe]).await?;
+        results.push(format!("{:?}", op.object("dir/a").range_read(7..12).await?));
+        results.push(format!("{:?}", op.object("dir/b").read().await?));
+        results.push(format!(
+            "{}",
+            op.object("dir/a").metadata().await?.content_length()
+        ));
+        let mut paths: Vec<_> = op
+            .object("dir/")
+            .list()
+            .await?
+            .map_ok(|de| de.path().to_string())
+            .try_collect()
+            .await?;
+        paths.sort();
+        results.push(format!("{paths:?}"));
+        op.object("dir/a").delete().await?;
+        results.push(format!(
+            "{:?}",
+            op.object("dir/a").metadata().await.map_err(|e| e.kind())
+        ));
+
+        Ok(results)
+    }
+
+    #[tokio::test]
+    async fn test_record_replay() -> anyhow::Result<()> {
+        let cassette = Cassette::new();
+        let op = Operator::new(memory::Builder::default().build()?)
+            .layer(RecordLayer::new(cassette.clone()));
+        let expected = run(&op).await?;
+        assert_eq!(cassette.len(), 8);
+
+        // Cassette should survive a round trip.
+        let cassette = Cassette::from_json(&cassette.to_json())?;
+        assert_eq!(
+            Cassette::from_json(&cassette.to_json())?.to_json(),
+            cassette.to_json()
+        );
+
+        let op = Operator::new(ReplayAccessor::new(&cassette));
+        assert_eq!(op.metadata().scheme(), Scheme::Memory);
+        assert!(!op.metadata().can_presign());
+        assert_eq!(run(&op).await?, expected);
+
+        // All interactions have been replayed.
+        let err = op.object("dir/b").read().await.expect_err("must fail");
+        assert_eq!(err.kind(), ErrorKind::Other);
+
+        // Writes with different body don't match.
+        let op = Operator::new(ReplayAccessor::new(&cassette));
+        assert!(op.object("dir/a").write("Hello, Earth!").await.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_body() -> anyhow::Result<()> {
+        for bs in [b"abc".to_vec(), vec![0xff, 0x00]] {
+            assert_eq!(Body::new(&bs).to_bytes()?, bs);
+        }
+        assert_eq!(
+            serde_json::to_string(&Body::new(&[0xff, 0x00]))?,
+            r#"{"base64":"/wA="}"#
+        );
+
+        Ok(())
+    }
+}
@@ -24,11 +24,15 @@ use std::task::Context;
 use std::task::Poll;
 use std::thread::sleep;
 
+use anyhow::anyhow;
 use async_trait::async_trait;
 use backon::Backoff;
 use backon::Retryable;
+use bytes::Bytes;
+use futures::future::BoxFuture;
 use futures::ready;
 use futures::AsyncRead;
+use futures::Stream;
 use log::warn;
 use pin_project::pin_project;
 use tokio::time::Sleep;
@@ -42,6 +46,7 @@ use crate::ops::OpCreateMultipart;
 use crate::ops::OpDelete;
 use crate::ops::OpList;
 use crate::ops::OpRead;
+use crate::ops::OpReadRanges;
 use crate::ops::OpStat;
 use crate::ops::OpWrite;
 use crate::ops::OpWriteMultipart;
@@ -49,6 +54,7 @@ use crate::ops::Operation;
 use crate::Accessor;
 use crate::BlockingBytesReader;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::Layer;
 use crate::ObjectIterator;
 use crate::ObjectMetadata;
@@ -57,6 +63,13 @@ use crate::ObjectStreamer;
 
 /// RetryLayer will add retry for OpenDAL.
 ///
+/// # Notes
+///
+/// Readers returned by `read` are resumable: on retryable errors in the
+/// middle of reading, a new `read` will be sent with the offset set to the
+/// current position. Use [`RetryLayer::with_etag_check`] to make sure the
+/// object has not been changed between requests.
+///
 /// # Examples
 ///
 /// ```
@@ -70,7 +83,10 @@ use crate::ObjectStreamer;
 ///     .expect("must init")
 ///     .layer(RetryLayer::new(ExponentialBackoff::default()));
 /// ```
-pub struct RetryLayer<B: Backoff + Send + Sync + Debug + 'static>(B);
+pub struct RetryLayer<B: Backoff + Send + Sync + Debug + 'static> {
+    backoff: B,
+    check_etag: bool,
+}
 
 impl<B> RetryLayer<B>
 where
@@ -91,7 +107,20 @@ where
     ///     .layer(RetryLayer::new(ExponentialBackoff::default()));
     /// ```
     pub fn new(b: B) -> Self {
-        Self(b)
+        Self {
+            backoff: b,
+            check_etag: false,
+        }
+    }
+
+    /// Check the etag of object while resuming reads, default to `false`.
+    ///
+    /// An extra `stat` will be sent before reading to fetch the etag. If
+    /// the etag changed while resuming, the read will fail with an error
+    /// instead of returning mixed content.
+    pub fn with_etag_check(mut self, check: bool) -> Self {
+        self.check_etag = check;
+        self
     }
 }
 
@@ -102,7 +131,8 @@ where
     fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
         Arc::new(RetryAccessor {
             inner,
-            backoff: self.0.clone(),
+            backoff: self.backoff.clone(),
+            check_etag: self.check_etag,
         })
     }
 }
@@ -111,6 +141,7 @@ where
 struct RetryAccessor<B: Backoff + Debug + Send + Sync> {
     inner: Arc<dyn Accessor>,
     backoff: B,
+    check_etag: bool,
 }
 
 #[async_trait]
@@ -137,6 +168,24 @@ where
     }
 
     async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        let etag = if self.check_etag {
+            { || self.inner.stat(path, OpStat::new()) }
+                .retry(self.backoff.clone())
+                .when(|e| e.kind() == ErrorKind::Interrupted)
+                .notify(|err, dur| {
+                    warn!(
+                        target: "opendal::service",
+                        "operation={} -> retry after {}s: error={:?}",
+                        Operation::Stat, dur.as_secs_f64(), err)
+                })
+                .await
+                .map_err(convert_interrupted_error)?
+                .etag()
+                .map(|v| v.to_string())
+        } else {
+            None
+        };
+
         let r = { || self.inner.read(path, args.clone()) }
             .retry(self.backoff.clone())
             .when(|e| e.kind() == ErrorKind::Interrupted)
@@ -148,10 +197,13 @@ where
             })
             .await
             .map_err(convert_interrupted_error)?;
-        Ok(Box::new(RetryReader::new(
-            r,
-            Operation::Read,
+        Ok(Box::new(ResumableReader::new(
+            self.inner.clone(),
+            path,
+            args,
+            etag,
             self.backoff.clone(),
+            r,
         )))
     }
 
@@ -174,6 +226,65 @@ where
             .await
     }
 
+    async fn read_stream(&self, path: &str, args: OpRead) -> Result<BytesStreamer> {
+        let etag = if self.check_etag {
+            { || self.inner.stat(path, OpStat::new()) }
+                .retry(self.backoff.clone())
+                .when(|e| e.kind() == ErrorKind::Interrupted)
+                .notify(|err, dur| {
+                    warn!(
+                        target: "opendal::service",
+                        "operation={} -> retry after {}s: error={:?}",
+                        Operation::Stat, dur.as_secs_f64(), err)
+                })
+                .await
+                .map_err(convert_interrupted_error)?
+                .etag()
+                .map(|v| v.to_string())
+        } else {
+            None
+        };
+
+        let s = { || self.inner.read_stream(path, args.clone()) }
+            .retry(self.backoff.clone())
+            .when(|e| e.kind() == ErrorKind::Interrupted)
+            .notify(|err, dur| {
+                warn!(
+                    target: "opendal::service",
+                    "operation={} -> retry after {}s: error={:?}",
+                    Operation::Read, dur.as_secs_f64(), err)
+            })
+            .await
+            .map_err(convert_interrupted_error)?;
+        Ok(Box::new(ResumableReader::new(
+            self.inner.clone(),
+            path,
+            args,
+            etag,
+            self.backoff.clone(),
+            s,
+        )))
+    }
+
+    async fn read_ranges(&self, path: &str, args: OpReadRanges) -> Result<Vec<Bytes>> {
+        { || self.inner.read_ranges(path, args.clone()) }
+            .retry(self.backoff.clone())
+            .when(|e| e.kind() == ErrorKind::Interrupted)
+            .notify(|err, dur| {
+                warn!(
+                    target: "opendal::service",
+                    "operation={} -> retry after {}s: error={:?}",
+                    Operation::Read, dur.as_secs_f64(), err)
+            })
+            .await
+            .map_err(convert_interrupted_error)
+    }
+
+    /// Streams can't be replayed, so `write_stream` will not be retried.
+    async fn write_stream(&self, path: &str, args: OpWrite, s: BytesStreamer) -> Result<u64> {
+        self.inner.write_stream(path, args, s).await
+    }
+
     async fn stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
         { || self.inner.stat(path, args.clone()) }
             .retry(self.backoff.clone())
@@ -520,6 +631,215 @@ where
     }
 }
 
+/// Resume is the source that could be reopened by [`ResumableReader`].
+trait Resume: Unpin + Send + Sized + 'static {
+    fn open(acc: Arc<dyn Accessor>, path: String, args: OpRead)
+        -> BoxFuture<'static, Result<Self>>;
+}
+
+impl Resume for BytesReader {
+    fn open(
+        acc: Arc<dyn Accessor>,
+        path: String,
+        args: OpRead,
+    ) -> BoxFuture<'static, Result<Self>> {
+        Box::pin(async move { acc.read(&path, args).await })
+    }
+}
+
+impl Resume for BytesStreamer {
+    fn open(
+        acc: Arc<dyn Accessor>,
+        path: String,
+        args: OpRead,
+    ) -> BoxFuture<'static, Result<Self>> {
+        Box::pin(async move { acc.read_stream(&path, args).await })
+    }
+}
+
+/// ResumableReader will reopen the reader (or stream) at current position
+/// while meeting retryable errors.
+#[pin_project]
+struct ResumableReader<B: Backoff + Debug + Send + Sync, R: Resume> {
+    acc: Arc<dyn Accessor>,
+    path: String,
+    args: OpRead,
+    /// Etag of object while reading starts, `None` means don't check.
+    etag: Option<String>,
+    /// Bytes that already delivered.
+    pos: u64,
+    state: ResumableState<R>,
+
+    backoff: B,
+    retry: Option<B>,
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+enum ResumableState<R> {
+    Reading(R),
+    Opening(BoxFuture<'static, Result<R>>),
+}
+
+impl<B: Backoff + Debug + Send + Sync, R: Resume> ResumableReader<B, R> {
+    fn new(
+        acc: Arc<dyn Accessor>,
+        path: &str,
+        args: OpRead,
+        etag: Option<String>,
+        backoff: B,
+        r: R,
+    ) -> Self {
+        Self {
+            acc,
+            path: path.to_string(),
+            args,
+            etag,
+            pos: 0,
+            state: ResumableState::Reading(r),
+            backoff,
+            retry: None,
+            sleep: None,
+        }
+    }
+
+    /// Build the args to read the rest of content.
+    fn resume_args(&self) -> OpRead {
+        let size = self.args.size().map(|v| v - self.pos);
+        match self.args.offset() {
+            Some(offset) => OpRead::default()
+                .with_offset(Some(offset + self.pos))
+                .with_size(size),
+            // Read the last `size` bytes, the rest is still at the end.
+            None if size.is_some() => OpRead::default().with_size(size),
+            None => OpRead::default().with_offset(Some(self.pos)),
+        }
+    }
+
+    fn reopen(&mut self) {
+        let acc = self.acc.clone();
+        let path = self.path.clone();
+        let etag = self.etag.clone();
+        let args = self.resume_args();
+
+        let fut = async move {
+            if let Some(etag) = etag {
+                let meta = acc.stat(&path, OpStat::new()).await?;
+                if meta.etag() != Some(etag.as_str()) {
+                    return Err(Error::new(
+                        ErrorKind::Other,
+                        anyhow!(
+                            "object has been changed while reading, expect etag {etag}, actual {:?}",
+                            meta.etag()
+                        ),
+                    ));
+                }
+            }
+
+            R::open(acc, path, args).await
+        };
+        self.state = ResumableState::Opening(Box::pin(fut));
+    }
+
+    /// Poll the inner reader (or stream) with `poll`, `len` returns the size
+    /// of content that has been delivered.
+    fn poll_resume<T>(
+        &mut self,
+        cx: &mut Context<'_>,
+        mut poll: impl FnMut(&mut R, &mut Context<'_>) -> Poll<Result<T>>,
+        len: impl Fn(&T) -> usize,
+    ) -> Poll<Result<T>> {
+        loop {
+            if let Some(fut) = &mut self.sleep {
+                ready!(fut.as_mut().poll(cx));
+                self.sleep = None;
+                self.reopen();
+            }
+
+            let res = match &mut self.state {
+                ResumableState::Opening(fut) => match ready!(fut.as_mut().poll(cx)) {
+                    Ok(r) => {
+                        self.state = ResumableState::Reading(r);
+                        continue;
+                    }
+                    Err(err) => {
+                        // The future has completed, prepare a fresh one so that
+                        // the next read can open again instead of polling it.
+                        self.reopen();
+                        Err(err)
+                    }
+                },
+                ResumableState::Reading(r) => ready!(poll(r, cx)),
+            };
+
+            match res {
+                Ok(v) => {
+                    // Reset retry to none.
+                    self.retry = None;
+                    self.pos += len(&v) as u64;
+
+                    return Poll::Ready(Ok(v));
+                }
+                Err(err) if err.kind() == ErrorKind::Interrupted => {
+                    let retry = self.retry.get_or_insert_with(|| self.backoff.clone());
+
+                    match retry.next() {
+                        None => {
+                            // Reset retry to none.
+                            self.retry = None;
+
+                            return Poll::Ready(Err(err));
+                        }
+                        Some(dur) => {
+                            warn!(
+                                target: "opendal::service",
+                                "operation={} path={} -> resume at {} after {}s: error={:?}",
+                                Operation::Read, self.path, self.pos, dur.as_secs_f64(), err);
+
+                            self.sleep = Some(Box::pin(tokio::time::sleep(dur)));
+                            continue;
+                        }
+                    }
+                }
+                Err(err) => {
+                    // Reset retry to none.
+                    self.retry = None;
+
+                    return Poll::Ready(Err(err));
+                }
+            }
+        }
+    }
+}
+
+impl<B> AsyncRead for ResumableReader<B, BytesReader>
+where
+    B: Backoff + Debug + Send + Sync,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        self.poll_resume(cx, |r, cx| Pin::new(r).poll_read(cx, buf), |n| *n)
+    }
+}
+
+impl<B> Stream for ResumableReader<B, BytesStreamer>
+where
+    B: Backoff + Debug + Send + Sync,
+{
+    type Item = Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_resume(
+            cx,
+            |s, cx| Pin::new(s).poll_next(cx).map(|v| v.transpose()),
+            |v| v.as_ref().map_or(0, |bs| bs.len()),
+        )
+        .map(|v| v.transpose())
+    }
+}
+
 /// CloneableReader makes a reader cloneable.
 ///
 /// # Safety
@@ -585,12 +905,16 @@ mod tests {
     use futures::io::Cursor;
     use futures::AsyncRead;
     use futures::AsyncReadExt;
+    use futures::TryStreamExt;
 
     use crate::layers::RetryLayer;
     use crate::ops::OpRead;
+    use crate::ops::OpStat;
     use crate::ops::OpWrite;
     use crate::Accessor;
     use crate::BytesReader;
+    use crate::ObjectMetadata;
+    use crate::ObjectMode;
     use crate::Operator;
 
     #[derive(Debug, Clone, Default)]
@@ -806,4 +1130,144 @@ mod tests {
 
         Ok(())
     }
+
+    #[derive(Debug, Clone, Default)]
+    struct MockResumeService {
+        offsets: Arc<Mutex<Vec<u64>>>,
+        etag: Arc<Mutex<String>>,
+    }
+
+    const RESUME_CONTENT: &[u8] = b"Hello, World!";
+
+    #[async_trait]
+    impl Accessor for MockResumeService {
+        async fn read(&self, _: &str, args: OpRead) -> io::Result<BytesReader> {
+            let offset = args.offset().unwrap_or(0);
+            self.offsets.lock().unwrap().push(offset);
+
+            let end = match args.size() {
+                Some(size) => offset + size,
+                None => RESUME_CONTENT.len() as u64,
+            };
+            Ok(Box::new(MockResumeReader {
+                data: &RESUME_CONTENT[offset as usize..end as usize],
+                sent: false,
+            }))
+        }
+
+        async fn stat(&self, _: &str, _: OpStat) -> io::Result<ObjectMetadata> {
+            Ok(ObjectMetadata::new(ObjectMode::FILE)
+                .with_content_length(RESUME_CONTENT.len() as u64)
+                .with_etag(&self.etag.lock().unwrap()))
+        }
+    }
+
+    /// MockResumeReader returns at most 5 bytes and then breaks.
+    struct MockResumeReader {
+        data: &'static [u8],
+        sent: bool,
+    }
+
+    impl AsyncRead for MockResumeReader {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<io::Result<usize>> {
+            if self.data.is_empty() {
+                return Poll::Ready(Ok(0));
+            }
+            if self.sent {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::Interrupted,
+                    anyhow!("connection reset"),
+                )));
+            }
+
+            let n = self.data.len().min(buf.len()).min(5);
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            self.sent = true;
+            Poll::Ready(Ok(n))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_read_resume() -> anyhow::Result<()> {
+        let _ = env_logger::try_init();
+
+        let srv = Arc::new(MockResumeService::default());
+
+        let backoff = ConstantBackoff::default().with_delay(Duration::from_micros(1));
+        let op = Operator::new(srv.clone()).layer(RetryLayer::new(backoff));
+
+        let mut content = Vec::new();
+        let mut r = op.object("test").reader().await?;
+        r.read_to_end(&mut content).await?;
+        assert_eq!(content, RESUME_CONTENT);
+        assert_eq!(*srv.offsets.lock().unwrap(), vec![0, 5, 10]);
+
+        srv.offsets.lock().unwrap().clear();
+        let mut content = Vec::new();
+        let mut r = op.object("test").range_reader(2..12).await?;
+        r.read_to_end(&mut content).await?;
+        assert_eq!(content, &RESUME_CONTENT[2..12]);
+        assert_eq!(*srv.offsets.lock().unwrap(), vec![2, 7]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_retry_read_stream_resume() -> anyhow::Result<()> {
+        let _ = env_logger::try_init();
+
+        let srv = Arc::new(MockResumeService::default());
+
+        let backoff = ConstantBackoff::default().with_delay(Duration::from_micros(1));
+        let op = Operator::new(srv.clone()).layer(RetryLayer::new(backoff));
+
+        let mut content = Vec::new();
+        let mut s = op.object("test").stream().await?;
+        while let Some(bs) = s.try_next().await? {
+            content.extend_from_slice(&bs);
+        }
+        assert_eq!(content, RESUME_CONTENT);
+        assert_eq!(*srv.offsets.lock().unwrap(), vec![0, 5, 10]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_retry_read_resume_etag_changed() -> anyhow::Result<()> {
+        let _ = env_logger::try_init();
+
+        let srv = Arc::new(MockResumeService::default());
+        *srv.etag.lock().unwrap() = "v1".to_string();
+
+        let backoff = ConstantBackoff::default().with_delay(Duration::from_micros(1));
+        let op = Operator::new(srv.clone()).layer(RetryLayer::new(backoff).with_etag_check(true));
+
+        let mut r = op.object("test").reader().await?;
+        let mut buf = vec![0; 1024];
+        let n = r.read(&mut buf).await?;
+        assert_eq!(&buf[..n], b"Hello");
+
+        *srv.etag.lock().unwrap() = "v2".to_string();
+        let result = r.read_to_end(&mut Vec::new()).await;
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("changed"));
+        // Reader must not be reopened after etag changed.
+        assert_eq!(*srv.offsets.lock().unwrap(), vec![0]);
+
+        // Read again after error should try to open again instead of panic.
+        let result = r.read(&mut buf).await;
+        assert!(result.unwrap_err().to_string().contains("changed"));
+        *srv.etag.lock().unwrap() = "v1".to_string();
+        let mut content = Vec::new();
+        r.read_to_end(&mut content).await?;
+        assert_eq!(content, &RESUME_CONTENT[5..]);
+        assert_eq!(*srv.offsets.lock().unwrap(), vec![0, 5, 10]);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,477 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeSet;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::Stream;
+
+use crate::object::EmptyObjectIterator;
+use crate::object::EmptyObjectStreamer;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpPresign;
+use crate::ops::OpRead;
+use crate::ops::OpReadRanges;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::PresignedRequest;
+use crate::path::normalize_root;
+use crate::Accessor;
+use crate::AccessorMetadata;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::BytesStreamer;
+use crate::Layer;
+use crate::ObjectEntry;
+use crate::ObjectIterator;
+use crate::ObjectMetadata;
+use crate::ObjectMode;
+use crate::ObjectPart;
+use crate::ObjectStreamer;
+use crate::Operator;
+
+/// MountTable maps path prefixes to operators.
+///
+/// Prefixes will be normalized like dirs, so `/raw`, `raw/` and `/raw/`
+/// are the same mount point. Mount the same prefix again will replace
+/// the previous one.
+#[derive(Debug, Clone, Default)]
+pub struct MountTable {
+    mounts: Vec<(String, Operator)>,
+}
+
+impl MountTable {
+    /// Create a new empty MountTable.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mount operator at prefix.
+    pub fn mount(mut self, prefix: &str, op: Operator) -> Self {
+        // Always trim the first `/`
+        let prefix = normalize_root(prefix)[1..].to_string();
+
+        self.mounts.retain(|(v, _)| v != &prefix);
+        self.mounts.push((prefix, op));
+        self
+    }
+}
+
+/// RouterLayer composes several operators into one namespace by a
+/// [`MountTable`].
+///
+/// Every operation will be dispatched to the operator mounted at the
+/// longest matching prefix, with the prefix stripped from path. Paths that
+/// don't match any mount point will be sent to the operator this layer
+/// applied on.
+///
+/// Listing a dir which contains mount points will include them as dir
+/// entries after the listed ones, like listing root with mount points `raw/`
+/// and `cache/`.
+///
+/// Every operation is handled by exactly one operator, objects can't be
+/// moved or copied across mount points by a single operation.
+///
+/// # Examples
+///
+/// ```
+/// use opendal::layers::MountTable;
+/// use opendal::layers::RouterLayer;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// let table = MountTable::new()
+///     .mount("/cache/", Operator::from_env(Scheme::Fs).expect("must init"))
+///     .mount("/tmp/", Operator::from_env(Scheme::Memory).expect("must init"));
+///
+/// let _ = Operator::from_env(Scheme::Memory)
+///     .expect("must init")
+///     .layer(RouterLayer::new(table));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RouterLayer {
+    table: MountTable,
+}
+
+impl RouterLayer {
+    /// Create a new RouterLayer by mount table.
+    pub fn new(table: MountTable) -> Self {
+        Self { table }
+    }
+}
+
+impl Layer for RouterLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        let mut mounts: Vec<_> = self
+            .table
+            .mounts
+            .iter()
+            .map(|(prefix, op)| (prefix.clone(), op.inner()))
+            .collect();
+        // Longer prefixes must be matched first.
+        mounts.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+
+        Arc::new(RouterAccessor {
+            inner,
+            mounts: Arc::new(mounts),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RouterAccessor {
+    inner: Arc<dyn Accessor>,
+    /// Mount points sorted by prefix length in descending order.
+    mounts: Arc<Vec<(String, Arc<dyn Accessor>)>>,
+}
+
+impl RouterAccessor {
+    /// Route path to the accessor, returns the matched prefix, accessor and
+    /// rewritten path.
+    fn route(&self, path: &str) -> (&str, &Arc<dyn Accessor>, String) {
+        for (prefix, acc) in self.mounts.iter() {
+            if let Some(p) = path.strip_prefix(prefix.as_str()) {
+                let p = if p.is_empty() { "/" } else { p };
+                return (prefix.as_str(), acc, p.to_string());
+            }
+        }
+
+        ("", &self.inner, path.to_string())
+    }
+
+    /// Mount points that should be listed in dir as sub dirs.
+    fn mount_points(&self, path: &str) -> Vec<String> {
+        let base = if path == "/" { "" } else { path };
+
+        let set: BTreeSet<_> = self
+            .mounts
+            .iter()
+            .filter_map(|(prefix, _)| {
+                let rest = prefix.strip_prefix(base)?;
+                let idx = rest.find('/')?;
+                Some(format!("{base}{}", &rest[..=idx]))
+            })
+            .collect();
+        // Reverse so that they can be popped in order.
+        set.into_iter().rev().collect()
+    }
+}
+
+#[async_trait]
+impl Accessor for RouterAccessor {
+    fn inner(&self) -> Option<Arc<dyn Accessor>> {
+        Some(self.inner.clone())
+    }
+
+    fn metadata(&self) -> AccessorMetadata {
+        let mut meta = self.inner.metadata();
+        let caps = self
+            .mounts
+            .iter()
+            .fold(meta.capabilities(), |caps, (_, acc)| {
+                caps | acc.metadata().capabilities()
+            });
+        meta.set_capabilities(caps);
+        meta
+    }
+
+    async fn create(&self, path: &str, args: OpCreate) -> Result<()> {
+        let (_, acc, path) = self.route(path);
+
+        acc.create(&path, args).await
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        let (_, acc, path) = self.route(path);
+
+        acc.read(&path, args).await
+    }
+
+    async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<u64> {
+        let (_, acc, path) = self.route(path);
+
+        acc.write(&path, args, r).await
+    }
+
+    async fn read_stream(&self, path: &str, args: OpRead) -> Result<BytesStreamer> {
+        let (_, acc, path) = self.route(path);
+
+        acc.read_stream(&path, args).await
+    }
+
+    async fn read_ranges(&self, path: &str, args: OpReadRanges) -> Result<Vec<Bytes>> {
+        let (_, acc, path) = self.route(path);
+
+        acc.read_ranges(&path, args).await
+    }
+
+    async fn write_stream(&self, path: &str, args: OpWrite, s: BytesStreamer) -> Result<u64> {
+        let (_, acc, path) = self.route(path);
+
+        acc.write_stream(&path, args, s).await
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
+        let (_, acc, path) = self.route(path);
+
+        acc.stat(&path, args).await
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<()> {
+        let (_, acc, path) = self.route(path);
+
+        acc.delete(&path, args).await
+    }
+
+    async fn list(&self, dir: &str, args: OpList) -> Result<ObjectStreamer> {
+        let (prefix, acc, path) = self.route(dir);
+        let mount_points = self.mount_points(dir);
+
+        let inner = match acc.list(&path, args).await {
+            Ok(s) => s,
+            Err(err) if err.kind() == ErrorKind::NotFound && !mount_points.is_empty() => {
+                Box::new(EmptyObjectStreamer)
+            }
+            Err(err) => return Err(err),
+        };
+
+        Ok(Box::new(RouterStreamer {
+            acc: Arc::new(self.clone()),
+            prefix: prefix.to_string(),
+            mount_points,
+            inner,
+        }))
+    }
+
+    fn presign(&self, path: &str, args: OpPresign) -> Result<PresignedRequest> {
+        let (_, acc, path) = self.route(path);
+
+        acc.presign(&path, args)
+    }
+
+    async fn create_multipart(&self, path: &str, args: OpCreateMultipart) -> Result<String> {
+        let (_, acc, path) = self.route(path);
+
+        acc.create_multipart(&path, args).await
+    }
+
+    async fn write_multipart(
+        &self,
+        path: &str,
+        args: OpWriteMultipart,
+        r: BytesReader,
+    ) -> Result<ObjectPart> {
+        let (_, acc, path) = self.route(path);
+
+        acc.write_multipart(&path, args, r).await
+    }
+
+    async fn complete_multipart(&self, path: &str, args: OpCompleteMultipart) -> Result<()> {
+        let (_, acc, path) = self.route(path);
+
+        acc.complete_multipart(&path, args).await
+    }
+
+    async fn abort_multipart(&self, path: &str, args: OpAbortMultipart) -> Result<()> {
+        let (_, acc, path) = self.route(path);
+
+        acc.abort_multipart(&path, args).await
+    }
+
+    fn blocking_create(&self, path: &str, args: OpCreate) -> Result<()> {
+        let (_, acc, path) = self.route(path);
+
+        acc.blocking_create(&path, args)
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<BlockingBytesReader> {
+        let (_, acc, path) = self.route(path);
+
+        acc.blocking_read(&path, args)
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite, r: BlockingBytesReader) -> Result<u64> {
+        let (_, acc, path) = self.route(path);
+
+        acc.blocking_write(&path, args, r)
+    }
+
+    fn blocking_stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
+        let (_, acc, path) = self.route(path);
+
+        acc.blocking_stat(&path, args)
+    }
+
+    fn blocking_delete(&self, path: &str, args: OpDelete) -> Result<()> {
+        let (_, acc, path) = self.route(path);
+
+        acc.blocking_delete(&path, args)
+    }
+
+    fn blocking_list(&self, dir: &str, args: OpList) -> Result<ObjectIterator> {
+        let (prefix, acc, path) = self.route(dir);
+        let mount_points = self.mount_points(dir);
+
+        let inner = match acc.blocking_list(&path, args) {
+            Ok(s) => s,
+            Err(err) if err.kind() == ErrorKind::NotFound && !mount_points.is_empty() => {
+                Box::new(EmptyObjectIterator)
+            }
+            Err(err) => return Err(err),
+        };
+
+        Ok(Box::new(RouterIterator {
+            acc: Arc::new(self.clone()),
+            prefix: prefix.to_string(),
+            mount_points,
+            inner,
+        }))
+    }
+}
+
+/// Fix the entry listed from mounted operator.
+fn fix_entry(
+    acc: &Arc<dyn Accessor>,
+    prefix: &str,
+    mount_points: &mut Vec<String>,
+    mut de: ObjectEntry,
+) -> ObjectEntry {
+    de.set_accessor(acc.clone());
+    if !prefix.is_empty() {
+        de.set_path(&format!("{prefix}{}", de.path()));
+    }
+    mount_points.retain(|v| v != de.path());
+    de
+}
+
+fn mount_point_entry(acc: &Arc<dyn Accessor>, path: &str) -> ObjectEntry {
+    ObjectEntry::new(acc.clone(), path, ObjectMetadata::new(ObjectMode::DIR))
+}
+
+struct RouterStreamer {
+    acc: Arc<dyn Accessor>,
+    prefix: String,
+    /// Mount points that not listed yet, in reversed order.
+    mount_points: Vec<String>,
+    inner: ObjectStreamer,
+}
+
+impl Stream for RouterStreamer {
+    type Item = Result<ObjectEntry>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        match Pin::new(&mut this.inner).poll_next(cx) {
+            Poll::Ready(Some(Ok(de))) => Poll::Ready(Some(Ok(fix_entry(
+                &this.acc,
+                &this.prefix,
+                &mut this.mount_points,
+                de,
+            )))),
+            Poll::Ready(None) => Poll::Ready(
+                this.mount_points
+                    .pop()
+                    .map(|p| Ok(mount_point_entry(&this.acc, &p))),
+            ),
+            v => v,
+        }
+    }
+}
+
+struct RouterIterator {
+    acc: Arc<dyn Accessor>,
+    prefix: String,
+    /// Mount points that not listed yet, in reversed order.
+    mount_points: Vec<String>,
+    inner: ObjectIterator,
+}
+
+impl Iterator for RouterIterator {
+    type Item = Result<ObjectEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.inner.next() {
+            Some(Ok(de)) => Some(Ok(fix_entry(
+                &self.acc,
+                &self.prefix,
+                &mut self.mount_points,
+                de,
+            ))),
+            None => self
+                .mount_points
+                .pop()
+                .map(|p| Ok(mount_point_entry(&self.acc, &p))),
+            v => v,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use super::*;
+    use crate::services::memory;
+
+    #[tokio::test]
+    async fn test_router() -> anyhow::Result<()> {
+        let root = Operator::new(memory::Builder::default().build()?);
+        let raw = Operator::new(memory::Builder::default().build()?);
+        let hot = Operator::new(memory::Builder::default().build()?);
+        let op = root.clone().layer(RouterLayer::new(
+            MountTable::new()
+                .mount("/raw/", raw.clone())
+                .mount("raw/hot", hot.clone()),
+        ));
+
+        op.object("raw/a").write("raw a").await?;
+        op.object("raw/hot/b").write("hot b").await?;
+        op.object("c").write("root c").await?;
+        assert_eq!(raw.object("a").read().await?, b"raw a");
+        assert_eq!(hot.object("b").read().await?, b"hot b");
+        assert_eq!(root.object("c").read().await?, b"root c");
+
+        let mut paths = Vec::new();
+        let mut s = op.object("/").list().await?;
+        while let Some(de) = s.next().await {
+            paths.push(de?.path().to_string());
+        }
+        assert_eq!(paths, vec!["c", "raw/"]);
+
+        let mut paths = Vec::new();
+        let mut s = op.object("raw/").list().await?;
+        while let Some(de) = s.next().await {
+            let de = de?;
+            // Entries must be accessible by listed path.
+            assert!(de.clone().into_object().is_exist().await?);
+            paths.push(de.path().to_string());
+        }
+        assert_eq!(paths, vec!["raw/a", "raw/hot/"]);
+
+        Ok(())
+    }
+}
@@ -20,6 +20,7 @@ use std::task::Context;
 use std::task::Poll;
 
 use async_trait::async_trait;
+use bytes::Bytes;
 use futures::Stream;
 
 use crate::ops::OpAbortMultipart;
@@ -30,6 +31,7 @@ use crate::ops::OpDelete;
 use crate::ops::OpList;
 use crate::ops::OpPresign;
 use crate::ops::OpRead;
+use crate::ops::OpReadRanges;
 use crate::ops::OpStat;
 use crate::ops::OpWrite;
 use crate::ops::OpWriteMultipart;
@@ -39,6 +41,7 @@ use crate::Accessor;
 use crate::AccessorMetadata;
 use crate::BlockingBytesReader;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::Layer;
 use crate::ObjectEntry;
 use crate::ObjectIterator;
@@ -133,6 +136,24 @@ impl Accessor for SubdirAccessor {
         self.inner.write(&path, args, r).await
     }
 
+    async fn read_stream(&self, path: &str, args: OpRead) -> Result<BytesStreamer> {
+        let path = self.prepend_subdir(path);
+
+        self.inner.read_stream(&path, args).await
+    }
+
+    async fn read_ranges(&self, path: &str, args: OpReadRanges) -> Result<Vec<Bytes>> {
+        let path = self.prepend_subdir(path);
+
+        self.inner.read_ranges(&path, args).await
+    }
+
+    async fn write_stream(&self, path: &str, args: OpWrite, s: BytesStreamer) -> Result<u64> {
+        let path = self.prepend_subdir(path);
+
+        self.inner.write_stream(&path, args, s).await
+    }
+
     async fn stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
         let path = self.prepend_subdir(path);
 
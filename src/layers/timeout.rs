@@ -0,0 +1,491 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::future::Future;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Result;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::task::Context;
+use std::task::Poll;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use futures::ready;
+use futures::AsyncRead;
+use tokio::time::Instant;
+use tokio::time::Sleep;
+
+use super::util::set_accessor_for_object_steamer;
+use crate::error::ObjectError;
+use crate::ops::OpAbortMultipart;
+use crate::ops::OpCompleteMultipart;
+use crate::ops::OpCreate;
+use crate::ops::OpCreateMultipart;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpRead;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::OpWriteMultipart;
+use crate::ops::Operation;
+use crate::Accessor;
+use crate::AccessorMetadata;
+use crate::BytesReader;
+use crate::Layer;
+use crate::ObjectMetadata;
+use crate::ObjectPart;
+use crate::ObjectStreamer;
+
+/// TimeoutLayer will add timeout for OpenDAL.
+///
+/// - Every operation will be failed if it doesn't finish before deadline.
+///   Deadlines can be set for all operations or per [`Operation`].
+/// - Readers returned by `read` and input readers of `write` will be failed
+///   if no bytes move for the io timeout.
+///
+/// Timeout errors are returned with [`ErrorKind::Interrupted`], so
+/// [`RetryLayer`][super::RetryLayer] will retry them. Put `TimeoutLayer`
+/// before `RetryLayer` to make every attempt has its own deadline.
+///
+/// # Notes
+///
+/// - The deadline of `read` only covers opening the reader, use io timeout
+///   to detect stalled readers.
+/// - The deadline of `write` covers the whole upload.
+/// - Blocking operations can't be cancelled, so they are not affected.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use anyhow::Result;
+/// use opendal::layers::TimeoutLayer;
+/// use opendal::ops::Operation;
+/// use opendal::Operator;
+/// use opendal::Scheme;
+///
+/// let _ = Operator::from_env(Scheme::Fs)
+///     .expect("must init")
+///     .layer(
+///         TimeoutLayer::new()
+///             .with_timeout(Duration::from_secs(10))
+///             .with_operation_timeout(Operation::Write, Duration::from_secs(60))
+///             .with_io_timeout(Duration::from_secs(3)),
+///     );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TimeoutLayer {
+    timeout: Option<Duration>,
+    operation_timeouts: HashMap<Operation, Duration>,
+    io_timeout: Option<Duration>,
+}
+
+impl TimeoutLayer {
+    /// Create a new TimeoutLayer without any timeout.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set deadline for all operations.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set deadline for given operation, overrides the one set by
+    /// [`TimeoutLayer::with_timeout`].
+    pub fn with_operation_timeout(mut self, op: Operation, timeout: Duration) -> Self {
+        self.operation_timeouts.insert(op, timeout);
+        self
+    }
+
+    /// Set timeout for readers and writers that no bytes move.
+    pub fn with_io_timeout(mut self, timeout: Duration) -> Self {
+        self.io_timeout = Some(timeout);
+        self
+    }
+}
+
+impl Layer for TimeoutLayer {
+    fn layer(&self, inner: Arc<dyn Accessor>) -> Arc<dyn Accessor> {
+        Arc::new(TimeoutAccessor {
+            inner,
+            timeout: self.timeout,
+            operation_timeouts: Arc::new(self.operation_timeouts.clone()),
+            io_timeout: self.io_timeout,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TimeoutAccessor {
+    inner: Arc<dyn Accessor>,
+    timeout: Option<Duration>,
+    operation_timeouts: Arc<HashMap<Operation, Duration>>,
+    io_timeout: Option<Duration>,
+}
+
+impl TimeoutAccessor {
+    async fn deadline<F: Future<Output = Result<T>>, T>(
+        &self,
+        op: Operation,
+        path: &str,
+        fut: F,
+    ) -> Result<T> {
+        let dur = match self.operation_timeouts.get(&op).or(self.timeout.as_ref()) {
+            Some(dur) => *dur,
+            None => return fut.await,
+        };
+
+        tokio::time::timeout(dur, fut).await.map_err(|_| {
+            Error::new(
+                ErrorKind::Interrupted,
+                ObjectError::new(
+                    op,
+                    path,
+                    anyhow!("operation timeout after {}s", dur.as_secs_f64()),
+                ),
+            )
+        })?
+    }
+
+    fn stall_reader(&self, op: Operation, path: &str, r: BytesReader) -> BytesReader {
+        match self.io_timeout {
+            Some(dur) => Box::new(StallTimeoutReader::new(r, op, path, dur)),
+            None => r,
+        }
+    }
+
+    /// Run write like operations, fail if the input reader has no progress
+    /// for io timeout.
+    async fn stall_write<'a, F, T>(
+        &'a self,
+        op: Operation,
+        path: &'a str,
+        r: BytesReader,
+        f: F,
+    ) -> Result<T>
+    where
+        F: FnOnce(BytesReader) -> Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>,
+    {
+        let dur = match self.io_timeout {
+            Some(dur) => dur,
+            None => return self.deadline(op, path, f(r)).await,
+        };
+
+        let progress = Arc::new(Mutex::new(Instant::now()));
+        let r = Box::new(ProgressReader {
+            inner: r,
+            progress: progress.clone(),
+        });
+        let fut = StallTimeoutFuture {
+            inner: f(r),
+            progress,
+            sleep: Box::pin(tokio::time::sleep(dur)),
+            timeout: dur,
+            op,
+            path: path.to_string(),
+        };
+        self.deadline(op, path, fut).await
+    }
+}
+
+#[async_trait]
+impl Accessor for TimeoutAccessor {
+    fn inner(&self) -> Option<Arc<dyn Accessor>> {
+        Some(self.inner.clone())
+    }
+
+    fn metadata(&self) -> AccessorMetadata {
+        self.inner.metadata()
+    }
+
+    async fn create(&self, path: &str, args: OpCreate) -> Result<()> {
+        self.deadline(Operation::Create, path, self.inner.create(path, args))
+            .await
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        self.deadline(Operation::Read, path, self.inner.read(path, args))
+            .await
+            .map(|r| self.stall_reader(Operation::Read, path, r))
+    }
+
+    async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<u64> {
+        self.stall_write(Operation::Write, path, r, |r| {
+            self.inner.write(path, args, r)
+        })
+        .await
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
+        self.deadline(Operation::Stat, path, self.inner.stat(path, args))
+            .await
+    }
+
+    async fn delete(&self, path: &str, args: OpDelete) -> Result<()> {
+        self.deadline(Operation::Delete, path, self.inner.delete(path, args))
+            .await
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<ObjectStreamer> {
+        self.deadline(Operation::List, path, self.inner.list(path, args))
+            .await
+            .map(|s| set_accessor_for_object_steamer(s, self.clone()))
+    }
+
+    async fn create_multipart(&self, path: &str, args: OpCreateMultipart) -> Result<String> {
+        self.deadline(
+            Operation::CreateMultipart,
+            path,
+            self.inner.create_multipart(path, args),
+        )
+        .await
+    }
+
+    async fn write_multipart(
+        &self,
+        path: &str,
+        args: OpWriteMultipart,
+        r: BytesReader,
+    ) -> Result<ObjectPart> {
+        self.stall_write(Operation::WriteMultipart, path, r, |r| {
+            self.inner.write_multipart(path, args, r)
+        })
+        .await
+    }
+
+    async fn complete_multipart(&self, path: &str, args: OpCompleteMultipart) -> Result<()> {
+        self.deadline(
+            Operation::CompleteMultipart,
+            path,
+            self.inner.complete_multipart(path, args),
+        )
+        .await
+    }
+
+    async fn abort_multipart(&self, path: &str, args: OpAbortMultipart) -> Result<()> {
+        self.deadline(
+            Operation::AbortMultipart,
+            path,
+            self.inner.abort_multipart(path, args),
+        )
+        .await
+    }
+}
+
+fn new_stall_timeout_error(op: Operation, path: &str, dur: Duration) -> Error {
+    Error::new(
+        ErrorKind::Interrupted,
+        ObjectError::new(
+            op,
+            path,
+            anyhow!("io timeout: no bytes moved in {}s", dur.as_secs_f64()),
+        ),
+    )
+}
+
+/// StallTimeoutReader will fail if inner reader keeps pending for timeout.
+struct StallTimeoutReader {
+    inner: BytesReader,
+    op: Operation,
+    path: String,
+    timeout: Duration,
+    /// Started while inner reader returns pending.
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl StallTimeoutReader {
+    fn new(inner: BytesReader, op: Operation, path: &str, timeout: Duration) -> Self {
+        Self {
+            inner,
+            op,
+            path: path.to_string(),
+            timeout,
+            sleep: None,
+        }
+    }
+}
+
+impl AsyncRead for StallTimeoutReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        match Pin::new(&mut (*self.inner)).poll_read(cx, buf) {
+            Poll::Ready(res) => {
+                self.sleep = None;
+                Poll::Ready(res)
+            }
+            Poll::Pending => {
+                let timeout = self.timeout;
+                let sleep = self
+                    .sleep
+                    .get_or_insert_with(|| Box::pin(tokio::time::sleep(timeout)));
+                ready!(sleep.as_mut().poll(cx));
+
+                self.sleep = None;
+                Poll::Ready(Err(new_stall_timeout_error(
+                    self.op,
+                    &self.path,
+                    self.timeout,
+                )))
+            }
+        }
+    }
+}
+
+/// ProgressReader records the last time that bytes have been read.
+struct ProgressReader {
+    inner: BytesReader,
+    progress: Arc<Mutex<Instant>>,
+}
+
+impl AsyncRead for ProgressReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        let res = ready!(Pin::new(&mut (*self.inner)).poll_read(cx, buf));
+        *self.progress.lock().expect("lock must succeed") = Instant::now();
+        Poll::Ready(res)
+    }
+}
+
+/// StallTimeoutFuture will fail if the input reader of inner future has
+/// no progress for timeout.
+struct StallTimeoutFuture<'a, T> {
+    inner: Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>,
+    progress: Arc<Mutex<Instant>>,
+    sleep: Pin<Box<Sleep>>,
+    timeout: Duration,
+    op: Operation,
+    path: String,
+}
+
+impl<'a, T> Future for StallTimeoutFuture<'a, T> {
+    type Output = Result<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Poll::Ready(res) = self.inner.as_mut().poll(cx) {
+            return Poll::Ready(res);
+        }
+
+        loop {
+            ready!(self.sleep.as_mut().poll(cx));
+
+            // Reset the timer if reader has progress since it started.
+            let deadline = *self.progress.lock().expect("lock must succeed") + self.timeout;
+            if deadline <= Instant::now() {
+                return Poll::Ready(Err(new_stall_timeout_error(
+                    self.op,
+                    &self.path,
+                    self.timeout,
+                )));
+            }
+            self.sleep.as_mut().reset(deadline);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use futures::AsyncReadExt;
+
+    use super::*;
+    use crate::Operator;
+
+    #[derive(Debug, Clone, Default)]
+    struct MockService;
+
+    #[async_trait]
+    impl Accessor for MockService {
+        async fn read(&self, path: &str, _: OpRead) -> Result<BytesReader> {
+            if path == "slow" {
+                tokio::time::sleep(Duration::from_secs(3600)).await;
+            }
+            Ok(Box::new(MockReader))
+        }
+
+        async fn write(&self, _: &str, _: OpWrite, mut r: BytesReader) -> Result<u64> {
+            let mut bs = Vec::new();
+            r.read_to_end(&mut bs).await?;
+            Ok(bs.len() as u64)
+        }
+    }
+
+    /// MockReader is never ready.
+    struct MockReader;
+
+    impl AsyncRead for MockReader {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+            _: &mut [u8],
+        ) -> Poll<Result<usize>> {
+            Poll::Pending
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_operation_timeout() -> anyhow::Result<()> {
+        let op = Operator::new(Arc::new(MockService)).layer(
+            TimeoutLayer::new()
+                .with_timeout(Duration::from_secs(3600 * 2))
+                .with_operation_timeout(Operation::Read, Duration::from_secs(1)),
+        );
+
+        let err = op
+            .object("slow")
+            .reader()
+            .await
+            .err()
+            .expect("read must fail");
+        assert_eq!(err.kind(), ErrorKind::Interrupted);
+
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_io_timeout() -> anyhow::Result<()> {
+        let op = Operator::new(Arc::new(MockService))
+            .layer(TimeoutLayer::new().with_io_timeout(Duration::from_secs(1)));
+
+        let mut r = op.object("test").reader().await?;
+        let err = r
+            .read(&mut [0; 8])
+            .await
+            .expect_err("stalled read must fail");
+        assert_eq!(err.kind(), ErrorKind::Interrupted);
+
+        let err = op
+            .object("test")
+            .write_from(1, MockReader)
+            .await
+            .expect_err("stalled write must fail");
+        assert_eq!(err.kind(), ErrorKind::Interrupted);
+
+        Ok(())
+    }
+}
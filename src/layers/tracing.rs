@@ -21,6 +21,7 @@ use std::task::Context;
 use std::task::Poll;
 
 use async_trait::async_trait;
+use bytes::Bytes;
 use futures::AsyncRead;
 use tracing::Span;
 
@@ -34,6 +35,7 @@ use crate::ops::OpDelete;
 use crate::ops::OpList;
 use crate::ops::OpPresign;
 use crate::ops::OpRead;
+use crate::ops::OpReadRanges;
 use crate::ops::OpStat;
 use crate::ops::OpWrite;
 use crate::ops::OpWriteMultipart;
@@ -42,6 +44,7 @@ use crate::Accessor;
 use crate::AccessorMetadata;
 use crate::BlockingBytesReader;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::Layer;
 use crate::ObjectEntry;
 use crate::ObjectIterator;
@@ -106,6 +109,25 @@ impl Accessor for TracingAccessor {
         self.inner.write(path, args, r).await
     }
 
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn read_stream(&self, path: &str, args: OpRead) -> Result<BytesStreamer> {
+        self.inner
+            .read_stream(path, args)
+            .await
+            .map(|s| Box::new(TracingBytesStreamer::new(Span::current(), s)) as BytesStreamer)
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
+    async fn read_ranges(&self, path: &str, args: OpReadRanges) -> Result<Vec<Bytes>> {
+        self.inner.read_ranges(path, args).await
+    }
+
+    #[tracing::instrument(level = "debug", skip(self, s))]
+    async fn write_stream(&self, path: &str, args: OpWrite, s: BytesStreamer) -> Result<u64> {
+        let s = Box::new(TracingBytesStreamer::new(Span::current(), s));
+        self.inner.write_stream(path, args, s).await
+    }
+
     #[tracing::instrument(level = "debug", skip(self))]
     async fn stat(&self, path: &str, args: OpStat) -> Result<ObjectMetadata> {
         self.inner.stat(path, args).await
@@ -218,6 +240,26 @@ impl AsyncRead for TracingReader {
     }
 }
 
+struct TracingBytesStreamer {
+    span: Span,
+    inner: BytesStreamer,
+}
+
+impl TracingBytesStreamer {
+    fn new(span: Span, inner: BytesStreamer) -> Self {
+        Self { span, inner }
+    }
+}
+
+impl futures::Stream for TracingBytesStreamer {
+    type Item = Result<Bytes>;
+
+    #[tracing::instrument(parent = &self.span, level = "trace", skip_all)]
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut (*self.inner)).poll_next(cx)
+    }
+}
+
 struct BlockingTracingReader {
     span: Span,
     inner: BlockingBytesReader,
@@ -23,6 +23,7 @@
 //! | Services | Description |
 //! | -------- | ----------- |
 //! | [azblob][services::azblob] | Azure Storage Blob services. |
+//! | [disk_cache][services::disk_cache] | Local disk cache with LRU eviction. |
 //! | [fs][services::fs] | POSIX alike file system. |
 //! | [ftp][services::ftp] | FTP and FTPS support. |
 //! | [gcs][services::gcs] | Google Cloud Storage service. |
@@ -47,14 +48,27 @@
 //!
 //! | Layers | Description |
 //! | -------- | ----------- |
+//! | [AuditLayer][layers::AuditLayer] | Audit records for every mutation. |
+//! | [ChaosLayer][layers::ChaosLayer] | Fault injection for testing. |
+//! | [CompressionLayer][layers::CompressionLayer] | Transparent compression. |
 //! | [ConcurrentLimitLayer][layers::ConcurrentLimitLayer] | Concurrent request limit. |
 //! | [ContentCacheLayer][layers::ContentCacheLayer] | Content cache. |
+//! | [EncryptionLayer][layers::EncryptionLayer] | Client-side encryption. |
+//! | [FailoverLayer][layers::FailoverLayer] | Fall back to replicas if primary is unavailable. |
+//! | [HedgeLayer][layers::HedgeLayer] | Hedged requests for tail latency. |
 //! | [ImmutableIndexLayer][layers::ImmutableIndexLayer] | Immutable in-memory index. |
 //! | [LoggingLayer][layers::LoggingLayer] | Logging for every operations. |
 //! | [MetadataCacheLayer][layers::MetadataCacheLayer] | Metadata cache. |
 //! | [MetricsLayer][layers::MetricsLayer] | Metrics for every operations. |
+//! | [MirrorLayer][layers::MirrorLayer] | Replicate writes to secondary operators. |
+//! | [OverlayLayer][layers::OverlayLayer] | Writable upper on a read-only lower. |
+//! | [PolicyLayer][layers::PolicyLayer] | Allow or deny operations by path. |
+//! | [RateLimitLayer][layers::RateLimitLayer] | Operations and bandwidth rate limit. |
+//! | [RecordLayer][layers::RecordLayer] | Record and replay for deterministic tests. |
 //! | [RetryLayer][layers::RetryLayer] | Retry for failed operations. |
+//! | [RouterLayer][layers::RouterLayer] | Compose operators by mount points. |
 //! | [SubdirLayer][layers::SubdirLayer] | Allow switching directory. |
+//! | [TimeoutLayer][layers::TimeoutLayer] | Deadlines and io stall timeout. |
 //! | [TracingLayer][layers::TracingLayer] | Tracing for every operations. |
 //!
 //! # Optional features
@@ -62,6 +76,8 @@
 //! ## Layers
 //!
 //! - `layers-all`: Enable all layers support.
+//! - `layers-chaos`: Enable fault injection support.
+//! - `layers-encryption`: Enable client-side encryption support.
 //! - `layers-metrics`: Enable operator metrics support.
 //! - `layers-tracing`: Enable operator tracing support.
 //!
@@ -75,7 +91,7 @@
 //!
 //! ## Dependencies features
 //!
-//! - `compress`: Enable object decompress read support.
+//! - `compress`: Enable object decompress read and compress write support.
 //!
 //! # Examples
 //!
@@ -155,6 +171,7 @@ pub use io::BytesRead;
 pub use io::BytesReader;
 pub use io::BytesSink;
 pub use io::BytesStream;
+pub use io::BytesStreamer;
 pub use io::BytesWrite;
 pub use io::BytesWriter;
 
@@ -182,6 +182,16 @@ impl ObjectEntry {
         self.meta.lock().expect("lock must succeed").clone()
     }
 
+    /// Return the metadata carried by this entry without fetching.
+    pub(crate) fn metadata_raw(&self) -> ObjectMetadata {
+        self.meta.lock().expect("lock must succeed").clone()
+    }
+
+    /// Check if this entry already carries all metadata.
+    pub(crate) fn is_complete(&self) -> bool {
+        self.complete.load(Ordering::Relaxed)
+    }
+
     /// Update ObjectEntry's metadata by setting new one.
     pub fn set_metadata(&self, meta: ObjectMetadata) -> &Self {
         let mut guard = self.meta.lock().expect("lock must succeed");
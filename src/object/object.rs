@@ -15,22 +15,31 @@
 use std::fmt::Debug;
 use std::io::ErrorKind;
 use std::io::Result;
+use std::ops::Range;
 use std::ops::RangeBounds;
 use std::sync::Arc;
 
 use anyhow::anyhow;
+use async_compat::Compat;
+use bytes::Bytes;
 use futures::io;
 use futures::io::Cursor;
 use time::Duration;
 
 use crate::error::new_other_object_error;
 use crate::io::BytesRead;
+use crate::io_util::blocking_seekable_read;
 use crate::io_util::seekable_read;
+use crate::io_util::streaming_write;
+use crate::io_util::BlockingSeekableReader;
 #[cfg(feature = "compress")]
 use crate::io_util::CompressAlgorithm;
 #[cfg(feature = "compress")]
+use crate::io_util::CompressReader;
+#[cfg(feature = "compress")]
 use crate::io_util::DecompressReader;
 use crate::io_util::SeekableReader;
+use crate::io_util::StreamingWriter;
 use crate::ops::BytesRange;
 use crate::ops::OpCreate;
 use crate::ops::OpCreateMultipart;
@@ -38,6 +47,7 @@ use crate::ops::OpDelete;
 use crate::ops::OpList;
 use crate::ops::OpPresign;
 use crate::ops::OpRead;
+use crate::ops::OpReadRanges;
 use crate::ops::OpStat;
 use crate::ops::OpWrite;
 use crate::ops::Operation;
@@ -47,6 +57,8 @@ use crate::path::normalize_path;
 use crate::path::validate_path;
 use crate::Accessor;
 use crate::BlockingBytesRead;
+use crate::BytesStream;
+use crate::BytesStreamer;
 use crate::ObjectIterator;
 use crate::ObjectMetadata;
 use crate::ObjectMode;
@@ -498,6 +510,123 @@ impl Object {
         self.acc.read(self.path(), OpRead::new(range)).await
     }
 
+    /// Create a new stream of bytes which can read the whole object.
+    ///
+    /// Services that receive data in chunks will pass chunks through
+    /// without copy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// use futures::TryStreamExt;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/file");
+    /// # o.write(vec![0; 4096]).await?;
+    /// let mut s = o.stream().await?;
+    /// while let Some(bs) = s.try_next().await? {
+    ///     println!("read {} bytes", bs.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn stream(&self) -> Result<BytesStreamer> {
+        self.range_stream(..).await
+    }
+
+    /// Create a new stream of bytes which can read the specified range.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/file");
+    /// let s = o.range_stream(1024..2048).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn range_stream(&self, range: impl RangeBounds<u64>) -> Result<BytesStreamer> {
+        if !validate_path(self.path(), ObjectMode::FILE) {
+            return Err(new_other_object_error(
+                Operation::Read,
+                self.path(),
+                anyhow!("Is a directory"),
+            ));
+        }
+
+        self.acc.read_stream(self.path(), OpRead::new(range)).await
+    }
+
+    /// Read multiple ranges of object, returns the content of every range
+    /// in the same order.
+    ///
+    /// Ranges that are close together will be merged into fewer requests
+    /// which will be sent concurrently. Use [`Object::read_ranges_with`] to
+    /// configure the max gap and concurrency.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/file");
+    /// # o.write(vec![0; 4096]).await?;
+    /// let bs = o.read_ranges(&[0..10, 1024..2048]).await?;
+    /// assert_eq!(bs[1].len(), 1024);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_ranges(&self, ranges: &[Range<u64>]) -> Result<Vec<Bytes>> {
+        self.read_ranges_with(OpReadRanges::new(ranges)).await
+    }
+
+    /// Read multiple ranges of object with extra options.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// use opendal::ops::OpReadRanges;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/file");
+    /// let args = OpReadRanges::new(vec![0..10, 1024..2048])
+    ///     .with_max_gap(1024 * 1024)
+    ///     .with_concurrency(4);
+    /// let bs = o.read_ranges_with(args).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_ranges_with(&self, args: OpReadRanges) -> Result<Vec<Bytes>> {
+        if !validate_path(self.path(), ObjectMode::FILE) {
+            return Err(new_other_object_error(
+                Operation::Read,
+                self.path(),
+                anyhow!("Is a directory"),
+            ));
+        }
+
+        self.acc.read_ranges(self.path(), args).await
+    }
+
     /// Create a new reader which can read the specified range.
     ///
     /// # Examples
@@ -540,6 +669,8 @@ impl Object {
     ///
     /// - Seeking is pure in memory operation.
     /// - Every first read after seeking will start a new read operation on backend.
+    ///   Read-ahead, block cache and seek coalescing can be enabled by
+    ///   [`SeekableReader::with_read_ahead`] and friends to reduce requests.
     ///
     /// This operation is neither async nor returning result, because real IO happens while
     /// users call `read` or `seek`.
@@ -564,6 +695,109 @@ impl Object {
         seekable_read(self, range)
     }
 
+    /// Create a reader which implements Read, Seek and BufRead inside specified range.
+    ///
+    /// # Notes
+    ///
+    /// It shares the same buffering policy with [`Object::seekable_reader`]:
+    ///
+    /// - Seeking is pure in memory operation.
+    /// - Every first read after seeking will start a new read operation on backend.
+    ///   Read-ahead, block cache and seek coalescing can be enabled by
+    ///   [`BlockingSeekableReader::with_read_ahead`] and friends to reduce requests.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// # fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Fs)?;
+    /// # let o = op.object("path/to/file");
+    /// let r = o.blocking_seekable_reader(1024..2048);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn blocking_seekable_reader(&self, range: impl RangeBounds<u64>) -> BlockingSeekableReader {
+        blocking_seekable_read(self, range)
+    }
+
+    /// Create a new reader which implements tokio's `AsyncRead` and can
+    /// read the whole object.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/file");
+    /// let mut r = o.tokio_reader().await?;
+    /// let mut f = tokio::fs::File::create("/tmp/file").await?;
+    /// tokio::io::copy(&mut r, &mut f).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn tokio_reader(&self) -> Result<impl tokio::io::AsyncRead + Unpin + Send> {
+        self.tokio_range_reader(..).await
+    }
+
+    /// Create a new reader which implements tokio's `AsyncRead` and can
+    /// read the specified range of object.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/file");
+    /// let r = o.tokio_range_reader(1024..2048).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn tokio_range_reader(
+        &self,
+        range: impl RangeBounds<u64>,
+    ) -> Result<impl tokio::io::AsyncRead + Unpin + Send> {
+        Ok(Compat::new(self.range_reader(range).await?))
+    }
+
+    /// Create a reader which implements tokio's `AsyncRead` and `AsyncSeek`
+    /// inside specified range.
+    ///
+    /// It shares the same behavior with [`Object::seekable_reader`]. To
+    /// enable read-ahead, wrap the configured reader by
+    /// [`async_compat::Compat`] directly.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// # let o = op.object("path/to/file");
+    /// let r = o.tokio_seekable_reader(1024..2048);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn tokio_seekable_reader(
+        &self,
+        range: impl RangeBounds<u64>,
+    ) -> impl tokio::io::AsyncRead + tokio::io::AsyncSeek + Unpin + Send {
+        Compat::new(self.seekable_reader(range))
+    }
+
     /// Read the whole object into a bytes with auto detected compress algorithm.
     ///
     /// If we can't find the correct algorithm, we return `Ok(None)` instead.
@@ -701,6 +935,168 @@ impl Object {
         Ok(DecompressReader::new(r, algo))
     }
 
+    /// Compress data from a [`BytesRead`] and write into object with auto-detected
+    /// compress algorithm.
+    ///
+    /// If we can't find the correct algorithm, an error will be returned.
+    ///
+    /// # Notes
+    ///
+    /// Please read the notes of [`Object::compress_write_with`].
+    ///
+    /// # Feature
+    ///
+    /// This function needs to enable feature `compress`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use opendal::services::memory;
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// use futures::io::Cursor;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/file.gz");
+    /// let r = Cursor::new(vec![0; 4096]);
+    /// let _ = o.compress_write(r).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "compress")]
+    pub async fn compress_write(&self, br: impl BytesRead + 'static) -> Result<()> {
+        let algo = match CompressAlgorithm::from_path(self.path()) {
+            Some(v) => v,
+            None => {
+                return Err(new_other_object_error(
+                    Operation::Write,
+                    self.path(),
+                    anyhow!("compress algorithm can't be detected from path"),
+                ))
+            }
+        };
+
+        self.compress_write_with(algo, br).await
+    }
+
+    /// Compress data from a [`BytesRead`] with specific compress algorithm and
+    /// write into object.
+    ///
+    /// The compressed content is stored as is: `Content-Encoding` will not be
+    /// set, otherwise services like gcs will decompress it while reading. Use
+    /// [`Object::compress_write_with_encoding`] to set it.
+    ///
+    /// # Notes
+    ///
+    /// The size of compressed content can't be known before compressing while
+    /// `write` requires it. So we will compress the whole content into memory
+    /// before writing.
+    ///
+    /// # Feature
+    ///
+    /// This function needs to enable feature `compress`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use opendal::services::memory;
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::io_util::CompressAlgorithm;
+    /// # use opendal::Scheme;
+    /// use futures::io::Cursor;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/file.gz");
+    /// let r = Cursor::new(vec![0; 4096]);
+    /// let _ = o.compress_write_with(CompressAlgorithm::Gzip, r).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "compress")]
+    pub async fn compress_write_with(
+        &self,
+        algo: CompressAlgorithm,
+        br: impl BytesRead + 'static,
+    ) -> Result<()> {
+        self.compress_write_inner(algo, br, None).await
+    }
+
+    /// Compress data from a [`BytesRead`] with specific compress algorithm and
+    /// write into object with `Content-Encoding` set.
+    ///
+    /// `Content-Encoding` will be set if the algorithm is a valid HTTP content
+    /// coding, see [`CompressAlgorithm::content_encoding`]. Services and HTTP
+    /// clients could decompress the content transparently while reading, so
+    /// only use it for content that should be served decompressed.
+    ///
+    /// # Feature
+    ///
+    /// This function needs to enable feature `compress`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use opendal::services::memory;
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::io_util::CompressAlgorithm;
+    /// # use opendal::Scheme;
+    /// use futures::io::Cursor;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/index.html");
+    /// let r = Cursor::new(vec![0; 4096]);
+    /// let _ = o
+    ///     .compress_write_with_encoding(CompressAlgorithm::Gzip, r)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "compress")]
+    pub async fn compress_write_with_encoding(
+        &self,
+        algo: CompressAlgorithm,
+        br: impl BytesRead + 'static,
+    ) -> Result<()> {
+        self.compress_write_inner(algo, br, algo.content_encoding())
+            .await
+    }
+
+    #[cfg(feature = "compress")]
+    async fn compress_write_inner(
+        &self,
+        algo: CompressAlgorithm,
+        br: impl BytesRead + 'static,
+        content_encoding: Option<&str>,
+    ) -> Result<()> {
+        if !validate_path(self.path(), ObjectMode::FILE) {
+            return Err(new_other_object_error(
+                Operation::Write,
+                self.path(),
+                anyhow!("Is a directory"),
+            ));
+        }
+
+        let r = CompressReader::new(br, algo);
+        let mut bs = Cursor::new(Vec::new());
+        io::copy(r, &mut bs).await?;
+        let bs = bs.into_inner();
+
+        let mut op = OpWrite::new(bs.len() as u64);
+        if let Some(encoding) = content_encoding {
+            op = op.with_content_encoding(encoding);
+        }
+        self.write_with(op, bs).await
+    }
+
     /// Write bytes into object.
     ///
     /// # Notes
@@ -890,6 +1286,116 @@ impl Object {
         Ok(())
     }
 
+    /// Write data into object from a [`BytesStream`].
+    ///
+    /// # Notes
+    ///
+    /// - Write will make sure all bytes has been written, or an error will be returned.
+    /// - Services that send data in chunks will pass chunks through without copy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// use bytes::Bytes;
+    /// use futures::stream;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/file");
+    /// let s = stream::iter(vec![Ok(Bytes::from(vec![0; 4096]))]);
+    /// let _ = o.write_stream(4096, s).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn write_stream(&self, size: u64, s: impl BytesStream + 'static) -> Result<()> {
+        if !validate_path(self.path(), ObjectMode::FILE) {
+            return Err(new_other_object_error(
+                Operation::Write,
+                self.path(),
+                anyhow!("Is a directory"),
+            ));
+        }
+
+        let _ = self
+            .acc
+            .write_stream(self.path(), OpWrite::new(size), Box::new(s))
+            .await?;
+        Ok(())
+    }
+
+    /// Create a writer which streams data into object with known size.
+    ///
+    /// # Notes
+    ///
+    /// - Data will be committed after `close` returns.
+    /// - Write will make sure all bytes has been written, or an error will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// use futures::AsyncWriteExt;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/file");
+    /// let mut w = o.writer(4096)?;
+    /// w.write_all(&vec![0; 4096]).await?;
+    /// w.close().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn writer(&self, size: u64) -> Result<StreamingWriter> {
+        if !validate_path(self.path(), ObjectMode::FILE) {
+            return Err(new_other_object_error(
+                Operation::Write,
+                self.path(),
+                anyhow!("Is a directory"),
+            ));
+        }
+
+        Ok(streaming_write(self, size))
+    }
+
+    /// Create a writer which implements tokio's `AsyncWrite` and streams
+    /// data into object with known size.
+    ///
+    /// # Notes
+    ///
+    /// - Data will be committed after `shutdown` returns.
+    /// - Write will make sure all bytes has been written, or an error will be returned.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::io::Result;
+    /// # use opendal::Operator;
+    /// # use opendal::Scheme;
+    /// use tokio::io::AsyncWriteExt;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/file");
+    /// let mut f = tokio::fs::File::open("/tmp/file").await?;
+    /// let size = f.metadata().await?.len();
+    /// let mut w = o.tokio_writer(size)?;
+    /// tokio::io::copy(&mut f, &mut w).await?;
+    /// w.shutdown().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn tokio_writer(&self, size: u64) -> Result<impl tokio::io::AsyncWrite + Unpin + Send> {
+        Ok(Compat::new(self.writer(size)?))
+    }
+
     /// Write data into object from a [`BlockingBytesRead`].
     ///
     /// # Notes
@@ -1022,6 +1528,32 @@ impl Object {
     /// # }
     /// ```
     pub async fn list(&self) -> Result<ObjectStreamer> {
+        self.list_with(OpList::new()).await
+    }
+
+    /// List current dir object with extra options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use anyhow::Result;
+    /// # use opendal::Operator;
+    /// # use futures::TryStreamExt;
+    /// # use opendal::Scheme;
+    /// use opendal::ops::OpList;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// # let op = Operator::from_env(Scheme::Memory)?;
+    /// let o = op.object("path/to/dir/");
+    /// // Fetch the next page while the current one is being consumed.
+    /// let mut ds = o.list_with(OpList::new().with_prefetch(1)).await?;
+    /// while let Some(de) = ds.try_next().await? {
+    ///     println!("{}", de.path());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_with(&self, args: OpList) -> Result<ObjectStreamer> {
         if !validate_path(self.path(), ObjectMode::DIR) {
             return Err(new_other_object_error(
                 Operation::List,
@@ -1030,7 +1562,7 @@ impl Object {
             ));
         }
 
-        self.acc.list(self.path(), OpList::new()).await
+        self.acc.list(self.path(), args).await
     }
 
     /// List current dir object.
@@ -1264,3 +1796,53 @@ impl Object {
         Ok(self.to_multipart(&upload_id))
     }
 }
+
+#[cfg(all(test, feature = "compress"))]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use futures::AsyncReadExt;
+
+    use super::*;
+    use crate::BytesReader;
+
+    /// MockService will record the content encoding of all writes.
+    #[derive(Debug, Default)]
+    struct MockService {
+        encodings: Mutex<Vec<Option<String>>>,
+    }
+
+    #[async_trait]
+    impl Accessor for MockService {
+        async fn write(&self, _: &str, args: OpWrite, mut r: BytesReader) -> Result<u64> {
+            self.encodings
+                .lock()
+                .unwrap()
+                .push(args.content_encoding().map(|v| v.to_string()));
+            let mut bs = Vec::new();
+            r.read_to_end(&mut bs).await?;
+            Ok(bs.len() as u64)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compress_write_content_encoding() -> anyhow::Result<()> {
+        let srv = Arc::new(MockService::default());
+        let o = Object::new(srv.clone(), "test.gz");
+
+        o.compress_write(Cursor::new(vec![0; 4096])).await?;
+        o.compress_write_with_encoding(CompressAlgorithm::Gzip, Cursor::new(vec![0; 4096]))
+            .await?;
+        // Raw deflate is not a valid HTTP content coding.
+        o.compress_write_with_encoding(CompressAlgorithm::Deflate, Cursor::new(vec![0; 4096]))
+            .await?;
+
+        assert_eq!(
+            *srv.encodings.lock().unwrap(),
+            vec![None, Some("gzip".to_string()), None]
+        );
+
+        Ok(())
+    }
+}
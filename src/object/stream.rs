@@ -12,10 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::VecDeque;
+use std::io::Error;
 use std::io::Result;
 use std::pin::Pin;
 use std::sync::Arc;
-use std::task::ready;
 use std::task::Context;
 use std::task::Poll;
 use std::vec::IntoIter;
@@ -59,11 +60,24 @@ pub trait ObjectPageStream: Send {
 }
 
 /// ObjectPageStreamer will convert an [`ObjectPageStream`] to [`ObjectStream`]
+///
+/// # Prefetch
+///
+/// By default, the next page will be fetched only after the current one has
+/// been consumed. Use [`ObjectPageStreamer::with_prefetch`] to fetch up to
+/// `depth` pages ahead while the current page is being yielded.
 #[pin_project]
 pub struct ObjectPageStreamer<S: ObjectPageStream> {
     inner: Arc<Mutex<S>>,
     fut: Option<BoxFuture<'static, Result<Option<Vec<ObjectEntry>>>>>,
     entries: IntoIter<ObjectEntry>,
+    /// Pages that have been fetched but not yielded yet.
+    pages: VecDeque<Vec<ObjectEntry>>,
+    depth: usize,
+    /// All pages have been fetched.
+    done: bool,
+    /// Error of fetching page, will be returned after all fetched entries.
+    err: Option<Error>,
 }
 
 impl<S> ObjectPageStreamer<S>
@@ -76,8 +90,19 @@ where
             inner: Arc::new(Mutex::new(inner)),
             fut: None,
             entries: vec![].into_iter(),
+            pages: VecDeque::new(),
+            depth: 0,
+            done: false,
+            err: None,
         }
     }
+
+    /// Fetch at most `depth` pages ahead, default to `0` which means
+    /// no prefetch.
+    pub fn with_prefetch(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
 }
 
 impl<S> Stream for ObjectPageStreamer<S>
@@ -87,33 +112,164 @@ where
     type Item = Result<ObjectEntry>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        let mut this = self.project();
+        let this = self.project();
 
         loop {
-            // Try to fetch entry from already cached entries.
-            if let Some(entry) = this.entries.next() {
-                return Poll::Ready(Some(Ok(entry)));
+            // Start fetching next page if we have room for it.
+            //
+            // Pages must be fetched one by one since the next page depends
+            // on the result of the last one.
+            let buffered = this.pages.len() + usize::from(!this.entries.as_slice().is_empty());
+            if this.fut.is_none() && !*this.done && buffered <= *this.depth {
+                let stream = this.inner.clone();
+                let fut = async move { stream.lock().await.next_page().await };
+                *this.fut = Some(Box::pin(fut));
             }
 
-            match &mut this.fut {
-                None => {
-                    let stream = this.inner.clone();
-                    let fut = async move { stream.lock().await.next_page().await };
-                    *this.fut = Some(Box::pin(fut));
-                }
-                Some(fut) => {
-                    let entries = ready!(Pin::new(fut).poll(cx))?;
-
+            // Drive the pending fetch even if we still have entries to yield.
+            if let Some(fut) = this.fut {
+                if let Poll::Ready(res) = Pin::new(fut).poll(cx) {
                     // Set future to None after we resolved the last one.
                     *this.fut = None;
 
-                    if let Some(entries) = entries {
-                        *this.entries = entries.into_iter();
-                    } else {
-                        return Poll::Ready(None);
+                    match res {
+                        Ok(Some(entries)) => this.pages.push_back(entries),
+                        Ok(None) => *this.done = true,
+                        Err(err) => {
+                            // Stop fetching and keep the order of entries
+                            // like a stream without prefetch.
+                            *this.done = true;
+                            *this.err = Some(err);
+                        }
                     }
+                    continue;
                 }
             }
+
+            // Try to fetch entry from already cached entries.
+            if let Some(entry) = this.entries.next() {
+                return Poll::Ready(Some(Ok(entry)));
+            }
+            if let Some(entries) = this.pages.pop_front() {
+                *this.entries = entries.into_iter();
+                continue;
+            }
+
+            if let Some(err) = this.err.take() {
+                return Poll::Ready(Some(Err(err)));
+            }
+            return if *this.done {
+                Poll::Ready(None)
+            } else {
+                Poll::Pending
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    use std::io::ErrorKind;
+
+    use anyhow::anyhow;
+    use futures::StreamExt;
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::services::memory;
+    use crate::Accessor;
+    use crate::ObjectMetadata;
+    use crate::ObjectMode;
+
+    struct MockPageStream {
+        acc: Arc<dyn Accessor>,
+        pages: usize,
+        /// Fetching this page will fail.
+        failed_page: Option<usize>,
+        fetched: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl ObjectPageStream for MockPageStream {
+        async fn next_page(&mut self) -> Result<Option<Vec<ObjectEntry>>> {
+            let idx = self.fetched.load(Ordering::SeqCst);
+            if idx >= self.pages {
+                return Ok(None);
+            }
+            if self.failed_page == Some(idx) {
+                return Err(Error::new(ErrorKind::Other, anyhow!("page {idx} failed")));
+            }
+            self.fetched.fetch_add(1, Ordering::SeqCst);
+
+            Ok(Some(
+                (0..2)
+                    .map(|i| {
+                        ObjectEntry::new(
+                            self.acc.clone(),
+                            &format!("{idx}-{i}"),
+                            ObjectMetadata::new(ObjectMode::FILE),
+                        )
+                    })
+                    .collect(),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_page_streamer_prefetch() -> anyhow::Result<()> {
+        let acc: Arc<dyn Accessor> = Arc::new(memory::Builder::default().build()?);
+
+        for depth in [0, 1, 2] {
+            let fetched = Arc::new(AtomicUsize::new(0));
+            let mut s = ObjectPageStreamer::new(MockPageStream {
+                acc: acc.clone(),
+                pages: 3,
+                failed_page: None,
+                fetched: fetched.clone(),
+            })
+            .with_prefetch(depth);
+
+            let first = s.next().await.expect("entry must exist")?;
+            assert_eq!(first.path(), "0-0");
+            // The current page plus `depth` pages ahead.
+            assert_eq!(fetched.load(Ordering::SeqCst), 1 + depth, "depth {depth}");
+
+            let rest: Vec<_> = s.map_ok(|v| v.path().to_string()).try_collect().await?;
+            assert_eq!(rest, vec!["0-1", "1-0", "1-1", "2-0", "2-1"]);
         }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_page_streamer_prefetch_failed() -> anyhow::Result<()> {
+        let acc: Arc<dyn Accessor> = Arc::new(memory::Builder::default().build()?);
+
+        for depth in [0, 1, 2] {
+            let mut s = ObjectPageStreamer::new(MockPageStream {
+                acc: acc.clone(),
+                pages: 3,
+                failed_page: Some(1),
+                fetched: Arc::new(AtomicUsize::new(0)),
+            })
+            .with_prefetch(depth);
+
+            // Entries of page 0 must be returned before the error of page 1.
+            let mut paths = Vec::new();
+            let err = loop {
+                match s.next().await.expect("stream must not end before error") {
+                    Ok(de) => paths.push(de.path().to_string()),
+                    Err(err) => break err,
+                }
+            };
+            assert_eq!(paths, vec!["0-0", "0-1"], "depth {depth}");
+            assert!(err.to_string().contains("page 1 failed"));
+            assert!(s.next().await.is_none());
+        }
+
+        Ok(())
     }
 }
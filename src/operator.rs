@@ -128,6 +128,7 @@ impl Operator {
     ) -> Result<Self> {
         let op = match scheme {
             Scheme::Azblob => services::azblob::Builder::from_iter(it).build()?.into(),
+            Scheme::DiskCache => services::disk_cache::Builder::from_iter(it).build()?.into(),
             Scheme::Fs => services::fs::Builder::from_iter(it).build()?.into(),
             #[cfg(feature = "services-ftp")]
             Scheme::Ftp => services::ftp::Builder::from_iter(it).build()?.into(),
@@ -236,7 +237,7 @@ impl Operator {
     }
 
     /// Get inner accessor.
-    fn inner(&self) -> Arc<dyn Accessor> {
+    pub(crate) fn inner(&self) -> Arc<dyn Accessor> {
         self.accessor.clone()
     }
 
@@ -36,6 +36,8 @@ pub use op_presign::PresignOperation;
 pub use op_presign::PresignedRequest;
 mod op_read;
 pub use op_read::OpRead;
+mod op_read_ranges;
+pub use op_read_ranges::OpReadRanges;
 mod op_stat;
 pub use op_stat::OpStat;
 mod op_write;
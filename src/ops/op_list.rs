@@ -14,11 +14,26 @@
 
 /// Args for `list` operation.
 #[derive(Debug, Clone, Default)]
-pub struct OpList {}
+pub struct OpList {
+    prefetch: usize,
+}
 
 impl OpList {
     /// Create a new `OpList`.
     pub fn new() -> Self {
-        Self {}
+        Self::default()
+    }
+
+    /// Get prefetch depth from option.
+    pub fn prefetch(&self) -> usize {
+        self.prefetch
+    }
+
+    /// Set the max pages to fetch ahead while listing, default to `0`.
+    ///
+    /// Only services that list objects by pages support this option.
+    pub fn with_prefetch(mut self, depth: usize) -> Self {
+        self.prefetch = depth;
+        self
     }
 }
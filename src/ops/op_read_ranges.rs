@@ -0,0 +1,120 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Range;
+
+/// Default max gap between ranges that will be merged into one request.
+const DEFAULT_MAX_GAP: u64 = 64 * 1024;
+/// Default max concurrent requests.
+const DEFAULT_CONCURRENCY: usize = 8;
+
+/// Args for `read_ranges` operation.
+#[derive(Debug, Clone)]
+pub struct OpReadRanges {
+    ranges: Vec<Range<u64>>,
+    max_gap: u64,
+    concurrency: usize,
+}
+
+impl OpReadRanges {
+    /// Create a new `OpReadRanges`.
+    pub fn new(ranges: impl Into<Vec<Range<u64>>>) -> Self {
+        Self {
+            ranges: ranges.into(),
+            max_gap: DEFAULT_MAX_GAP,
+            concurrency: DEFAULT_CONCURRENCY,
+        }
+    }
+
+    /// Get ranges from option.
+    pub fn ranges(&self) -> &[Range<u64>] {
+        &self.ranges
+    }
+
+    /// Get max gap from option.
+    pub fn max_gap(&self) -> u64 {
+        self.max_gap
+    }
+
+    /// Set the max gap in bytes between ranges that will be merged into
+    /// one request, default to 64 KiB.
+    pub fn with_max_gap(mut self, max_gap: u64) -> Self {
+        self.max_gap = max_gap;
+        self
+    }
+
+    /// Get concurrency from option.
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// Set the max concurrent requests, default to 8.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Merge ranges that are close together into fewer ranges.
+    ///
+    /// Returned ranges are sorted and not overlapped, empty ranges are ignored.
+    pub fn merged_ranges(&self) -> Vec<Range<u64>> {
+        let mut ranges: Vec<_> = self
+            .ranges
+            .iter()
+            .filter(|v| v.start < v.end)
+            .cloned()
+            .collect();
+        ranges.sort_by_key(|v| v.start);
+
+        let mut merged: Vec<Range<u64>> = Vec::with_capacity(ranges.len());
+        for r in ranges {
+            match merged.last_mut() {
+                Some(last) if r.start <= last.end.saturating_add(self.max_gap) => {
+                    last.end = last.end.max(r.end);
+                }
+                _ => merged.push(r),
+            }
+        }
+        merged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merged_ranges() {
+        let cases = vec![
+            ("empty", vec![], 10, vec![]),
+            ("single", vec![0..10], 0, vec![0..10]),
+            ("ignore empty range", vec![0..10, 5..5], 0, vec![0..10]),
+            (
+                "gap too large",
+                vec![0..10, 21..30],
+                10,
+                vec![0..10, 21..30],
+            ),
+            ("gap in limit", vec![0..10, 20..30], 10, vec![0..30]),
+            ("unsorted", vec![20..30, 0..10], 10, vec![0..30]),
+            ("overlapped", vec![0..10, 5..8, 8..20], 0, vec![0..20]),
+        ];
+
+        for (name, input, gap, expected) in cases {
+            let actual = OpReadRanges::new(input).with_max_gap(gap).merged_ranges();
+
+            assert_eq!(expected, actual, "{name}")
+        }
+    }
+}
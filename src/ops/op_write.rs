@@ -17,6 +17,7 @@
 pub struct OpWrite {
     size: u64,
     content_type: Option<String>,
+    content_encoding: Option<String>,
 }
 
 impl OpWrite {
@@ -27,14 +28,27 @@ impl OpWrite {
         Self {
             size,
             content_type: None,
+            content_encoding: None,
         }
     }
 
     /// Set the content type of option
     pub fn with_content_type(self, content_type: &str) -> Self {
         Self {
-            size: self.size(),
             content_type: Some(content_type.to_string()),
+            ..self
+        }
+    }
+
+    /// Set the content encoding of option
+    ///
+    /// Services will send it as `Content-Encoding`, which means the content
+    /// could be decoded by services or clients while reading. Don't set it
+    /// for compressed archives that should be read as is.
+    pub fn with_content_encoding(self, content_encoding: &str) -> Self {
+        Self {
+            content_encoding: Some(content_encoding.to_string()),
+            ..self
         }
     }
 }
@@ -48,4 +62,8 @@ impl OpWrite {
     pub fn content_type(&self) -> Option<&str> {
         self.content_type.as_deref()
     }
+    /// Get the content encoding from option
+    pub fn content_encoding(&self) -> Option<&str> {
+        self.content_encoding.as_deref()
+    }
 }
@@ -29,6 +29,8 @@ use std::str::FromStr;
 pub enum Scheme {
     /// [azblob][crate::services::azblob]: Azure Storage Blob services.
     Azblob,
+    /// [disk_cache][crate::services::disk_cache]: Local disk cache with LRU eviction.
+    DiskCache,
     /// [fs][crate::services::fs]: POSIX alike file system.
     Fs,
     /// [gcs][crate::services::gcs]: Google Cloud Storage backend.
@@ -86,6 +88,7 @@ impl Display for Scheme {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Scheme::Azblob => write!(f, "azblob"),
+            Scheme::DiskCache => write!(f, "disk_cache"),
             Scheme::Fs => write!(f, "fs"),
             #[cfg(feature = "services-hdfs")]
             Scheme::Hdfs => write!(f, "hdfs"),
@@ -116,6 +119,7 @@ impl FromStr for Scheme {
         let s = s.to_lowercase();
         match s.as_str() {
             "azblob" => Ok(Scheme::Azblob),
+            "disk_cache" => Ok(Scheme::DiskCache),
             "fs" => Ok(Scheme::Fs),
             "gcs" => Ok(Scheme::Gcs),
             #[cfg(feature = "services-hdfs")]
@@ -143,6 +147,7 @@ impl From<Scheme> for &'static str {
     fn from(v: Scheme) -> Self {
         match v {
             Scheme::Azblob => "azblob",
+            Scheme::DiskCache => "disk_cache",
             Scheme::Fs => "fs",
             Scheme::Gcs => "gcs",
             #[cfg(feature = "services-hdfs")]
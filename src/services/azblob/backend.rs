@@ -23,6 +23,7 @@ use std::sync::Arc;
 use anyhow::anyhow;
 use async_trait::async_trait;
 use http::header::HeaderName;
+use http::header::CONTENT_ENCODING;
 use http::header::CONTENT_LENGTH;
 use http::header::CONTENT_TYPE;
 use http::Request;
@@ -51,6 +52,8 @@ use crate::http_util::percent_encode_path;
 use crate::http_util::AsyncBody;
 use crate::http_util::HttpClient;
 use crate::http_util::IncomingAsyncBody;
+use crate::io_util::into_reader;
+use crate::io_util::into_stream;
 use crate::object::ObjectMetadata;
 use crate::object::ObjectPageStreamer;
 use crate::ops::BytesRange;
@@ -65,6 +68,7 @@ use crate::path::build_abs_path;
 use crate::path::normalize_root;
 use crate::Accessor;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::ObjectMode;
 use crate::ObjectStreamer;
 use crate::Scheme;
@@ -79,6 +83,8 @@ pub struct Builder {
     endpoint: Option<String>,
     account_name: Option<String>,
     account_key: Option<String>,
+
+    http_client: Option<HttpClient>,
 }
 
 impl Debug for Builder {
@@ -176,6 +182,15 @@ impl Builder {
         self
     }
 
+    /// Specify the http client that used by this service.
+    ///
+    /// A new client will be created if not set, see [`HttpClient`] for
+    /// sharing clients across services.
+    pub fn http_client(&mut self, client: HttpClient) -> &mut Self {
+        self.http_client = Some(client);
+        self
+    }
+
     /// Consume builder to build an azblob backend.
     pub fn build(&mut self) -> Result<impl Accessor> {
         debug!("backend build started: {:?}", &self);
@@ -207,7 +222,7 @@ impl Builder {
             ("endpoint".to_string(), endpoint.to_string()),
         ]);
 
-        let client = HttpClient::new();
+        let client = self.http_client.take().unwrap_or_default();
 
         let mut signer_builder = AzureStorageSigner::builder();
         if let (Some(name), Some(key)) = (&self.account_name, &self.account_key) {
@@ -256,7 +271,7 @@ impl Accessor for Backend {
     }
 
     async fn create(&self, path: &str, _: OpCreate) -> Result<()> {
-        let mut req = self.azblob_put_blob_request(path, Some(0), None, AsyncBody::Empty)?;
+        let mut req = self.azblob_put_blob_request(path, Some(0), None, None, AsyncBody::Empty)?;
 
         self.signer
             .sign(&mut req)
@@ -287,6 +302,11 @@ impl Accessor for Backend {
     }
 
     async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        let s = self.read_stream(path, args).await?;
+        Ok(Box::new(into_reader(s)))
+    }
+
+    async fn read_stream(&self, path: &str, args: OpRead) -> Result<BytesStreamer> {
         let resp = self
             .azblob_get_blob(path, args.offset(), args.size())
             .await?;
@@ -294,7 +314,7 @@ impl Accessor for Backend {
         let status = resp.status();
 
         match status {
-            StatusCode::OK | StatusCode::PARTIAL_CONTENT => Ok(resp.into_body().reader()),
+            StatusCode::OK | StatusCode::PARTIAL_CONTENT => Ok(resp.into_body().stream()),
             _ => {
                 let er = parse_error_response(resp).await?;
                 let err = parse_error(Operation::Read, path, er);
@@ -304,11 +324,17 @@ impl Accessor for Backend {
     }
 
     async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<u64> {
+        self.write_stream(path, args, Box::new(into_stream(r, 16 * 1024)))
+            .await
+    }
+
+    async fn write_stream(&self, path: &str, args: OpWrite, s: BytesStreamer) -> Result<u64> {
         let mut req = self.azblob_put_blob_request(
             path,
             Some(args.size()),
             args.content_type(),
-            AsyncBody::Reader(r),
+            args.content_encoding(),
+            AsyncBody::Stream(s),
         )?;
 
         self.signer
@@ -416,12 +442,15 @@ impl Accessor for Backend {
         }
     }
 
-    async fn list(&self, path: &str, _: OpList) -> Result<ObjectStreamer> {
-        Ok(Box::new(ObjectPageStreamer::new(DirStream::new(
-            Arc::new(self.clone()),
-            self.root.clone(),
-            path.to_string(),
-        ))))
+    async fn list(&self, path: &str, args: OpList) -> Result<ObjectStreamer> {
+        Ok(Box::new(
+            ObjectPageStreamer::new(DirStream::new(
+                Arc::new(self.clone()),
+                self.root.clone(),
+                path.to_string(),
+            ))
+            .with_prefetch(args.prefetch()),
+        ))
     }
 }
 
@@ -469,6 +498,7 @@ impl Backend {
         path: &str,
         size: Option<u64>,
         content_type: Option<&str>,
+        content_encoding: Option<&str>,
         body: AsyncBody,
     ) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
@@ -490,6 +520,10 @@ impl Backend {
             req = req.header(CONTENT_TYPE, ty)
         }
 
+        if let Some(encoding) = content_encoding {
+            req = req.header(CONTENT_ENCODING, encoding)
+        }
+
         req = req.header(HeaderName::from_static(X_MS_BLOB_TYPE), "BlockBlob");
 
         // Set body
@@ -0,0 +1,768 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Result;
+use std::io::SeekFrom;
+use std::path::Path;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use anyhow::anyhow;
+use async_compat::Compat;
+use async_trait::async_trait;
+use futures::stream;
+use futures::AsyncReadExt;
+use futures::AsyncSeekExt;
+use futures::AsyncWriteExt;
+use log::debug;
+use parking_lot::Mutex;
+use tokio::fs;
+
+use crate::accessor::AccessorCapability;
+use crate::accessor::AccessorMetadata;
+use crate::error::new_other_backend_error;
+use crate::error::new_other_object_error;
+use crate::error::ObjectError;
+use crate::ops::OpCreate;
+use crate::ops::OpDelete;
+use crate::ops::OpList;
+use crate::ops::OpRead;
+use crate::ops::OpStat;
+use crate::ops::OpWrite;
+use crate::ops::Operation;
+use crate::path::normalize_root;
+use crate::Accessor;
+use crate::BlockingBytesReader;
+use crate::BytesReader;
+use crate::ObjectEntry;
+use crate::ObjectIterator;
+use crate::ObjectMetadata;
+use crate::ObjectMode;
+use crate::ObjectStreamer;
+use crate::Scheme;
+
+/// Dir to store cached objects.
+const DATA_DIR: &str = "data/";
+/// Dir to store objects that are being written.
+const TMP_DIR: &str = "tmp/";
+/// Temp files not modified for this duration are left by crashed writers.
+const TMP_EXPIRE: Duration = Duration::from_secs(3600);
+
+/// Sequence of temp files shared by all instances in the process.
+static TMP_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Builder for disk cache backend.
+#[derive(Default, Debug)]
+pub struct Builder {
+    root: Option<String>,
+    capacity: Option<u64>,
+}
+
+impl Builder {
+    pub(crate) fn from_iter(it: impl Iterator<Item = (String, String)>) -> Self {
+        let mut builder = Builder::default();
+
+        for (k, v) in it {
+            let v = v.as_str();
+            match k.as_ref() {
+                "root" => builder.root(v),
+                "capacity" => match v.parse::<u64>() {
+                    Ok(v) => builder.capacity(v),
+                    _ => continue,
+                },
+                _ => continue,
+            };
+        }
+
+        builder
+    }
+
+    /// Set root for backend.
+    pub fn root(&mut self, root: &str) -> &mut Self {
+        self.root = if root.is_empty() {
+            None
+        } else {
+            Some(root.to_string())
+        };
+
+        self
+    }
+
+    /// Set the max bytes of cached objects.
+    pub fn capacity(&mut self, capacity: u64) -> &mut Self {
+        if capacity != 0 {
+            self.capacity = Some(capacity);
+        }
+
+        self
+    }
+
+    /// Consume current builder to build a disk cache backend.
+    ///
+    /// Index of existing objects in root will be rebuilt.
+    pub fn build(&mut self) -> Result<impl Accessor> {
+        debug!("backend build started: {:?}", &self);
+
+        let root = match self.root.take() {
+            Some(v) => normalize_root(&v),
+            None => {
+                return Err(new_other_backend_error(
+                    HashMap::from([("root".to_string(), "".to_string())]),
+                    anyhow!("root is empty"),
+                ))
+            }
+        };
+        debug!("backend use root {}", root);
+        let capacity = match self.capacity {
+            Some(v) => v,
+            None => {
+                return Err(new_other_backend_error(
+                    HashMap::from([("capacity".to_string(), "".to_string())]),
+                    anyhow!("capacity is empty"),
+                ))
+            }
+        };
+        debug!("backend use capacity {}", capacity);
+
+        let context = || HashMap::from([("root".to_string(), root.clone())]);
+        let tmp = format!("{root}{TMP_DIR}");
+        std::fs::create_dir_all(&tmp).map_err(|err| new_other_backend_error(context(), err))?;
+        // Objects that are not committed before crash are useless, but temp
+        // files that are still being written by other instances must be kept.
+        clean_tmp_dir(Path::new(&tmp), TMP_EXPIRE)
+            .map_err(|err| new_other_backend_error(context(), err))?;
+        let data = format!("{root}{DATA_DIR}");
+        std::fs::create_dir_all(&data).map_err(|err| new_other_backend_error(context(), err))?;
+
+        let mut files = Vec::new();
+        scan_dir(Path::new(&data), "", &mut files)
+            .map_err(|err| new_other_backend_error(context(), err))?;
+        files.sort_by_key(|(_, _, t)| *t);
+
+        let backend = Backend {
+            root,
+            capacity,
+            index: Arc::new(Mutex::new(Index::default())),
+        };
+        let evicted = {
+            let mut index = backend.index.lock();
+            for (path, size, _) in files {
+                index.insert(path, size);
+            }
+            backend.evict(&mut index)
+        };
+        backend.blocking_remove_evicted(evicted);
+
+        debug!("backend build finished: {:?}", &self);
+        Ok(backend)
+    }
+}
+
+/// Remove temp files that have not been modified for `expire`.
+fn clean_tmp_dir(dir: &Path, expire: Duration) -> Result<()> {
+    let now = SystemTime::now();
+    for de in std::fs::read_dir(dir)? {
+        let de = de?;
+        let modified = de.metadata()?.modified()?;
+        if now.duration_since(modified).unwrap_or_default() < expire {
+            continue;
+        }
+        debug!("disk cache remove expired temp file {:?}", de.path());
+        if let Err(err) = std::fs::remove_file(de.path()) {
+            if err.kind() != ErrorKind::NotFound {
+                return Err(err);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Collect `(path, size, access time)` of all files in dir recursively.
+fn scan_dir(dir: &Path, prefix: &str, files: &mut Vec<(String, u64, SystemTime)>) -> Result<()> {
+    for de in std::fs::read_dir(dir)? {
+        let de = de?;
+        let name = de.file_name().to_string_lossy().to_string();
+        let meta = de.metadata()?;
+        if meta.is_dir() {
+            scan_dir(&de.path(), &format!("{prefix}{name}/"), files)?;
+        } else if meta.is_file() {
+            let t = meta
+                .accessed()
+                .or_else(|_| meta.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            files.push((format!("{prefix}{name}"), meta.len(), t));
+        }
+    }
+    Ok(())
+}
+
+/// Index of cached objects in LRU order.
+#[derive(Debug, Default)]
+struct Index {
+    /// Path => (size, tick of last access).
+    entries: HashMap<String, (u64, u64)>,
+    /// Tick of last access => path.
+    lru: BTreeMap<u64, String>,
+    /// Total bytes of all cached objects.
+    total: u64,
+    tick: u64,
+}
+
+impl Index {
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    /// Get size of path, and mark it as recently used.
+    fn touch(&mut self, path: &str) -> Option<u64> {
+        let tick = self.next_tick();
+        let (size, last) = self.entries.get_mut(path)?;
+        let old = std::mem::replace(last, tick);
+        let size = *size;
+
+        self.lru.remove(&old);
+        self.lru.insert(tick, path.to_string());
+        Some(size)
+    }
+
+    fn insert(&mut self, path: String, size: u64) {
+        self.remove(&path);
+
+        let tick = self.next_tick();
+        self.total += size;
+        self.lru.insert(tick, path.clone());
+        self.entries.insert(path, (size, tick));
+    }
+
+    fn remove(&mut self, path: &str) -> bool {
+        match self.entries.remove(path) {
+            Some((size, tick)) => {
+                self.total -= size;
+                self.lru.remove(&tick);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove the least recently used path.
+    fn pop_lru(&mut self) -> Option<String> {
+        let tick = *self.lru.keys().next()?;
+        let path = self.lru.remove(&tick)?;
+        if let Some((size, _)) = self.entries.remove(&path) {
+            self.total -= size;
+        }
+        Some(path)
+    }
+}
+
+/// Backend is used to serve `Accessor` support for local disk cache.
+#[derive(Debug, Clone)]
+pub struct Backend {
+    root: String,
+    capacity: u64,
+    index: Arc<Mutex<Index>>,
+}
+
+impl Backend {
+    fn data_path(&self, path: &str) -> String {
+        format!("{}{DATA_DIR}{}", self.root, path)
+    }
+
+    /// Temp files are named with pid, creation time and a sequence, so that
+    /// instances sharing the same root will never write into the same file.
+    fn tmp_path(&self) -> String {
+        let nanos = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        format!(
+            "{}{TMP_DIR}{}-{nanos}-{}",
+            self.root,
+            std::process::id(),
+            TMP_SEQ.fetch_add(1, Ordering::Relaxed)
+        )
+    }
+
+    /// Pop the least recently used objects until total bytes are under
+    /// capacity, returns the evicted paths.
+    ///
+    /// Files of evicted paths should be removed after the index lock is
+    /// released.
+    fn evict(&self, index: &mut Index) -> Vec<String> {
+        let mut evicted = Vec::new();
+        while index.total > self.capacity {
+            match index.pop_lru() {
+                Some(path) => evicted.push(path),
+                None => break,
+            }
+        }
+        evicted
+    }
+
+    /// Insert path into index, returns the evicted paths.
+    fn insert(&self, path: &str, size: u64) -> Vec<String> {
+        let mut index = self.index.lock();
+        index.insert(path.to_string(), size);
+        self.evict(&mut index)
+    }
+
+    async fn remove_evicted(&self, evicted: Vec<String>) {
+        for path in evicted {
+            debug!("disk cache evict path {}", path);
+            // The file will be leaked if failed to remove, we can only
+            // reclaim it while rebuilding index.
+            if let Err(err) = fs::remove_file(self.data_path(&path)).await {
+                if err.kind() != ErrorKind::NotFound {
+                    debug!("disk cache evict path {} failed: {:?}", path, err);
+                }
+            }
+        }
+    }
+
+    fn blocking_remove_evicted(&self, evicted: Vec<String>) {
+        for path in evicted {
+            debug!("disk cache evict path {}", path);
+            // The file will be leaked if failed to remove, we can only
+            // reclaim it while rebuilding index.
+            if let Err(err) = std::fs::remove_file(self.data_path(&path)) {
+                if err.kind() != ErrorKind::NotFound {
+                    debug!("disk cache evict path {} failed: {:?}", path, err);
+                }
+            }
+        }
+    }
+
+    fn check_size(&self, op: Operation, path: &str, size: u64) -> Result<()> {
+        if size > self.capacity {
+            return Err(new_other_object_error(
+                op,
+                path,
+                anyhow!("size {size} exceeds cache capacity {}", self.capacity),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Move the written temp file into place.
+    ///
+    /// File operations are done without holding the index lock. If the
+    /// file is removed by a racing eviction, reads will see `NotFound`
+    /// and drop it from index.
+    async fn commit(&self, op: Operation, path: &str, tmp: &str, size: u64) -> Result<()> {
+        let p = self.data_path(path);
+        if let Some(parent) = Path::new(&p).parent() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(|err| parse_io_error(err, op, path))?;
+        }
+        fs::rename(tmp, &p)
+            .await
+            .map_err(|err| parse_io_error(err, op, path))?;
+
+        let evicted = self.insert(path, size);
+        self.remove_evicted(evicted).await;
+        Ok(())
+    }
+
+    fn blocking_commit(&self, op: Operation, path: &str, tmp: &str, size: u64) -> Result<()> {
+        let p = self.data_path(path);
+        if let Some(parent) = Path::new(&p).parent() {
+            std::fs::create_dir_all(parent).map_err(|err| parse_io_error(err, op, path))?;
+        }
+        std::fs::rename(tmp, &p).map_err(|err| parse_io_error(err, op, path))?;
+
+        let evicted = self.insert(path, size);
+        self.blocking_remove_evicted(evicted);
+        Ok(())
+    }
+
+    /// Get size of path, and mark it as recently used.
+    fn touch(&self, op: Operation, path: &str) -> Result<u64> {
+        self.index
+            .lock()
+            .touch(path)
+            .ok_or_else(|| new_not_found_error(op, path))
+    }
+
+    fn stat_object(&self, op: Operation, path: &str) -> Result<ObjectMetadata> {
+        if path.ends_with('/') {
+            return Ok(ObjectMetadata::new(ObjectMode::DIR));
+        }
+
+        match self.index.lock().entries.get(path) {
+            Some((size, _)) => Ok(ObjectMetadata::new(ObjectMode::FILE).with_content_length(*size)),
+            None => Err(new_not_found_error(op, path)),
+        }
+    }
+
+    /// List direct children of path from index.
+    fn list_entries(&self, path: &str) -> Vec<Result<ObjectEntry>> {
+        let prefix = if path == "/" { "" } else { path };
+        let acc: Arc<dyn Accessor> = Arc::new(self.clone());
+
+        let mut dirs = BTreeSet::new();
+        let mut entries = Vec::new();
+        for (p, (size, _)) in self.index.lock().entries.iter() {
+            let rest = match p.strip_prefix(prefix) {
+                Some(v) => v,
+                None => continue,
+            };
+            match rest.find('/') {
+                Some(idx) => {
+                    dirs.insert(format!("{prefix}{}", &rest[..=idx]));
+                }
+                None => {
+                    let meta = ObjectMetadata::new(ObjectMode::FILE).with_content_length(*size);
+                    entries.push(Ok(ObjectEntry::new(acc.clone(), p, meta).with_complete()));
+                }
+            }
+        }
+        for dir in dirs {
+            let meta = ObjectMetadata::new(ObjectMode::DIR);
+            entries.push(Ok(ObjectEntry::new(acc.clone(), &dir, meta).with_complete()));
+        }
+        entries
+    }
+
+    /// Build the `(offset, size)` to read.
+    fn read_range(args: &OpRead, total: u64) -> (u64, u64) {
+        let offset = match (args.offset(), args.size()) {
+            (Some(offset), _) => offset.min(total),
+            (None, Some(size)) => total - size.min(total),
+            (None, None) => 0,
+        };
+        let size = args.size().unwrap_or(total).min(total - offset);
+        (offset, size)
+    }
+}
+
+fn parse_io_error(err: Error, op: Operation, path: &str) -> Error {
+    Error::new(err.kind(), ObjectError::new(op, path, err))
+}
+
+fn new_not_found_error(op: Operation, path: &str) -> Error {
+    Error::new(
+        ErrorKind::NotFound,
+        ObjectError::new(op, path, anyhow!("object not found in disk cache")),
+    )
+}
+
+#[async_trait]
+impl Accessor for Backend {
+    fn metadata(&self) -> AccessorMetadata {
+        let mut am = AccessorMetadata::default();
+        am.set_scheme(Scheme::DiskCache)
+            .set_root(&self.root)
+            .set_capabilities(
+                AccessorCapability::Read
+                    | AccessorCapability::Write
+                    | AccessorCapability::List
+                    | AccessorCapability::Blocking,
+            );
+
+        am
+    }
+
+    async fn create(&self, path: &str, args: OpCreate) -> Result<()> {
+        if args.mode() == ObjectMode::DIR {
+            return Ok(());
+        }
+
+        let tmp = self.tmp_path();
+        fs::File::create(&tmp)
+            .await
+            .map_err(|err| parse_io_error(err, Operation::Create, path))?;
+        self.commit(Operation::Create, path, &tmp, 0).await
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        let total = self.touch(Operation::Read, path)?;
+        let (offset, size) = Self::read_range(&args, total);
+
+        let f = match fs::File::open(self.data_path(path)).await {
+            Ok(f) => f,
+            Err(err) => {
+                if err.kind() == ErrorKind::NotFound {
+                    self.index.lock().remove(path);
+                }
+                return Err(parse_io_error(err, Operation::Read, path));
+            }
+        };
+        let mut f = Compat::new(f);
+        f.seek(SeekFrom::Start(offset))
+            .await
+            .map_err(|err| parse_io_error(err, Operation::Read, path))?;
+
+        Ok(Box::new(f.take(size)))
+    }
+
+    async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<u64> {
+        self.check_size(Operation::Write, path, args.size())?;
+
+        let tmp = self.tmp_path();
+        let res = async {
+            let f = fs::File::create(&tmp).await?;
+            let mut f = Compat::new(f);
+            // Read one more byte than capacity to detect oversize input.
+            let size = futures::io::copy(r.take(self.capacity + 1), &mut f).await?;
+            f.flush().await?;
+            f.get_ref().sync_all().await?;
+            Ok::<_, Error>(size)
+        }
+        .await;
+
+        let size = match res {
+            Ok(size) => size,
+            Err(err) => {
+                let _ = fs::remove_file(&tmp).await;
+                return Err(parse_io_error(err, Operation::Write, path));
+            }
+        };
+        // `args.size()` could be smaller than the actual input.
+        if let Err(err) = self.check_size(Operation::Write, path, size) {
+            let _ = fs::remove_file(&tmp).await;
+            return Err(err);
+        }
+        self.commit(Operation::Write, path, &tmp, size).await?;
+        Ok(size)
+    }
+
+    async fn stat(&self, path: &str, _: OpStat) -> Result<ObjectMetadata> {
+        self.stat_object(Operation::Stat, path)
+    }
+
+    async fn delete(&self, path: &str, _: OpDelete) -> Result<()> {
+        if !self.index.lock().remove(path) {
+            return Ok(());
+        }
+
+        match fs::remove_file(self.data_path(path)).await {
+            Err(err) if err.kind() != ErrorKind::NotFound => {
+                Err(parse_io_error(err, Operation::Delete, path))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    async fn list(&self, path: &str, _: OpList) -> Result<ObjectStreamer> {
+        Ok(Box::new(stream::iter(self.list_entries(path))))
+    }
+
+    fn blocking_create(&self, path: &str, args: OpCreate) -> Result<()> {
+        if args.mode() == ObjectMode::DIR {
+            return Ok(());
+        }
+
+        let tmp = self.tmp_path();
+        std::fs::File::create(&tmp)
+            .map_err(|err| parse_io_error(err, Operation::BlockingCreate, path))?;
+        self.blocking_commit(Operation::BlockingCreate, path, &tmp, 0)
+    }
+
+    fn blocking_read(&self, path: &str, args: OpRead) -> Result<BlockingBytesReader> {
+        use std::io::Seek;
+
+        let total = self.touch(Operation::BlockingRead, path)?;
+        let (offset, size) = Self::read_range(&args, total);
+
+        let mut f = match std::fs::File::open(self.data_path(path)) {
+            Ok(f) => f,
+            Err(err) => {
+                if err.kind() == ErrorKind::NotFound {
+                    self.index.lock().remove(path);
+                }
+                return Err(parse_io_error(err, Operation::BlockingRead, path));
+            }
+        };
+        f.seek(SeekFrom::Start(offset))
+            .map_err(|err| parse_io_error(err, Operation::BlockingRead, path))?;
+
+        Ok(Box::new(f.take(size)))
+    }
+
+    fn blocking_write(&self, path: &str, args: OpWrite, r: BlockingBytesReader) -> Result<u64> {
+        self.check_size(Operation::BlockingWrite, path, args.size())?;
+
+        let tmp = self.tmp_path();
+        let res = (|| {
+            let mut f = std::fs::File::create(&tmp)?;
+            // Read one more byte than capacity to detect oversize input.
+            let size = std::io::copy(&mut r.take(self.capacity + 1), &mut f)?;
+            f.sync_all()?;
+            Ok::<_, Error>(size)
+        })();
+
+        let size = match res {
+            Ok(size) => size,
+            Err(err) => {
+                let _ = std::fs::remove_file(&tmp);
+                return Err(parse_io_error(err, Operation::BlockingWrite, path));
+            }
+        };
+        // `args.size()` could be smaller than the actual input.
+        if let Err(err) = self.check_size(Operation::BlockingWrite, path, size) {
+            let _ = std::fs::remove_file(&tmp);
+            return Err(err);
+        }
+        self.blocking_commit(Operation::BlockingWrite, path, &tmp, size)?;
+        Ok(size)
+    }
+
+    fn blocking_stat(&self, path: &str, _: OpStat) -> Result<ObjectMetadata> {
+        self.stat_object(Operation::BlockingStat, path)
+    }
+
+    fn blocking_delete(&self, path: &str, _: OpDelete) -> Result<()> {
+        if !self.index.lock().remove(path) {
+            return Ok(());
+        }
+
+        match std::fs::remove_file(self.data_path(path)) {
+            Err(err) if err.kind() != ErrorKind::NotFound => {
+                Err(parse_io_error(err, Operation::BlockingDelete, path))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn blocking_list(&self, path: &str, _: OpList) -> Result<ObjectIterator> {
+        Ok(Box::new(self.list_entries(path).into_iter()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::Operator;
+
+    fn new_root() -> String {
+        let root = std::env::temp_dir().join(format!(
+            "opendal-disk-cache-{}-{}",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .expect("time must be valid")
+                .as_nanos()
+        ));
+        root.to_string_lossy().to_string()
+    }
+
+    fn new_backend(root: &str, capacity: u64) -> Result<Operator> {
+        let mut builder = Builder::default();
+        builder.root(root).capacity(capacity);
+        Ok(Operator::new(builder.build()?))
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_evict() -> anyhow::Result<()> {
+        let root = new_root();
+        let op = new_backend(&root, 10)?;
+
+        op.object("a").write("aaaa").await?;
+        op.object("dir/b").write("bbbb").await?;
+        // Read a to make b the least recently used.
+        assert_eq!(op.object("a").read().await?, b"aaaa");
+        op.object("c").write("cccc").await?;
+
+        assert_eq!(op.object("a").range_read(1..3).await?, b"aa");
+        assert!(!op.object("dir/b").is_exist().await?);
+        assert_eq!(op.object("c").read().await?, b"cccc");
+
+        // Objects larger than capacity can't be cached.
+        assert!(op.object("d").write(vec![0; 11]).await.is_err());
+        // Even if the size in args is smaller than actual.
+        let r = Box::new(futures::io::Cursor::new(vec![0; 11]));
+        assert!(op.object("d").write_from(4, r).await.is_err());
+        assert!(!op.object("d").is_exist().await?);
+        assert_eq!(std::fs::read_dir(format!("{root}/{TMP_DIR}"))?.count(), 0);
+
+        std::fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_rebuild() -> anyhow::Result<()> {
+        let root = new_root();
+        let op = new_backend(&root, 10)?;
+        op.object("a").write("aaaa").await?;
+        op.object("dir/b").write("bbbb").await?;
+        // Temp file that is being written by another instance.
+        let writing = format!("{root}/{TMP_DIR}writing");
+        std::fs::write(&writing, "xxxx")?;
+        drop(op);
+
+        let op = new_backend(&root, 10)?;
+        assert_eq!(op.object("a").read().await?, b"aaaa");
+        assert_eq!(op.object("dir/b").metadata().await?.content_length(), 4);
+        assert!(Path::new(&writing).exists());
+
+        // Expired temp files are leftovers of crashed writes.
+        clean_tmp_dir(Path::new(&format!("{root}/{TMP_DIR}")), Duration::ZERO)?;
+        assert!(!Path::new(&writing).exists());
+
+        // Capacity could be shrunk while rebuilding.
+        drop(op);
+        let op = new_backend(&root, 4)?;
+        let a = op.object("a").is_exist().await?;
+        let b = op.object("dir/b").is_exist().await?;
+        assert!(a ^ b, "only one object should be kept");
+
+        std::fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_disk_cache_list() -> anyhow::Result<()> {
+        let root = new_root();
+        let op = new_backend(&root, 100)?;
+        op.object("a").write("aaaa").await?;
+        op.object("dir/b").write("bbbb").await?;
+        op.object("dir/sub/c").write("cccc").await?;
+
+        async fn list(op: &Operator, path: &str) -> anyhow::Result<Vec<String>> {
+            let mut paths: Vec<_> = op
+                .object(path)
+                .list()
+                .await?
+                .map_ok(|de| de.path().to_string())
+                .try_collect()
+                .await?;
+            paths.sort();
+            Ok(paths)
+        }
+
+        assert_eq!(list(&op, "/").await?, vec!["a", "dir/"]);
+        assert_eq!(list(&op, "dir/").await?, vec!["dir/b", "dir/sub/"]);
+        assert!(list(&op, "not_exist/").await?.is_empty());
+
+        op.object("dir/b").delete().await?;
+        assert_eq!(list(&op, "dir/").await?, vec!["dir/sub/"]);
+
+        std::fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+}
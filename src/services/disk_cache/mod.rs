@@ -0,0 +1,72 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Local disk cache support with LRU eviction.
+//!
+//! This service is designed to be used as the cache of
+//! [`ContentCacheLayer`][crate::layers::ContentCacheLayer] and
+//! [`MetadataCacheLayer`][crate::layers::MetadataCacheLayer].
+//!
+//! - Total bytes of cached objects are kept under `capacity` by evicting the
+//!   least recently read objects.
+//! - Objects are written into a temp file and renamed into place, so that a
+//!   crash never leaves a half-written object.
+//! - Index will be rebuilt by scanning `root` on startup, access order will
+//!   be restored from the access time (or modified time if not available)
+//!   of files.
+//!
+//! - Temp files are named by pid, time and sequence, only temp files that have not been
+//!   modified for an hour will be removed on startup.
+//!
+//! `list` is served from the index. Index is not shared between instances,
+//! so the `root` should be owned by only one cache instance.
+//!
+//! # Configuration
+//!
+//! - `root`: Set the work dir for cache.
+//! - `capacity`: Set the max bytes of cached objects.
+//!
+//! Refer to [`Builder`]'s public API docs for more information.
+//!
+//! # Environment
+//!
+//! - `OPENDAL_DISK_CACHE_ROOT`
+//! - `OPENDAL_DISK_CACHE_CAPACITY`
+//!
+//! # Example
+//!
+//! ## Via Builder
+//!
+//! ```no_run
+//! use anyhow::Result;
+//! use opendal::layers::ContentCacheLayer;
+//! use opendal::services::disk_cache;
+//! use opendal::Operator;
+//! use opendal::Scheme;
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<()> {
+//!     let mut builder = disk_cache::Builder::default();
+//!     builder.root("/var/cache/opendal");
+//!     builder.capacity(16 * 1024 * 1024 * 1024);
+//!
+//!     let _ = Operator::from_env(Scheme::S3)?.layer(ContentCacheLayer::new(builder.build()?));
+//!
+//!     Ok(())
+//! }
+//! ```
+
+mod backend;
+pub use backend::Backend;
+pub use backend::Builder;
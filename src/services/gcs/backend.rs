@@ -47,6 +47,8 @@ use crate::http_util::parse_error_response;
 use crate::http_util::AsyncBody;
 use crate::http_util::HttpClient;
 use crate::http_util::IncomingAsyncBody;
+use crate::io_util::into_reader;
+use crate::io_util::into_stream;
 use crate::object::ObjectPageStreamer;
 use crate::ops::BytesRange;
 use crate::ops::OpCreate;
@@ -61,6 +63,7 @@ use crate::path::normalize_root;
 use crate::Accessor;
 use crate::AccessorMetadata;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::ObjectMetadata;
 use crate::ObjectMode;
 use crate::ObjectStreamer;
@@ -84,6 +87,8 @@ pub struct Builder {
 
     /// credential string for GCS service
     credential: Option<String>,
+
+    http_client: Option<HttpClient>,
 }
 
 impl Builder {
@@ -133,6 +138,15 @@ impl Builder {
         self
     }
 
+    /// Specify the http client that used by this service.
+    ///
+    /// A new client will be created if not set, see [`HttpClient`] for
+    /// sharing clients across services.
+    pub fn http_client(&mut self, client: HttpClient) -> &mut Self {
+        self.http_client = Some(client);
+        self
+    }
+
     /// Establish connection to GCS and finish making GCS backend
     pub fn build(&mut self) -> Result<impl Accessor> {
         debug!("backend build started: {:?}", self);
@@ -155,7 +169,7 @@ impl Builder {
         // TODO: server side encryption
 
         // build http client
-        let client = HttpClient::new();
+        let client = self.http_client.take().unwrap_or_default();
         let endpoint = self
             .endpoint
             .clone()
@@ -240,7 +254,8 @@ impl Accessor for Backend {
     }
 
     async fn create(&self, path: &str, _: OpCreate) -> Result<()> {
-        let mut req = self.gcs_insert_object_request(path, Some(0), None, AsyncBody::Empty)?;
+        let mut req =
+            self.gcs_insert_object_request(path, Some(0), None, None, AsyncBody::Empty)?;
 
         self.signer
             .sign(&mut req)
@@ -266,12 +281,17 @@ impl Accessor for Backend {
     }
 
     async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        let s = self.read_stream(path, args).await?;
+        Ok(Box::new(into_reader(s)))
+    }
+
+    async fn read_stream(&self, path: &str, args: OpRead) -> Result<BytesStreamer> {
         let resp = self
             .gcs_get_object(path, args.offset(), args.size())
             .await?;
 
         if resp.status().is_success() {
-            Ok(resp.into_body().reader())
+            Ok(resp.into_body().stream())
         } else {
             let er = parse_error_response(resp).await?;
             let e = parse_error(Operation::Read, path, er);
@@ -280,11 +300,17 @@ impl Accessor for Backend {
     }
 
     async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<u64> {
+        self.write_stream(path, args, Box::new(into_stream(r, 16 * 1024)))
+            .await
+    }
+
+    async fn write_stream(&self, path: &str, args: OpWrite, s: BytesStreamer) -> Result<u64> {
         let mut req = self.gcs_insert_object_request(
             path,
             Some(args.size()),
             args.content_type(),
-            AsyncBody::Reader(r),
+            args.content_encoding(),
+            AsyncBody::Stream(s),
         )?;
 
         self.signer
@@ -381,12 +407,11 @@ impl Accessor for Backend {
         }
     }
 
-    async fn list(&self, path: &str, _: OpList) -> Result<ObjectStreamer> {
-        Ok(Box::new(ObjectPageStreamer::new(DirStream::new(
-            Arc::new(self.clone()),
-            &self.root,
-            path,
-        ))))
+    async fn list(&self, path: &str, args: OpList) -> Result<ObjectStreamer> {
+        Ok(Box::new(
+            ObjectPageStreamer::new(DirStream::new(Arc::new(self.clone()), &self.root, path))
+                .with_prefetch(args.prefetch()),
+        ))
     }
 
     // inherits the default implementation of Accessor.
@@ -447,17 +472,24 @@ impl Backend {
         path: &str,
         size: Option<u64>,
         content_type: Option<&str>,
+        content_encoding: Option<&str>,
         body: AsyncBody,
     ) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
 
-        let url = format!(
+        let mut url = format!(
             "{}/upload/storage/v1/b/{}/o?uploadType=media&name={}",
             self.endpoint,
             self.bucket,
             percent_encode_path(&p)
         );
 
+        // GCS media upload only accepts content encoding via query.
+        if let Some(encoding) = content_encoding {
+            write!(url, "&contentEncoding={}", percent_encode_path(encoding))
+                .expect("write into string must succeed");
+        }
+
         let mut req = Request::post(&url);
 
         if let Some(size) = size {
@@ -40,6 +40,7 @@ use crate::http_util::percent_encode_path;
 use crate::http_util::AsyncBody;
 use crate::http_util::HttpClient;
 use crate::http_util::IncomingAsyncBody;
+use crate::io_util::into_reader;
 use crate::ops::BytesRange;
 use crate::ops::OpRead;
 use crate::ops::OpStat;
@@ -49,6 +50,7 @@ use crate::path::normalize_root;
 use crate::Accessor;
 use crate::AccessorMetadata;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::ObjectMetadata;
 use crate::ObjectMode;
 use crate::Scheme;
@@ -58,6 +60,8 @@ use crate::Scheme;
 pub struct Builder {
     endpoint: Option<String>,
     root: Option<String>,
+
+    http_client: Option<HttpClient>,
 }
 
 impl Debug for Builder {
@@ -110,6 +114,15 @@ impl Builder {
         self
     }
 
+    /// Specify the http client that used by this service.
+    ///
+    /// A new client will be created if not set, see [`HttpClient`] for
+    /// sharing clients across services.
+    pub fn http_client(&mut self, client: HttpClient) -> &mut Self {
+        self.http_client = Some(client);
+        self
+    }
+
     /// Build a HTTP backend.
     pub fn build(&mut self) -> Result<impl Accessor> {
         debug!("backend build started: {:?}", &self);
@@ -127,7 +140,7 @@ impl Builder {
         let root = normalize_root(&self.root.take().unwrap_or_default());
         debug!("backend use root {}", root);
 
-        let client = HttpClient::new();
+        let client = self.http_client.take().unwrap_or_default();
 
         debug!("backend build finished: {:?}", &self);
         Ok(Backend {
@@ -168,12 +181,17 @@ impl Accessor for Backend {
     }
 
     async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        let s = self.read_stream(path, args).await?;
+        Ok(Box::new(into_reader(s)))
+    }
+
+    async fn read_stream(&self, path: &str, args: OpRead) -> Result<BytesStreamer> {
         let resp = self.http_get(path, args.offset(), args.size()).await?;
 
         let status = resp.status();
 
         match status {
-            StatusCode::OK | StatusCode::PARTIAL_CONTENT => Ok(resp.into_body().reader()),
+            StatusCode::OK | StatusCode::PARTIAL_CONTENT => Ok(resp.into_body().stream()),
             _ => {
                 let er = parse_error_response(resp).await?;
                 let err = parse_error(Operation::Read, path, er);
@@ -52,6 +52,7 @@ use crate::http_util::percent_encode_path;
 use crate::http_util::AsyncBody;
 use crate::http_util::HttpClient;
 use crate::http_util::IncomingAsyncBody;
+use crate::io_util::into_reader;
 use crate::ops::BytesRange;
 use crate::ops::OpList;
 use crate::ops::OpRead;
@@ -62,6 +63,7 @@ use crate::path::normalize_root;
 use crate::Accessor;
 use crate::AccessorMetadata;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::ObjectEntry;
 use crate::ObjectMetadata;
 use crate::ObjectMode;
@@ -73,6 +75,8 @@ use crate::Scheme;
 pub struct Builder {
     endpoint: Option<String>,
     root: Option<String>,
+
+    http_client: Option<HttpClient>,
 }
 
 impl Builder {
@@ -126,6 +130,15 @@ impl Builder {
         self
     }
 
+    /// Specify the http client that used by this service.
+    ///
+    /// A new client will be created if not set, see [`HttpClient`] for
+    /// sharing clients across services.
+    pub fn http_client(&mut self, client: HttpClient) -> &mut Self {
+        self.http_client = Some(client);
+        self
+    }
+
     /// Consume builder to build an ipfs backend.
     pub fn build(&mut self) -> Result<impl Accessor> {
         debug!("backend build started: {:?}", &self);
@@ -152,7 +165,7 @@ impl Builder {
         Ok(Backend {
             root,
             endpoint,
-            client: HttpClient::new(),
+            client: self.http_client.take().unwrap_or_default(),
         })
     }
 }
@@ -187,12 +200,17 @@ impl Accessor for Backend {
     }
 
     async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        let s = self.read_stream(path, args).await?;
+        Ok(Box::new(into_reader(s)))
+    }
+
+    async fn read_stream(&self, path: &str, args: OpRead) -> Result<BytesStreamer> {
         let resp = self.ipfs_get(path, args.offset(), args.size()).await?;
 
         let status = resp.status();
 
         match status {
-            StatusCode::OK | StatusCode::PARTIAL_CONTENT => Ok(resp.into_body().reader()),
+            StatusCode::OK | StatusCode::PARTIAL_CONTENT => Ok(resp.into_body().stream()),
             _ => {
                 let er = parse_error_response(resp).await?;
                 let err = parse_error(Operation::Read, path, er);
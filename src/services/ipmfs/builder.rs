@@ -26,6 +26,8 @@ use crate::Accessor;
 pub struct Builder {
     root: Option<String>,
     endpoint: Option<String>,
+
+    http_client: Option<HttpClient>,
 }
 
 impl Builder {
@@ -67,6 +69,15 @@ impl Builder {
         self
     }
 
+    /// Specify the http client that used by this service.
+    ///
+    /// A new client will be created if not set, see [`HttpClient`] for
+    /// sharing clients across services.
+    pub fn http_client(&mut self, client: HttpClient) -> &mut Self {
+        self.http_client = Some(client);
+        self
+    }
+
     /// Consume builder to build an ipfs::Backend.
     pub fn build(&mut self) -> Result<impl Accessor> {
         let root = normalize_root(&self.root.take().unwrap_or_default());
@@ -77,7 +88,7 @@ impl Builder {
             .clone()
             .unwrap_or_else(|| "http://localhost:5001".to_string());
 
-        let client = HttpClient::new();
+        let client = self.http_client.take().unwrap_or_default();
 
         debug!("backend build finished: {:?}", &self);
         Ok(Backend::new(root, client, endpoint))
@@ -20,6 +20,7 @@ use std::sync::Arc;
 
 use anyhow::anyhow;
 use async_trait::async_trait;
+use http::header::CONTENT_ENCODING;
 use http::header::CONTENT_LENGTH;
 use http::header::CONTENT_TYPE;
 use http::Request;
@@ -46,6 +47,8 @@ use crate::http_util::percent_encode_path;
 use crate::http_util::AsyncBody;
 use crate::http_util::HttpClient;
 use crate::http_util::IncomingAsyncBody;
+use crate::io_util::into_reader;
+use crate::io_util::into_stream;
 use crate::object::ObjectPageStreamer;
 use crate::ops::BytesRange;
 use crate::ops::OpCreate;
@@ -61,6 +64,7 @@ use crate::services::obs::dir_stream::DirStream;
 use crate::Accessor;
 use crate::AccessorMetadata;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::ObjectMetadata;
 use crate::ObjectMode;
 use crate::ObjectStreamer;
@@ -74,6 +78,8 @@ pub struct Builder {
     access_key_id: Option<String>,
     secret_access_key: Option<String>,
     bucket: Option<String>,
+
+    http_client: Option<HttpClient>,
 }
 
 impl Debug for Builder {
@@ -166,6 +172,15 @@ impl Builder {
         self
     }
 
+    /// Specify the http client that used by this service.
+    ///
+    /// A new client will be created if not set, see [`HttpClient`] for
+    /// sharing clients across services.
+    pub fn http_client(&mut self, client: HttpClient) -> &mut Self {
+        self.http_client = Some(client);
+        self
+    }
+
     /// Consume builder to build an OBS backend.
     pub fn build(&mut self) -> Result<impl Accessor> {
         debug!("backend build started: {:?}", &self);
@@ -216,7 +231,7 @@ impl Builder {
             ("endpoint".to_string(), endpoint.to_string()),
         ]);
 
-        let client = HttpClient::new();
+        let client = self.http_client.take().unwrap_or_default();
 
         let mut signer_builder = HuaweicloudObsSigner::builder();
         if let (Some(access_key_id), Some(secret_access_key)) =
@@ -280,7 +295,7 @@ impl Accessor for Backend {
     }
 
     async fn create(&self, path: &str, _: OpCreate) -> Result<()> {
-        let mut req = self.obs_put_object_request(path, Some(0), None, AsyncBody::Empty)?;
+        let mut req = self.obs_put_object_request(path, Some(0), None, None, AsyncBody::Empty)?;
 
         self.signer
             .sign(&mut req)
@@ -311,6 +326,11 @@ impl Accessor for Backend {
     }
 
     async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        let s = self.read_stream(path, args).await?;
+        Ok(Box::new(into_reader(s)))
+    }
+
+    async fn read_stream(&self, path: &str, args: OpRead) -> Result<BytesStreamer> {
         let resp = self
             .obs_get_object(path, args.offset(), args.size())
             .await?;
@@ -318,7 +338,7 @@ impl Accessor for Backend {
         let status = resp.status();
 
         match status {
-            StatusCode::OK | StatusCode::PARTIAL_CONTENT => Ok(resp.into_body().reader()),
+            StatusCode::OK | StatusCode::PARTIAL_CONTENT => Ok(resp.into_body().stream()),
             _ => {
                 let er = parse_error_response(resp).await?;
                 let err = parse_error(Operation::Read, path, er);
@@ -328,11 +348,17 @@ impl Accessor for Backend {
     }
 
     async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<u64> {
+        self.write_stream(path, args, Box::new(into_stream(r, 16 * 1024)))
+            .await
+    }
+
+    async fn write_stream(&self, path: &str, args: OpWrite, s: BytesStreamer) -> Result<u64> {
         let mut req = self.obs_put_object_request(
             path,
             Some(args.size()),
             args.content_type(),
-            AsyncBody::Reader(r),
+            args.content_encoding(),
+            AsyncBody::Stream(s),
         )?;
 
         self.signer
@@ -436,12 +462,11 @@ impl Accessor for Backend {
         }
     }
 
-    async fn list(&self, path: &str, _: OpList) -> Result<ObjectStreamer> {
-        Ok(Box::new(ObjectPageStreamer::new(DirStream::new(
-            Arc::new(self.clone()),
-            &self.root,
-            path,
-        ))))
+    async fn list(&self, path: &str, args: OpList) -> Result<ObjectStreamer> {
+        Ok(Box::new(
+            ObjectPageStreamer::new(DirStream::new(Arc::new(self.clone()), &self.root, path))
+                .with_prefetch(args.prefetch()),
+        ))
     }
 }
 
@@ -484,6 +509,7 @@ impl Backend {
         path: &str,
         size: Option<u64>,
         content_type: Option<&str>,
+        content_encoding: Option<&str>,
         body: AsyncBody,
     ) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
@@ -500,6 +526,10 @@ impl Backend {
             req = req.header(CONTENT_TYPE, mime)
         }
 
+        if let Some(encoding) = content_encoding {
+            req = req.header(CONTENT_ENCODING, encoding)
+        }
+
         let req = req
             .body(body)
             .map_err(|e| new_request_build_error(Operation::Write, path, e))?;
@@ -19,6 +19,7 @@ use std::io::Result;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use http::header::CONTENT_ENCODING;
 use http::header::CONTENT_LENGTH;
 use http::header::CONTENT_TYPE;
 use http::header::HOST;
@@ -51,6 +52,8 @@ use crate::http_util::percent_encode_path;
 use crate::http_util::AsyncBody;
 use crate::http_util::HttpClient;
 use crate::http_util::IncomingAsyncBody;
+use crate::io_util::into_reader;
+use crate::io_util::into_stream;
 use crate::object::ObjectPageStreamer;
 use crate::ops::BytesRange;
 use crate::ops::OpCreate;
@@ -65,6 +68,7 @@ use crate::path::normalize_root;
 use crate::Accessor;
 use crate::AccessorMetadata;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::ObjectMetadata;
 use crate::ObjectMode;
 use crate::ObjectStreamer;
@@ -83,6 +87,8 @@ pub struct Builder {
     access_key_secret: Option<String>,
 
     allow_anonymous: bool,
+
+    http_client: Option<HttpClient>,
 }
 
 impl Debug for Builder {
@@ -184,6 +190,15 @@ impl Builder {
         self
     }
 
+    /// Specify the http client that used by this service.
+    ///
+    /// A new client will be created if not set, see [`HttpClient`] for
+    /// sharing clients across services.
+    pub fn http_client(&mut self, client: HttpClient) -> &mut Self {
+        self.http_client = Some(client);
+        self
+    }
+
     /// finish building
     pub fn build(&self) -> Result<impl Accessor> {
         debug!("backend build started: {:?}", &self);
@@ -256,7 +271,7 @@ impl Builder {
             root,
             endpoint,
             host,
-            client: HttpClient::new(),
+            client: self.http_client.clone().unwrap_or_default(),
             bucket: self.bucket.clone(),
             signer: Arc::new(signer),
         })
@@ -295,6 +310,7 @@ impl Backend {
         path: &str,
         size: Option<u64>,
         content_type: Option<&str>,
+        content_encoding: Option<&str>,
         body: AsyncBody,
     ) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
@@ -311,6 +327,10 @@ impl Backend {
             req = req.header(CONTENT_TYPE, mime);
         }
 
+        if let Some(encoding) = content_encoding {
+            req = req.header(CONTENT_ENCODING, encoding);
+        }
+
         let req = req
             .body(body)
             .map_err(|e| new_request_build_error(Operation::Write, path, e))?;
@@ -430,9 +450,11 @@ impl Backend {
         path: &str,
         size: Option<u64>,
         content_type: Option<&str>,
+        content_encoding: Option<&str>,
         body: AsyncBody,
     ) -> Result<Response<IncomingAsyncBody>> {
-        let mut req = self.oss_put_object_request(path, size, content_type, body)?;
+        let mut req =
+            self.oss_put_object_request(path, size, content_type, content_encoding, body)?;
 
         self.signer
             .sign(&mut req)
@@ -486,7 +508,7 @@ impl Accessor for Backend {
 
     async fn create(&self, path: &str, _: OpCreate) -> Result<()> {
         let resp = self
-            .oss_put_object(path, None, None, AsyncBody::Empty)
+            .oss_put_object(path, None, None, None, AsyncBody::Empty)
             .await?;
         let status = resp.status();
 
@@ -507,6 +529,11 @@ impl Accessor for Backend {
     }
 
     async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        let s = self.read_stream(path, args).await?;
+        Ok(Box::new(into_reader(s)))
+    }
+
+    async fn read_stream(&self, path: &str, args: OpRead) -> Result<BytesStreamer> {
         let resp = self
             .oss_get_object(path, args.offset(), args.size())
             .await?;
@@ -514,7 +541,7 @@ impl Accessor for Backend {
         let status = resp.status();
 
         match status {
-            StatusCode::OK | StatusCode::PARTIAL_CONTENT => Ok(resp.into_body().reader()),
+            StatusCode::OK | StatusCode::PARTIAL_CONTENT => Ok(resp.into_body().stream()),
             _ => {
                 let er = parse_error_response(resp).await?;
                 let err = parse_error(Operation::Read, path, er);
@@ -524,12 +551,18 @@ impl Accessor for Backend {
     }
 
     async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<u64> {
+        self.write_stream(path, args, Box::new(into_stream(r, 16 * 1024)))
+            .await
+    }
+
+    async fn write_stream(&self, path: &str, args: OpWrite, s: BytesStreamer) -> Result<u64> {
         let resp = self
             .oss_put_object(
                 path,
                 Some(args.size()),
                 args.content_type(),
-                AsyncBody::Reader(r),
+                args.content_encoding(),
+                AsyncBody::Stream(s),
             )
             .await?;
 
@@ -640,11 +673,10 @@ impl Accessor for Backend {
         }
     }
 
-    async fn list(&self, path: &str, _: OpList) -> Result<ObjectStreamer> {
-        Ok(Box::new(ObjectPageStreamer::new(DirStream::new(
-            Arc::new(self.clone()),
-            &self.root,
-            path,
-        ))))
+    async fn list(&self, path: &str, args: OpList) -> Result<ObjectStreamer> {
+        Ok(Box::new(
+            ObjectPageStreamer::new(DirStream::new(Arc::new(self.clone()), &self.root, path))
+                .with_prefetch(args.prefetch()),
+        ))
     }
 }
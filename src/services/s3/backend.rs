@@ -24,6 +24,7 @@ use async_trait::async_trait;
 use bytes::Buf;
 use bytes::Bytes;
 use http::header::HeaderName;
+use http::header::CONTENT_ENCODING;
 use http::header::CONTENT_LENGTH;
 use http::header::CONTENT_TYPE;
 use http::HeaderValue;
@@ -58,6 +59,8 @@ use crate::http_util::AsyncBody;
 use crate::http_util::Body;
 use crate::http_util::HttpClient;
 use crate::http_util::IncomingAsyncBody;
+use crate::io_util::into_reader;
+use crate::io_util::into_stream;
 use crate::object::ObjectPageStreamer;
 use crate::ops::BytesRange;
 use crate::ops::OpAbortMultipart;
@@ -79,6 +82,7 @@ use crate::path::normalize_root;
 use crate::Accessor;
 use crate::AccessorMetadata;
 use crate::BytesReader;
+use crate::BytesStreamer;
 use crate::ObjectMetadata;
 use crate::ObjectMode;
 use crate::ObjectPart;
@@ -132,6 +136,8 @@ pub struct Builder {
 
     disable_credential_loader: bool,
     enable_virtual_host_style: bool,
+
+    http_client: Option<HttpClient>,
 }
 
 impl Debug for Builder {
@@ -604,6 +610,15 @@ impl Builder {
         }
     }
 
+    /// Specify the http client that used by this service.
+    ///
+    /// A new client will be created if not set, see [`HttpClient`] for
+    /// sharing clients across services.
+    pub fn http_client(&mut self, client: HttpClient) -> &mut Self {
+        self.http_client = Some(client);
+        self
+    }
+
     /// Finish the build process and create a new accessor.
     pub fn build(&mut self) -> Result<impl Accessor> {
         debug!("backend build started: {:?}", &self);
@@ -693,7 +708,7 @@ impl Builder {
                 })?),
             };
 
-        let client = HttpClient::new();
+        let client = self.http_client.take().unwrap_or_default();
 
         let (mut endpoint, region) = self.detect_region(&client, bucket, &context)?;
         // Construct endpoint which contains bucket name.
@@ -850,7 +865,7 @@ impl Accessor for Backend {
     }
 
     async fn create(&self, path: &str, _: OpCreate) -> Result<()> {
-        let mut req = self.put_object_request(path, Some(0), None, AsyncBody::Empty)?;
+        let mut req = self.put_object_request(path, Some(0), None, None, AsyncBody::Empty)?;
 
         self.signer
             .sign(&mut req)
@@ -881,12 +896,17 @@ impl Accessor for Backend {
     }
 
     async fn read(&self, path: &str, args: OpRead) -> Result<BytesReader> {
+        let s = self.read_stream(path, args).await?;
+        Ok(Box::new(into_reader(s)))
+    }
+
+    async fn read_stream(&self, path: &str, args: OpRead) -> Result<BytesStreamer> {
         let resp = self.get_object(path, args.offset(), args.size()).await?;
 
         let status = resp.status();
 
         match status {
-            StatusCode::OK | StatusCode::PARTIAL_CONTENT => Ok(resp.into_body().reader()),
+            StatusCode::OK | StatusCode::PARTIAL_CONTENT => Ok(resp.into_body().stream()),
             _ => {
                 let er = parse_error_response(resp).await?;
                 let err = parse_error(Operation::Read, path, er);
@@ -896,11 +916,17 @@ impl Accessor for Backend {
     }
 
     async fn write(&self, path: &str, args: OpWrite, r: BytesReader) -> Result<u64> {
+        self.write_stream(path, args, Box::new(into_stream(r, 16 * 1024)))
+            .await
+    }
+
+    async fn write_stream(&self, path: &str, args: OpWrite, s: BytesStreamer) -> Result<u64> {
         let mut req = self.put_object_request(
             path,
             Some(args.size()),
             args.content_type(),
-            AsyncBody::Reader(r),
+            args.content_encoding(),
+            AsyncBody::Stream(s),
         )?;
 
         self.signer
@@ -1003,12 +1029,11 @@ impl Accessor for Backend {
         }
     }
 
-    async fn list(&self, path: &str, _: OpList) -> Result<ObjectStreamer> {
-        Ok(Box::new(ObjectPageStreamer::new(DirStream::new(
-            Arc::new(self.clone()),
-            &self.root,
-            path,
-        ))))
+    async fn list(&self, path: &str, args: OpList) -> Result<ObjectStreamer> {
+        Ok(Box::new(
+            ObjectPageStreamer::new(DirStream::new(Arc::new(self.clone()), &self.root, path))
+                .with_prefetch(args.prefetch()),
+        ))
     }
 
     fn presign(&self, path: &str, args: OpPresign) -> Result<PresignedRequest> {
@@ -1016,7 +1041,7 @@ impl Accessor for Backend {
         let mut req = match args.operation() {
             PresignOperation::Read(v) => self.get_object_request(path, v.offset(), v.size())?,
             PresignOperation::Write(_) => {
-                self.put_object_request(path, None, None, AsyncBody::Empty)?
+                self.put_object_request(path, None, None, None, AsyncBody::Empty)?
             }
             PresignOperation::WriteMultipart(v) => self.s3_upload_part_request(
                 path,
@@ -1227,6 +1252,7 @@ impl Backend {
         path: &str,
         size: Option<u64>,
         content_type: Option<&str>,
+        content_encoding: Option<&str>,
         body: AsyncBody,
     ) -> Result<Request<AsyncBody>> {
         let p = build_abs_path(&self.root, path);
@@ -1243,6 +1269,10 @@ impl Backend {
             req = req.header(CONTENT_TYPE, mime)
         }
 
+        if let Some(encoding) = content_encoding {
+            req = req.header(CONTENT_ENCODING, encoding)
+        }
+
         // Set SSE headers.
         req = self.insert_sse_headers(req, true);
 
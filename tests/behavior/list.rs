@@ -19,6 +19,7 @@ use futures::stream::FuturesUnordered;
 use futures::StreamExt;
 use futures::TryStreamExt;
 use log::debug;
+use opendal::ops::OpList;
 use opendal::ObjectMode;
 use opendal::Operator;
 
@@ -155,6 +156,18 @@ pub async fn test_list_rich_dir(op: Operator) -> Result<()> {
 
     assert_eq!(actual, expected);
 
+    // Listing with prefetch should return the same objects.
+    let mut actual: Vec<String> = op
+        .object("test_list_rich_dir/")
+        .list_with(OpList::new().with_prefetch(2))
+        .await?
+        .map_ok(|o| o.path().to_string())
+        .try_collect()
+        .await?;
+    actual.sort_unstable();
+
+    assert_eq!(actual, expected);
+
     op.batch().remove_all("test_list_rich_dir/").await?;
     Ok(())
 }
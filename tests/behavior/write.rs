@@ -15,11 +15,16 @@
 use std::io;
 use std::io::Result;
 
+use bytes::Bytes;
+use futures::TryStreamExt;
 use log::debug;
 use opendal::ObjectMode;
 use opendal::Operator;
 use sha2::Digest;
 use sha2::Sha256;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncSeekExt;
+use tokio::io::AsyncWriteExt;
 
 use super::utils::*;
 
@@ -85,7 +90,16 @@ macro_rules! behavior_write_tests {
                 test_read_decompress_gzip,
                 #[cfg(feature = "compress")]
                 test_read_decompress_zstd,
+                #[cfg(feature = "compress")]
+                test_compress_write_gzip,
+                #[cfg(feature = "compress")]
+                test_compress_write_zstd,
                 test_read_with_special_chars,
+                test_tokio_seekable_read,
+                test_writer,
+                test_tokio_writer,
+                test_stream,
+                test_read_ranges,
                 test_delete,
                 test_delete_empty_dir,
                 test_delete_with_special_chars,
@@ -404,6 +418,174 @@ pub async fn test_read_range(op: Operator) -> Result<()> {
     Ok(())
 }
 
+/// Read range with tokio seekable reader should match.
+pub async fn test_tokio_seekable_read(op: Operator) -> Result<()> {
+    let path = uuid::Uuid::new_v4().to_string();
+    debug!("Generate a random file: {}", &path);
+    let (content, size) = gen_bytes();
+    let (offset, length) = gen_offset_length(size as usize);
+
+    op.object(&path)
+        .write(content.clone())
+        .await
+        .expect("write must succeed");
+
+    let mut r = op.object(&path).tokio_seekable_reader(..);
+    let n = r.seek(io::SeekFrom::Start(offset)).await?;
+    assert_eq!(n, offset, "seek position");
+    let mut bs = vec![0; length as usize];
+    r.read_exact(&mut bs).await?;
+    assert_eq!(
+        format!("{:x}", Sha256::digest(&bs)),
+        format!(
+            "{:x}",
+            Sha256::digest(&content[offset as usize..(offset + length) as usize])
+        ),
+        "read content"
+    );
+
+    op.object(&path)
+        .delete()
+        .await
+        .expect("delete must succeed");
+    Ok(())
+}
+
+/// Write file with streaming writer should succeed.
+pub async fn test_writer(op: Operator) -> Result<()> {
+    let path = uuid::Uuid::new_v4().to_string();
+    debug!("Generate a random file: {}", &path);
+    let (content, size) = gen_bytes();
+
+    let mut w = op.object(&path).writer(size as u64)?;
+    for chunk in content.chunks(4096) {
+        futures::AsyncWriteExt::write_all(&mut w, chunk).await?;
+    }
+    futures::AsyncWriteExt::close(&mut w).await?;
+
+    let bs = op.object(&path).read().await?;
+    assert_eq!(bs.len(), size, "read size");
+    assert_eq!(
+        format!("{:x}", Sha256::digest(&bs)),
+        format!("{:x}", Sha256::digest(&content)),
+        "read content"
+    );
+
+    op.object(&path)
+        .delete()
+        .await
+        .expect("delete must succeed");
+    Ok(())
+}
+
+/// Copy data into tokio writer should succeed.
+pub async fn test_tokio_writer(op: Operator) -> Result<()> {
+    let path = uuid::Uuid::new_v4().to_string();
+    debug!("Generate a random file: {}", &path);
+    let (content, size) = gen_bytes();
+
+    let mut w = op.object(&path).tokio_writer(size as u64)?;
+    let n = tokio::io::copy(&mut content.as_slice(), &mut w).await?;
+    assert_eq!(n, size as u64, "copy size");
+    w.shutdown().await?;
+
+    let mut r = op.object(&path).tokio_reader().await?;
+    let mut bs = Vec::new();
+    r.read_to_end(&mut bs).await?;
+    assert_eq!(
+        format!("{:x}", Sha256::digest(&bs)),
+        format!("{:x}", Sha256::digest(&content)),
+        "read content"
+    );
+
+    op.object(&path)
+        .delete()
+        .await
+        .expect("delete must succeed");
+    Ok(())
+}
+
+/// Write and read with bytes stream should succeed.
+pub async fn test_stream(op: Operator) -> Result<()> {
+    let path = uuid::Uuid::new_v4().to_string();
+    debug!("Generate a random file: {}", &path);
+    let (content, size) = gen_bytes();
+    let (offset, length) = gen_offset_length(size as usize);
+
+    let chunks = content
+        .chunks(64 * 1024)
+        .map(|v| Ok(Bytes::copy_from_slice(v)))
+        .collect::<Vec<_>>();
+    op.object(&path)
+        .write_stream(size as u64, futures::stream::iter(chunks))
+        .await?;
+
+    let bs: Vec<Bytes> = op
+        .object(&path)
+        .range_stream(offset..offset + length)
+        .await?
+        .try_collect()
+        .await?;
+    let bs = bs.concat();
+    assert_eq!(bs.len() as u64, length, "read size");
+    assert_eq!(
+        format!("{:x}", Sha256::digest(&bs)),
+        format!(
+            "{:x}",
+            Sha256::digest(&content[offset as usize..(offset + length) as usize])
+        ),
+        "read content"
+    );
+
+    op.object(&path)
+        .delete()
+        .await
+        .expect("delete must succeed");
+    Ok(())
+}
+
+/// Read multiple ranges should match.
+pub async fn test_read_ranges(op: Operator) -> Result<()> {
+    let path = uuid::Uuid::new_v4().to_string();
+    debug!("Generate a random file: {}", &path);
+    let (content, size) = gen_bytes();
+
+    op.object(&path)
+        .write(content.clone())
+        .await
+        .expect("write must succeed");
+
+    let mut ranges = Vec::new();
+    for _ in 0..8 {
+        let (offset, length) = gen_offset_length(size as usize);
+        ranges.push(offset..offset + length);
+    }
+    // Empty range and range beyond the end should be truncated.
+    ranges.push(0..0);
+    ranges.push(size as u64 - 1..size as u64 + 1024);
+
+    let bs = op.object(&path).read_ranges(&ranges).await?;
+    assert_eq!(bs.len(), ranges.len(), "ranges count");
+    for (r, bs) in ranges.iter().zip(bs) {
+        let end = r.end.min(size as u64);
+        assert_eq!(bs.len() as u64, end - r.start, "read size of {r:?}");
+        assert_eq!(
+            format!("{:x}", Sha256::digest(&bs)),
+            format!(
+                "{:x}",
+                Sha256::digest(&content[r.start as usize..end as usize])
+            ),
+            "read content of {r:?}"
+        );
+    }
+
+    op.object(&path)
+        .delete()
+        .await
+        .expect("delete must succeed");
+    Ok(())
+}
+
 /// Read not exist file should return NotFound
 pub async fn test_read_not_exist(op: Operator) -> Result<()> {
     let path = uuid::Uuid::new_v4().to_string();
@@ -504,6 +686,66 @@ pub async fn test_read_decompress_zstd(op: Operator) -> Result<()> {
     Ok(())
 }
 
+// Write a gzip file with compress write and read it back.
+#[cfg(feature = "compress")]
+pub async fn test_compress_write_gzip(op: Operator) -> Result<()> {
+    let path = format!("{}.gz", uuid::Uuid::new_v4());
+    debug!("Generate a random file: {}", &path);
+    let (content, size) = gen_bytes();
+
+    op.object(&path)
+        .compress_write(futures::io::Cursor::new(content.clone()))
+        .await?;
+
+    let bs = op
+        .object(&path)
+        .decompress_read()
+        .await?
+        .expect("decompress read must succeed");
+    assert_eq!(bs.len(), size, "read size");
+    assert_eq!(
+        format!("{:x}", Sha256::digest(&bs)),
+        format!("{:x}", Sha256::digest(&content)),
+        "read content"
+    );
+
+    op.object(&path)
+        .delete()
+        .await
+        .expect("delete must succeed");
+    Ok(())
+}
+
+// Write a zstd file with compress write and read it back.
+#[cfg(feature = "compress")]
+pub async fn test_compress_write_zstd(op: Operator) -> Result<()> {
+    let path = format!("{}.zst", uuid::Uuid::new_v4());
+    debug!("Generate a random file: {}", &path);
+    let (content, size) = gen_bytes();
+
+    op.object(&path)
+        .compress_write(futures::io::Cursor::new(content.clone()))
+        .await?;
+
+    let bs = op
+        .object(&path)
+        .decompress_read()
+        .await?
+        .expect("decompress read must succeed");
+    assert_eq!(bs.len(), size, "read size");
+    assert_eq!(
+        format!("{:x}", Sha256::digest(&bs)),
+        format!("{:x}", Sha256::digest(&content)),
+        "read content"
+    );
+
+    op.object(&path)
+        .delete()
+        .await
+        .expect("delete must succeed");
+    Ok(())
+}
+
 /// Read file with special chars should succeed.
 pub async fn test_read_with_special_chars(op: Operator) -> Result<()> {
     let path = format!("{} !@#$%^&*()_+-=;'><,?.txt", uuid::Uuid::new_v4());